@@ -1,12 +1,12 @@
-use crate::types::EnvironmentStack;
+use crate::types::{BuiltinFunction, EnvironmentStack};
 use crate::utils::{identifier_to_c_string, identifier_to_string, string_to_c_string};
 use crate::visitor::{PreOrderVisitor, PreOrderVisitorResponse};
 use dishsoap_parser::ast::*;
 use itertools::Itertools;
 use llvm_sys::core::*;
 pub use llvm_sys::prelude::*;
-use llvm_sys::LLVMIntPredicate;
-use std::collections::HashMap;
+use llvm_sys::{LLVMIntPredicate, LLVMRealPredicate};
+use std::collections::{HashMap, HashSet};
 use std::mem::forget;
 use std::rc::Rc;
 
@@ -68,12 +68,36 @@ use std::rc::Rc;
 //     expression:
 // }
 
+/// An instantiation of a generic function that has been called but not lowered yet.
+struct Instantiation {
+    /// The name of the instantiation, mangled with its type arguments.
+    name: String,
+    function_declaration: Rc<FunctionDeclaration<TypedNodeCommonFields>>,
+    type_arguments: HashMap<String, Type>,
+}
+
+/// The basic blocks that `continue` and `break` statements jump to inside a loop.
+struct LoopTargets {
+    continue_block: LLVMBasicBlockRef,
+    break_block: LLVMBasicBlockRef,
+}
+
 pub struct Builder<'a> {
     context: &'a LLVMContextRef,
     module: &'a LLVMModuleRef,
     builder: &'a LLVMBuilderRef,
     environment_stack: &'a mut EnvironmentStack,
     variables: HashMap<String, LLVMValueRef>,
+    /// The generic functions in the program, by name. These are only lowered once they are
+    /// instantiated.
+    generic_functions: HashMap<String, Rc<FunctionDeclaration<TypedNodeCommonFields>>>,
+    /// The names of all the functions declared in the program.
+    declared_functions: HashSet<String>,
+    pending_instantiations: Vec<Instantiation>,
+    /// The type arguments of the instantiation of a generic function being lowered.
+    type_arguments: HashMap<String, Type>,
+    /// The targets of the loops enclosing the code being lowered, innermost last.
+    loop_targets: Vec<LoopTargets>,
 }
 
 impl<'a> Builder<'a> {
@@ -89,6 +113,11 @@ impl<'a> Builder<'a> {
             builder,
             environment_stack,
             variables: HashMap::new(),
+            generic_functions: HashMap::new(),
+            declared_functions: HashSet::new(),
+            pending_instantiations: vec![],
+            type_arguments: HashMap::new(),
+            loop_targets: vec![],
         }
     }
 
@@ -137,38 +166,76 @@ impl<'a> Builder<'a> {
 
     pub fn lower_type(&mut self, r#type: &Type) -> LLVMTypeRef {
         match r#type {
-            Type::UnitType | Type::BoolType => unsafe { LLVMInt1Type() },
-            Type::I64Type => unsafe { LLVMInt64Type() },
+            // Values of the never type are never produced, so any type can stand in for them.
+            Type::UnitType | Type::BoolType | Type::NeverType => unsafe { LLVMInt1Type() },
+            Type::I8Type | Type::U8Type => unsafe { LLVMInt8Type() },
+            Type::I16Type => unsafe { LLVMInt16Type() },
+            Type::I32Type | Type::U32Type => unsafe { LLVMInt32Type() },
+            Type::I64Type | Type::U64Type => unsafe { LLVMInt64Type() },
+            Type::F64Type => unsafe { LLVMDoubleType() },
             Type::RecordType(t) => self.lower_record_type(t.as_ref(), true),
             Type::FunctionType(t) => self.lower_function_type(t.as_ref()),
-            Type::TypeReference(_) => unreachable!(),
+            Type::TypeParameter(p) => {
+                let type_argument = self.type_arguments.get(&p.name).unwrap().clone();
+                self.lower_type(&type_argument)
+            }
+            Type::TypeReference(_) | Type::ErrorType => unreachable!(),
         }
     }
 
+    pub fn lower_unit_literal(&self) -> LLVMValueRef {
+        unsafe { LLVMConstInt(LLVMInt1Type(), 0, false.into()) }
+    }
+
     pub fn lower_boolean_literal(
         &self,
         boolean_literal: &BooleanLiteral<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
-        match boolean_literal {
-            BooleanLiteral {
-                common_fields: _,
-                value,
-            } => unsafe { LLVMConstInt(LLVMInt1Type(), *value as u64, false.into()) },
-        }
+        let BooleanLiteral {
+            common_fields: _,
+            value,
+        } = boolean_literal;
+        unsafe { LLVMConstInt(LLVMInt1Type(), *value as u64, false.into()) }
     }
 
     pub fn lower_integer_literal(
-        &self,
+        &mut self,
         integer_literal: &IntegerLiteral<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
-        match integer_literal {
-            IntegerLiteral {
-                common_fields: _,
-                value,
-            } => unsafe { LLVMConstInt(LLVMInt64Type(), *value as u64, 0) },
+        let IntegerLiteral {
+            common_fields,
+            value,
+            suffix: _,
+        } = integer_literal;
+        self.lower_integer_constant(&common_fields.r#type, (*value).into())
+    }
+
+    /// Lowers `value` as a constant of the integer type `r#type`. The type checker has already
+    /// made sure that `value` fits in `r#type`.
+    fn lower_integer_constant(&mut self, r#type: &Type, value: i128) -> LLVMValueRef {
+        let integer_type = self.lower_type(r#type);
+        // The low 64 bits of `value` are its two's complement representation, which LLVM
+        // truncates to the width of the type.
+        unsafe {
+            LLVMConstInt(
+                integer_type,
+                value as u64,
+                r#type.is_signed_integer().into(),
+            )
         }
     }
 
+    pub fn lower_float_literal(
+        &self,
+        float_literal: &FloatLiteral<TypedNodeCommonFields>,
+    ) -> LLVMValueRef {
+        let FloatLiteral {
+            common_fields: _,
+            value,
+        } = float_literal;
+        unsafe { LLVMConstReal(LLVMDoubleType(), *value) }
+    }
+
     pub fn lower_object_literal(
         &mut self,
         object_literal: &ObjectLiteral<TypedNodeCommonFields>,
@@ -179,16 +246,27 @@ impl<'a> Builder<'a> {
                 _ => unreachable!(),
             };
             let llvm_object_type = self.lower_record_type(object_type, false);
+            let keys = object_literal
+                .fields
+                .keys()
+                .sorted()
+                .collect::<Vec<&String>>();
+            let field_values = match self
+                .lower_operands(keys.iter().map(|k| &object_literal.fields[*k]))
+            {
+                Some(field_values) => field_values,
+                // A field returns or jumps elsewhere, so the object is never created.
+                None => return LLVMGetUndef(self.lower_type(&object_literal.common_fields.r#type)),
+            };
             let object_pointer = LLVMBuildMalloc(
                 *self.builder,
                 llvm_object_type,
                 string_to_c_string("object_literal_malloc_temp".to_owned()).as_ptr(),
             );
-            for (index, key) in object_literal.fields.keys().sorted().enumerate() {
-                let field = object_literal.fields.get(&*key).unwrap();
+            for (index, field_value) in field_values.into_iter().enumerate() {
                 LLVMBuildStore(
                     *self.builder,
-                    self.lower_expression(field),
+                    field_value,
                     LLVMBuildStructGEP2(
                         *self.builder,
                         llvm_object_type,
@@ -208,18 +286,17 @@ impl<'a> Builder<'a> {
         &mut self,
         variable_reference: &VariableReference<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
-        match variable_reference {
-            VariableReference {
-                common_fields,
-                identifier,
-            } => unsafe {
-                LLVMBuildLoad2(
-                    *self.builder,
-                    self.lower_type(&common_fields.r#type),
-                    *self.variables.get(&identifier.name).unwrap(),
-                    identifier_to_c_string(identifier).as_ptr(),
-                )
-            },
+        let VariableReference {
+            common_fields,
+            identifier,
+        } = variable_reference;
+        unsafe {
+            LLVMBuildLoad2(
+                *self.builder,
+                self.lower_type(&common_fields.r#type),
+                *self.variables.get(&identifier.name).unwrap(),
+                identifier_to_c_string(identifier).as_ptr(),
+            )
         }
     }
 
@@ -227,252 +304,766 @@ impl<'a> Builder<'a> {
         &mut self,
         function_call: &FunctionCall<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
-        match function_call {
-            FunctionCall {
-                common_fields: _,
-                identifier,
-                arguments,
-            } => unsafe {
-                match identifier.name.as_str() {
-                    "__malloc" => {
-                        let malloc_function_type =
-                            LLVMFunctionType(LLVMInt64Type(), [LLVMInt64Type()].as_mut_ptr(), 1, 0);
-                        let mut malloc_function = LLVMGetNamedFunction(
+        let FunctionCall {
+            common_fields,
+            identifier,
+            type_arguments,
+            arguments,
+        } = function_call;
+        unsafe {
+            let mut arguments = match self.lower_operands(arguments) {
+                Some(arguments) => arguments,
+                // An argument returns or jumps elsewhere, so the function is never called.
+                None => return LLVMGetUndef(self.lower_type(&common_fields.r#type)),
+            };
+            // Like in the type checker's environment, a function declared in the program shadows the
+            // builtin function with the same name.
+            let builtin_function = BuiltinFunction::from_name(&identifier.name)
+                .filter(|_| !self.declared_functions.contains(&identifier.name));
+            match builtin_function {
+                Some(BuiltinFunction::Malloc) => {
+                    let malloc_function_type =
+                        LLVMFunctionType(LLVMInt64Type(), [LLVMInt64Type()].as_mut_ptr(), 1, 0);
+                    let mut malloc_function = LLVMGetNamedFunction(
+                        *self.module,
+                        string_to_c_string("malloc".to_owned()).as_ptr(),
+                    );
+                    if malloc_function.is_null() {
+                        malloc_function = LLVMAddFunction(
                             *self.module,
                             string_to_c_string("malloc".to_owned()).as_ptr(),
-                        );
-                        if malloc_function.is_null() {
-                            malloc_function = LLVMAddFunction(
-                                *self.module,
-                                string_to_c_string("malloc".to_owned()).as_ptr(),
-                                malloc_function_type,
-                            );
-                        }
-
-                        LLVMBuildCall2(
-                            *self.builder,
                             malloc_function_type,
-                            malloc_function,
-                            [LLVMBuildMul(
-                                *self.builder,
-                                self.lower_expression(&arguments[0]),
-                                LLVMSizeOf(LLVMInt64Type()),
-                                string_to_c_string("malloc_size_calculation_temp".to_owned())
-                                    .as_ptr(),
-                            )]
-                            .as_mut_ptr(),
-                            arguments.len() as u32,
-                            string_to_c_string("call_temp".to_owned()).as_ptr(),
-                        )
+                        );
                     }
-                    "__free" => {
-                        let free_function_type =
-                            LLVMFunctionType(LLVMVoidType(), [LLVMInt64Type()].as_mut_ptr(), 1, 0);
-                        let mut free_function = LLVMGetNamedFunction(
+
+                    LLVMBuildCall2(
+                        *self.builder,
+                        malloc_function_type,
+                        malloc_function,
+                        [LLVMBuildMul(
+                            *self.builder,
+                            arguments[0],
+                            LLVMSizeOf(LLVMInt64Type()),
+                            string_to_c_string("malloc_size_calculation_temp".to_owned()).as_ptr(),
+                        )]
+                        .as_mut_ptr(),
+                        arguments.len() as u32,
+                        string_to_c_string("call_temp".to_owned()).as_ptr(),
+                    )
+                }
+                Some(BuiltinFunction::Free) => {
+                    let free_function_type =
+                        LLVMFunctionType(LLVMVoidType(), [LLVMInt64Type()].as_mut_ptr(), 1, 0);
+                    let mut free_function = LLVMGetNamedFunction(
+                        *self.module,
+                        string_to_c_string("free".to_owned()).as_ptr(),
+                    );
+                    if free_function.is_null() {
+                        free_function = LLVMAddFunction(
                             *self.module,
                             string_to_c_string("free".to_owned()).as_ptr(),
-                        );
-                        if free_function.is_null() {
-                            free_function = LLVMAddFunction(
-                                *self.module,
-                                string_to_c_string("free".to_owned()).as_ptr(),
-                                free_function_type,
-                            );
-                        }
-
-                        LLVMBuildCall2(
-                            *self.builder,
                             free_function_type,
-                            free_function,
-                            [self.lower_expression(&arguments[0])].as_mut_ptr(),
-                            arguments.len() as u32,
-                            string_to_c_string("".to_owned()).as_ptr(),
                         );
-                        LLVMConstInt(LLVMInt1Type(), 1, 0)
                     }
-                    "__memMove" => {
-                        LLVMBuildMemMove(
-                            *self.builder,
-                            self.lower_expression(&arguments[0]),
-                            0,
-                            self.lower_expression(&arguments[1]),
-                            0,
-                            LLVMBuildMul(
-                                *self.builder,
-                                self.lower_expression(&arguments[2]),
-                                LLVMSizeOf(LLVMInt64Type()),
-                                string_to_c_string("malloc_size_calculation_temp".to_owned())
-                                    .as_ptr(),
-                            ),
-                        );
 
-                        LLVMConstInt(LLVMInt1Type(), 1, 0)
-                    }
-                    "__memStore" => {
-                        LLVMBuildStore(
+                    LLVMBuildCall2(
+                        *self.builder,
+                        free_function_type,
+                        free_function,
+                        [arguments[0]].as_mut_ptr(),
+                        arguments.len() as u32,
+                        string_to_c_string("".to_owned()).as_ptr(),
+                    );
+                    LLVMConstInt(LLVMInt1Type(), 1, 0)
+                }
+                Some(BuiltinFunction::MemMove) => {
+                    LLVMBuildMemMove(
+                        *self.builder,
+                        arguments[0],
+                        0,
+                        arguments[1],
+                        0,
+                        LLVMBuildMul(
                             *self.builder,
-                            self.lower_expression(&arguments[2]),
-                            LLVMBuildGEP2(
-                                *self.builder,
-                                LLVMInt64Type(),
-                                LLVMBuildIntToPtr(
-                                    *self.builder,
-                                    self.lower_expression(&arguments[0]),
-                                    LLVMPointerType(LLVMInt64Type(), 0),
-                                    string_to_c_string("memStore_cast_temp".to_owned()).as_ptr(),
-                                ),
-                                [self.lower_expression(&arguments[1])].as_mut_ptr(),
-                                1,
-                                string_to_c_string("memStore_index_temp".to_owned()).as_ptr(),
-                            ),
-                        );
+                            arguments[2],
+                            LLVMSizeOf(LLVMInt64Type()),
+                            string_to_c_string("malloc_size_calculation_temp".to_owned()).as_ptr(),
+                        ),
+                    );
 
-                        LLVMConstInt(LLVMInt1Type(), 1, 0)
-                    }
-                    "__memLoad" => LLVMBuildLoad2(
+                    LLVMConstInt(LLVMInt1Type(), 1, 0)
+                }
+                Some(BuiltinFunction::MemStore) => {
+                    LLVMBuildStore(
                         *self.builder,
-                        LLVMInt64Type(),
+                        arguments[2],
                         LLVMBuildGEP2(
                             *self.builder,
                             LLVMInt64Type(),
                             LLVMBuildIntToPtr(
                                 *self.builder,
-                                self.lower_expression(&arguments[0]),
+                                arguments[0],
                                 LLVMPointerType(LLVMInt64Type(), 0),
-                                string_to_c_string("memLoad_cast_temp".to_owned()).as_ptr(),
+                                string_to_c_string("memStore_cast_temp".to_owned()).as_ptr(),
                             ),
-                            [self.lower_expression(&arguments[1])].as_mut_ptr(),
+                            [arguments[1]].as_mut_ptr(),
                             1,
-                            string_to_c_string("memLoad_index_temp".to_owned()).as_ptr(),
+                            string_to_c_string("memStore_index_temp".to_owned()).as_ptr(),
                         ),
-                        string_to_c_string("load_temp".to_owned()).as_ptr(),
-                    ),
-                    _ => {
-                        let function = LLVMGetNamedFunction(
-                            *self.module,
-                            identifier_to_c_string(identifier).as_ptr(),
-                        );
-                        let function_type = self
-                            .environment_stack
-                            .top()
-                            .get(&identifier.name)
-                            .unwrap()
-                            .clone();
-
-                        LLVMBuildCall2(
+                    );
+
+                    LLVMConstInt(LLVMInt1Type(), 1, 0)
+                }
+                Some(BuiltinFunction::MemLoad) => LLVMBuildLoad2(
+                    *self.builder,
+                    LLVMInt64Type(),
+                    LLVMBuildGEP2(
+                        *self.builder,
+                        LLVMInt64Type(),
+                        LLVMBuildIntToPtr(
                             *self.builder,
-                            self.lower_type(&function_type),
-                            function,
-                            arguments
-                                .iter()
-                                .map(|a| self.lower_expression(a))
-                                .collect::<Vec<LLVMValueRef>>()
-                                .as_mut_ptr(),
-                            arguments.len() as u32,
-                            string_to_c_string("call_temp".to_owned()).as_ptr(),
-                        )
-                    }
+                            arguments[0],
+                            LLVMPointerType(LLVMInt64Type(), 0),
+                            string_to_c_string("memLoad_cast_temp".to_owned()).as_ptr(),
+                        ),
+                        [arguments[1]].as_mut_ptr(),
+                        1,
+                        string_to_c_string("memLoad_index_temp".to_owned()).as_ptr(),
+                    ),
+                    string_to_c_string("load_temp".to_owned()).as_ptr(),
+                ),
+                Some(BuiltinFunction::I64ToF64) => LLVMBuildSIToFP(
+                    *self.builder,
+                    arguments[0],
+                    LLVMDoubleType(),
+                    string_to_c_string("sitofp_temp".to_owned()).as_ptr(),
+                ),
+                Some(BuiltinFunction::F64ToI64) => {
+                    // Unlike `fptosi`, this saturates out of range values instead of producing
+                    // poison, and converts NaN to 0.
+                    let conversion_function_type =
+                        LLVMFunctionType(LLVMInt64Type(), [LLVMDoubleType()].as_mut_ptr(), 1, 0);
+                    let conversion_function =
+                        self.get_intrinsic("llvm.fptosi.sat.i64.f64", conversion_function_type);
+
+                    LLVMBuildCall2(
+                        *self.builder,
+                        conversion_function_type,
+                        conversion_function,
+                        [arguments[0]].as_mut_ptr(),
+                        1,
+                        string_to_c_string("fptosi_temp".to_owned()).as_ptr(),
+                    )
+                }
+                None if !type_arguments.is_empty() => {
+                    let (function, function_type) =
+                        self.instantiate_generic_function(identifier, type_arguments);
+
+                    LLVMBuildCall2(
+                        *self.builder,
+                        self.lower_type(&function_type),
+                        function,
+                        arguments.as_mut_ptr(),
+                        arguments.len() as u32,
+                        string_to_c_string("call_temp".to_owned()).as_ptr(),
+                    )
                 }
-            },
+                None => {
+                    let function = LLVMGetNamedFunction(
+                        *self.module,
+                        identifier_to_c_string(identifier).as_ptr(),
+                    );
+                    let function_type = self
+                        .environment_stack
+                        .top()
+                        .get(&identifier.name)
+                        .unwrap()
+                        .clone();
+
+                    LLVMBuildCall2(
+                        *self.builder,
+                        self.lower_type(&function_type),
+                        function,
+                        arguments.as_mut_ptr(),
+                        arguments.len() as u32,
+                        string_to_c_string("call_temp".to_owned()).as_ptr(),
+                    )
+                }
+            }
         }
     }
 
-    pub fn lower_if_expression(
+    /// Returns the LLVM function for the instantiation of the generic function `identifier` with
+    /// `type_arguments`, along with its type. Each distinct instantiation is lowered into its own
+    /// function, whose name is mangled with the type arguments, e.g. `identity<P_i64>`.
+    fn instantiate_generic_function(
         &mut self,
-        if_expression: &IfExpression<TypedNodeCommonFields>,
-    ) -> LLVMValueRef {
-        match if_expression {
-            IfExpression {
-                common_fields,
-                condition,
-                then_block,
-                else_block,
-            } => unsafe {
-                let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(*self.builder));
-
-                // The value that this if-expression evaluates to.
-                let result_value = LLVMBuildAlloca(
-                    *self.builder,
-                    self.lower_type(&common_fields.r#type),
-                    string_to_c_string("if_result_temp".to_owned()).as_ptr(),
-                );
+        identifier: &Identifier,
+        type_arguments: &[Type],
+    ) -> (LLVMValueRef, Type) {
+        let generic_function = self
+            .generic_functions
+            .get(&identifier.name)
+            .unwrap()
+            .clone();
+        // Type arguments may refer to the type parameters of the function being lowered.
+        let type_arguments = type_arguments
+            .iter()
+            .map(|t| t.substitute_type_parameters(&self.type_arguments))
+            .collect::<Vec<Type>>();
+        let substitutions = Iterator::zip(
+            generic_function
+                .type_parameters
+                .iter()
+                .map(|p| p.name.clone()),
+            type_arguments.iter().cloned(),
+        )
+        .collect::<HashMap<String, Type>>();
+        let function_type = generic_function
+            .common_fields
+            .r#type
+            .substitute_type_parameters(&substitutions);
 
-                let condition = self.lower_expression(&condition);
-                let then_bb = LLVMCreateBasicBlockInContext(
-                    *self.context,
-                    string_to_c_string("then_block".to_owned()).as_ptr(),
-                );
-                let else_bb = LLVMCreateBasicBlockInContext(
-                    *self.context,
-                    string_to_c_string("else_block".to_owned()).as_ptr(),
+        let mangled_name = format!(
+            "{}<{}>",
+            identifier.name,
+            type_arguments.iter().map(|t| t.to_string()).join(", ")
+        );
+        unsafe {
+            let mut function = LLVMGetNamedFunction(
+                *self.module,
+                string_to_c_string(mangled_name.clone()).as_ptr(),
+            );
+            if function.is_null() {
+                function = LLVMAddFunction(
+                    *self.module,
+                    string_to_c_string(mangled_name.clone()).as_ptr(),
+                    self.lower_type(&function_type),
                 );
-                let merge_bb = LLVMCreateBasicBlockInContext(
-                    *self.context,
-                    string_to_c_string("merge_block".to_owned()).as_ptr(),
+                self.pending_instantiations.push(Instantiation {
+                    name: mangled_name,
+                    function_declaration: generic_function,
+                    type_arguments: substitutions,
+                });
+            }
+            (function, function_type)
+        }
+    }
+
+    /// Lowers the body of `function_declaration` into the function called `name`, adding the
+    /// function to the module if it hasn't been declared yet.
+    fn lower_function(
+        &mut self,
+        name: &str,
+        function_declaration: &FunctionDeclaration<TypedNodeCommonFields>,
+    ) {
+        let FunctionDeclaration {
+            common_fields,
+            identifier: _,
+            type_parameters: _,
+            return_type: _,
+            parameters,
+            body,
+        } = function_declaration;
+        unsafe {
+            let parameter_count = parameters.len();
+            let mut function =
+                LLVMGetNamedFunction(*self.module, string_to_c_string(name.to_owned()).as_ptr());
+            if function.is_null() {
+                let function_type = self.lower_type(&common_fields.r#type);
+                function = LLVMAddFunction(
+                    *self.module,
+                    string_to_c_string(name.to_owned()).as_ptr(),
+                    function_type,
                 );
-                LLVMBuildCondBr(*self.builder, condition, then_bb, else_bb);
+            }
+            let block = LLVMAppendBasicBlockInContext(
+                *self.context,
+                function,
+                string_to_c_string(name.to_owned()).as_ptr(),
+            );
+            LLVMPositionBuilderAtEnd(*self.builder, block);
 
-                // TODO(derekxu16): This is unsafe.
-                let then_block_final_expression = then_block.final_expression.as_ref().unwrap();
-                LLVMAppendExistingBasicBlock(function, then_bb);
-                LLVMPositionBuilderAtEnd(*self.builder, then_bb);
-                self.visit(&Node::Block(then_block.clone()));
-                LLVMBuildStore(
+            let mut raw_vec: Vec<LLVMValueRef> = Vec::with_capacity(parameter_count);
+            let raw_vec_as_ptr = raw_vec.as_mut_ptr();
+            forget(raw_vec);
+
+            let params = {
+                LLVMGetParams(function, raw_vec_as_ptr);
+                Vec::from_raw_parts(raw_vec_as_ptr, parameter_count, parameter_count)
+            };
+
+            // Global variables and nested function definitions currently aren't supported,
+            // so it's safe to clear the variable "symbol table" when entering a new
+            // function scope.
+            self.variables.clear();
+            params.iter().enumerate().for_each(|(i, llvm_p)| {
+                let p = &function_declaration.parameters[i];
+                let identifier = &p.variable_declarator.identifier;
+                let var = LLVMBuildAlloca(
                     *self.builder,
-                    self.lower_expression(then_block_final_expression),
-                    result_value,
+                    self.lower_type(&p.common_fields.r#type),
+                    identifier_to_c_string(identifier).as_ptr(),
                 );
+                LLVMBuildStore(*self.builder, *llvm_p, var);
+                self.variables.insert(identifier_to_string(identifier), var);
+            });
+
+            self.lower_statements(&body.statements);
+            match &body.final_expression {
+                _ if self.is_terminated() => {}
+                Some(e) => {
+                    self.visit(&Node::Statement(Statement::ReturnStatement(Rc::new(
+                        ReturnStatement::new(e.clone()).with_span(e.span()),
+                    ))));
+                }
+                // The type checker only lets bodies of unit functions end without a value, unless
+                // they return earlier.
+                None => {
+                    LLVMBuildRet(*self.builder, self.lower_unit_literal());
+                }
+            };
+        }
+    }
+
+    pub fn lower_if_expression(
+        &mut self,
+        if_expression: &IfExpression<TypedNodeCommonFields>,
+    ) -> LLVMValueRef {
+        let IfExpression {
+            common_fields,
+            condition,
+            then_block,
+            else_block,
+        } = if_expression;
+        unsafe {
+            let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(*self.builder));
+
+            // The value that this if-expression evaluates to.
+            let result_type = self.lower_type(&common_fields.r#type);
+            let result_value = self.build_entry_alloca(result_type, "if_result_temp");
+
+            let condition = self.lower_expression(condition);
+            // The condition returns or jumps elsewhere, so neither branch is taken.
+            if self.is_terminated() {
+                return LLVMGetUndef(result_type);
+            }
+            let then_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("then_block".to_owned()).as_ptr(),
+            );
+            let else_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("else_block".to_owned()).as_ptr(),
+            );
+            let merge_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("merge_block".to_owned()).as_ptr(),
+            );
+            LLVMBuildCondBr(*self.builder, condition, then_bb, else_bb);
+
+            // A branch that returns early doesn't produce a value or continue to the merge block.
+            LLVMAppendExistingBasicBlock(function, then_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, then_bb);
+            let then_value = self.lower_block(then_block);
+            if let Some(value) = then_value {
+                LLVMBuildStore(*self.builder, value, result_value);
                 LLVMBuildBr(*self.builder, merge_bb);
+            }
 
-                LLVMAppendExistingBasicBlock(function, else_bb);
-                LLVMPositionBuilderAtEnd(*self.builder, else_bb);
-                self.visit(&Node::Block(else_block.clone()));
-                LLVMBuildStore(
-                    *self.builder,
-                    // TODO(derekxu16): This is unsafe.
-                    self.lower_expression(else_block.final_expression.as_ref().unwrap()),
-                    result_value,
-                );
+            LLVMAppendExistingBasicBlock(function, else_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, else_bb);
+            let else_value = self.lower_block(else_block);
+            if let Some(value) = else_value {
+                LLVMBuildStore(*self.builder, value, result_value);
                 LLVMBuildBr(*self.builder, merge_bb);
+            }
+
+            LLVMAppendExistingBasicBlock(function, merge_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, merge_bb);
 
-                LLVMAppendExistingBasicBlock(function, merge_bb);
-                LLVMPositionBuilderAtEnd(*self.builder, merge_bb);
+            // If neither branch finishes, nothing after the if-expression is reachable. Terminating
+            // the merge block makes the code that uses the if-expression's value skip it.
+            if then_value.is_none() && else_value.is_none() {
+                LLVMBuildUnreachable(*self.builder);
+                return LLVMGetUndef(result_type);
+            }
 
-                LLVMBuildLoad2(
+            LLVMBuildLoad2(
+                *self.builder,
+                self.lower_type(&common_fields.r#type),
+                result_value,
+                string_to_c_string("load_temp".to_owned()).as_ptr(),
+            )
+        }
+    }
+
+    /// Lowers `while (condition) { body }`. The condition is checked in its own basic block, which
+    /// the end of the body and any `continue` statements jump back to.
+    fn lower_while_statement(&mut self, while_statement: &WhileStatement<TypedNodeCommonFields>) {
+        let WhileStatement {
+            condition,
+            body,
+            span: _,
+        } = while_statement;
+        unsafe {
+            let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(*self.builder));
+            let condition_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("while_condition".to_owned()).as_ptr(),
+            );
+            let body_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("while_body".to_owned()).as_ptr(),
+            );
+            let exit_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("while_exit".to_owned()).as_ptr(),
+            );
+            LLVMBuildBr(*self.builder, condition_bb);
+
+            LLVMAppendExistingBasicBlock(function, condition_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, condition_bb);
+            let condition = self.lower_expression(condition);
+            if !self.is_terminated() {
+                LLVMBuildCondBr(*self.builder, condition, body_bb, exit_bb);
+            }
+
+            LLVMAppendExistingBasicBlock(function, body_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, body_bb);
+            self.loop_targets.push(LoopTargets {
+                continue_block: condition_bb,
+                break_block: exit_bb,
+            });
+            // The value of the body is unused, but evaluating it may have side effects.
+            if self.lower_block(body).is_some() {
+                LLVMBuildBr(*self.builder, condition_bb);
+            }
+            self.loop_targets.pop();
+
+            LLVMAppendExistingBasicBlock(function, exit_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, exit_bb);
+        }
+    }
+
+    /// Lowers `for (i in start..end step s) { body }` to a loop over an induction variable. The
+    /// bounds and the step are evaluated once, before the first iteration, and `continue`
+    /// statements jump to the block that steps the variable.
+    fn lower_for_statement(&mut self, for_statement: &ForStatement<TypedNodeCommonFields>) {
+        let ForStatement {
+            variable_declarator,
+            start,
+            end,
+            kind,
+            step,
+            body,
+            span: _,
+        } = for_statement;
+        unsafe {
+            let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(*self.builder));
+            let variable_type = self.lower_type(&variable_declarator.common_fields.r#type);
+
+            let start = self.lower_expression(start);
+            let end = self.lower_expression(end);
+            let step = match step {
+                Some(step) => self.lower_expression(step),
+                None => LLVMConstInt(variable_type, 1, false.into()),
+            };
+            let name = &variable_declarator.identifier.name;
+            let variable = self.build_entry_alloca(variable_type, name);
+            LLVMBuildStore(*self.builder, start, variable);
+            // The loop variable is only in scope inside the loop, so the variable it shadows is
+            // restored afterwards.
+            let enclosing_variables = self.variables.clone();
+            self.variables.insert(name.clone(), variable);
+            let is_signed = variable_declarator.common_fields.r#type.is_signed_integer();
+
+            let condition_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("for_condition".to_owned()).as_ptr(),
+            );
+            let body_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("for_body".to_owned()).as_ptr(),
+            );
+            let step_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("for_step".to_owned()).as_ptr(),
+            );
+            let exit_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("for_exit".to_owned()).as_ptr(),
+            );
+            if for_statement.step.is_some() {
+                // A step that isn't positive would never reach the end of the range.
+                let invalid_step_bb = LLVMAppendBasicBlockInContext(
+                    *self.context,
+                    function,
+                    string_to_c_string("for_invalid_step".to_owned()).as_ptr(),
+                );
+                let is_invalid_step = LLVMBuildICmp(
                     *self.builder,
-                    self.lower_type(then_block_final_expression.get_type()),
-                    result_value,
-                    string_to_c_string("load_temp".to_owned()).as_ptr(),
-                )
-            },
+                    if is_signed {
+                        LLVMIntPredicate::LLVMIntSLE
+                    } else {
+                        LLVMIntPredicate::LLVMIntEQ
+                    },
+                    step,
+                    LLVMConstInt(variable_type, 0, false.into()),
+                    string_to_c_string("is_invalid_step".to_owned()).as_ptr(),
+                );
+                LLVMBuildCondBr(
+                    *self.builder,
+                    is_invalid_step,
+                    invalid_step_bb,
+                    condition_bb,
+                );
+                LLVMPositionBuilderAtEnd(*self.builder, invalid_step_bb);
+                self.build_trap(*self.builder);
+            } else {
+                LLVMBuildBr(*self.builder, condition_bb);
+            }
+
+            LLVMAppendExistingBasicBlock(function, condition_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, condition_bb);
+            let current = LLVMBuildLoad2(
+                *self.builder,
+                variable_type,
+                variable,
+                string_to_c_string("for_current".to_owned()).as_ptr(),
+            );
+            let predicate = match (kind, is_signed) {
+                (RangeKind::Exclusive, true) => LLVMIntPredicate::LLVMIntSLT,
+                (RangeKind::Inclusive, true) => LLVMIntPredicate::LLVMIntSLE,
+                (RangeKind::Exclusive, false) => LLVMIntPredicate::LLVMIntULT,
+                (RangeKind::Inclusive, false) => LLVMIntPredicate::LLVMIntULE,
+            };
+            let condition = LLVMBuildICmp(
+                *self.builder,
+                predicate,
+                current,
+                end,
+                string_to_c_string("for_in_range".to_owned()).as_ptr(),
+            );
+            LLVMBuildCondBr(*self.builder, condition, body_bb, exit_bb);
+
+            LLVMAppendExistingBasicBlock(function, body_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, body_bb);
+            self.loop_targets.push(LoopTargets {
+                continue_block: step_bb,
+                break_block: exit_bb,
+            });
+            if self.lower_block(body).is_some() {
+                LLVMBuildBr(*self.builder, step_bb);
+            }
+            self.loop_targets.pop();
+
+            LLVMAppendExistingBasicBlock(function, step_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, step_bb);
+            let current = LLVMBuildLoad2(
+                *self.builder,
+                variable_type,
+                variable,
+                string_to_c_string("for_current".to_owned()).as_ptr(),
+            );
+            // Stepping past the largest value of the type leaves the range, and mustn't wrap around
+            // to the start of it, for example when the end of an inclusive range is that value.
+            let add_function_type = LLVMFunctionType(
+                LLVMStructType([variable_type, LLVMInt1Type()].as_mut_ptr(), 2, 0),
+                [variable_type, variable_type].as_mut_ptr(),
+                2,
+                0,
+            );
+            let add_function = self.get_intrinsic(
+                &format!(
+                    "llvm.{}add.with.overflow.i{}",
+                    if is_signed { "s" } else { "u" },
+                    LLVMGetIntTypeWidth(variable_type)
+                ),
+                add_function_type,
+            );
+            let add_result = LLVMBuildCall2(
+                *self.builder,
+                add_function_type,
+                add_function,
+                [current, step].as_mut_ptr(),
+                2,
+                string_to_c_string("for_add".to_owned()).as_ptr(),
+            );
+            let next = LLVMBuildExtractValue(
+                *self.builder,
+                add_result,
+                0,
+                string_to_c_string("for_next".to_owned()).as_ptr(),
+            );
+            let has_overflowed = LLVMBuildExtractValue(
+                *self.builder,
+                add_result,
+                1,
+                string_to_c_string("for_overflowed".to_owned()).as_ptr(),
+            );
+            LLVMBuildStore(*self.builder, next, variable);
+            LLVMBuildCondBr(*self.builder, has_overflowed, exit_bb, condition_bb);
+
+            LLVMAppendExistingBasicBlock(function, exit_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, exit_bb);
+            self.variables = enclosing_variables;
         }
     }
 
+    /// Returns the intrinsic or runtime function `name`, declaring it in the module the first time
+    /// it is needed.
+    fn get_intrinsic(&self, name: &str, function_type: LLVMTypeRef) -> LLVMValueRef {
+        unsafe {
+            let name = string_to_c_string(name.to_owned());
+            let function = LLVMGetNamedFunction(*self.module, name.as_ptr());
+            if function.is_null() {
+                LLVMAddFunction(*self.module, name.as_ptr(), function_type)
+            } else {
+                function
+            }
+        }
+    }
+
+    /// Builds a call to `llvm.trap` with `builder`, which ends the current block.
+    fn build_trap(&self, builder: LLVMBuilderRef) {
+        unsafe {
+            let trap_type = LLVMFunctionType(LLVMVoidType(), [].as_mut_ptr(), 0, 0);
+            LLVMBuildCall2(
+                builder,
+                trap_type,
+                self.get_intrinsic("llvm.trap", trap_type),
+                [].as_mut_ptr(),
+                0,
+                string_to_c_string("".to_owned()).as_ptr(),
+            );
+            LLVMBuildUnreachable(builder);
+        }
+    }
+
+    /// Builds an alloca at the start of the entry block of the function being lowered, so that
+    /// variables declared inside loops don't grow the stack on every iteration.
+    fn build_entry_alloca(&self, r#type: LLVMTypeRef, name: &str) -> LLVMValueRef {
+        unsafe {
+            let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(*self.builder));
+            let entry_bb = LLVMGetEntryBasicBlock(function);
+            let entry_builder = LLVMCreateBuilderInContext(*self.context);
+            let first_instruction = LLVMGetFirstInstruction(entry_bb);
+            if first_instruction.is_null() {
+                LLVMPositionBuilderAtEnd(entry_builder, entry_bb);
+            } else {
+                LLVMPositionBuilderBefore(entry_builder, first_instruction);
+            }
+            let alloca = LLVMBuildAlloca(
+                entry_builder,
+                r#type,
+                string_to_c_string(name.to_owned()).as_ptr(),
+            );
+            LLVMDisposeBuilder(entry_builder);
+            alloca
+        }
+    }
+
+    /// Returns whether the basic block being built already ends with a terminator, such as the
+    /// `ret` of a return statement. Nothing may be added to such a block.
+    fn is_terminated(&self) -> bool {
+        unsafe { !LLVMGetBasicBlockTerminator(LLVMGetInsertBlock(*self.builder)).is_null() }
+    }
+
+    /// Lowers `statements` in order, stopping after a statement that returns or jumps, since the
+    /// code after it is unreachable.
+    fn lower_statements(&mut self, statements: &[Statement<TypedNodeCommonFields>]) {
+        for statement in statements.iter() {
+            if self.is_terminated() {
+                break;
+            }
+            self.visit(&Node::Statement(statement.clone()));
+        }
+    }
+
+    /// Lowers the value that `block` evaluates to, after its statements have been lowered. Blocks
+    /// without a final expression evaluate to unit. Returns `None` if the block returns or jumps
+    /// elsewhere before it produces a value.
+    fn lower_block_value(&mut self, block: &Block<TypedNodeCommonFields>) -> Option<LLVMValueRef> {
+        if self.is_terminated() {
+            return None;
+        }
+        let value = match &block.final_expression {
+            Some(e) => self.lower_expression(e),
+            None => self.lower_unit_literal(),
+        };
+        if self.is_terminated() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Lowers `expressions` in order. Returns `None` if one of them returns or jumps elsewhere,
+    /// since nothing may be added to the block after that, so the values can't be used.
+    fn lower_operands<'e>(
+        &mut self,
+        expressions: impl IntoIterator<Item = &'e Expression<TypedNodeCommonFields>>,
+    ) -> Option<Vec<LLVMValueRef>> {
+        let mut values = vec![];
+        for expression in expressions {
+            let value = self.lower_expression(expression);
+            if self.is_terminated() {
+                return None;
+            }
+            values.push(value);
+        }
+        Some(values)
+    }
+
+    /// Lowers `block`, returning its value like [Builder::lower_block_value]. The variables
+    /// declared in the block go out of scope at its end, so the variables they shadow are restored.
+    fn lower_block(&mut self, block: &Rc<Block<TypedNodeCommonFields>>) -> Option<LLVMValueRef> {
+        let enclosing_variables = self.variables.clone();
+        self.visit(&Node::Block(block.clone()));
+        let value = self.lower_block_value(block);
+        self.variables = enclosing_variables;
+        value
+    }
+
     pub fn lower_prefix_expression(
         &mut self,
         prefix_expression: &PrefixExpression<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
-        match prefix_expression {
-            PrefixExpression {
-                common_fields: _,
-                operator,
-                operand,
-            } => unsafe {
-                match operator {
-                    PrefixOperator::Minus => LLVMBuildSub(
-                        *self.builder,
-                        LLVMConstInt(self.lower_type(operand.get_type()), 0, false.into()),
-                        self.lower_expression(operand),
-                        string_to_c_string("sub_temp".to_owned()).as_ptr(),
-                    ),
-                    PrefixOperator::Bang => LLVMBuildXor(
-                        *self.builder,
-                        LLVMConstInt(LLVMInt1Type(), 1, false.into()),
-                        self.lower_expression(operand),
-                        string_to_c_string("xor_temp".to_owned()).as_ptr(),
-                    ),
+        let PrefixExpression {
+            common_fields,
+            operator,
+            operand,
+        } = prefix_expression;
+        unsafe {
+            match (operator, operand) {
+                // Negated literals are constants, rather than subtractions from 0.
+                (PrefixOperator::Minus, Expression::IntegerLiteral(l)) => {
+                    self.lower_integer_constant(&common_fields.r#type, -i128::from(l.value))
                 }
-            },
+                (PrefixOperator::Minus, _) if operand.get_type().is_float() => LLVMBuildFNeg(
+                    *self.builder,
+                    self.lower_expression(operand),
+                    string_to_c_string("fneg_temp".to_owned()).as_ptr(),
+                ),
+                (PrefixOperator::Minus, _) => LLVMBuildSub(
+                    *self.builder,
+                    LLVMConstInt(self.lower_type(operand.get_type()), 0, false.into()),
+                    self.lower_expression(operand),
+                    string_to_c_string("sub_temp".to_owned()).as_ptr(),
+                ),
+                (PrefixOperator::Bang, _) => LLVMBuildXor(
+                    *self.builder,
+                    LLVMConstInt(LLVMInt1Type(), 1, false.into()),
+                    self.lower_expression(operand),
+                    string_to_c_string("xor_temp".to_owned()).as_ptr(),
+                ),
+                (PrefixOperator::BitNot, _) => LLVMBuildNot(
+                    *self.builder,
+                    self.lower_expression(operand),
+                    string_to_c_string("not_temp".to_owned()).as_ptr(),
+                ),
+            }
         }
     }
 
@@ -480,91 +1071,532 @@ impl<'a> Builder<'a> {
         &mut self,
         binary_expression: &BinaryExpression<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
-        match binary_expression {
-            BinaryExpression {
-                common_fields: _,
-                left,
-                operator,
-                right,
-            } => unsafe {
-                match operator {
-                    InfixOperator::Plus => LLVMBuildAdd(
-                        *self.builder,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("add_temp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::Minus => LLVMBuildSub(
-                        *self.builder,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("sub_temp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::Times => LLVMBuildMul(
-                        *self.builder,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("mul_temp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::Divide => LLVMBuildSDiv(
-                        *self.builder,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("sdiv_temp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::Modulo => LLVMBuildSRem(
-                        *self.builder,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("srem_temp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::DoubleEquals => LLVMBuildICmp(
-                        *self.builder,
-                        LLVMIntPredicate::LLVMIntEQ,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("eq_temp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::LessThan => LLVMBuildICmp(
-                        *self.builder,
-                        LLVMIntPredicate::LLVMIntSLT,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("slt_tmp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::LessThanEquals => LLVMBuildICmp(
-                        *self.builder,
-                        LLVMIntPredicate::LLVMIntSLE,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("sle_tmp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::GreaterThan => LLVMBuildICmp(
-                        *self.builder,
-                        LLVMIntPredicate::LLVMIntSGT,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("sgt_tmp".to_owned()).as_ptr(),
-                    ),
-                    InfixOperator::GreaterThanEquals => LLVMBuildICmp(
+        let BinaryExpression {
+            common_fields,
+            left,
+            operator,
+            right,
+        } = binary_expression;
+        unsafe {
+            match operator {
+                InfixOperator::Equals => return self.lower_assignment(left, right),
+                InfixOperator::LogicalAnd | InfixOperator::LogicalOr => {
+                    return self.lower_logical_expression(left, operator, right)
+                }
+                _ => {}
+            }
+            let (left_value, right_value) = match self.lower_operands([left, right]).as_deref() {
+                Some(&[left_value, right_value]) => (left_value, right_value),
+                // An operand returns or jumps elsewhere, so the operation is never performed.
+                _ => return LLVMGetUndef(self.lower_type(&common_fields.r#type)),
+            };
+            match operator {
+                InfixOperator::Equals | InfixOperator::LogicalAnd | InfixOperator::LogicalOr => {
+                    unreachable!("assignments and logical operators are lowered above")
+                }
+                _ if left.get_type().is_float() => {
+                    self.lower_float_binary_expression(left_value, operator, right_value)
+                }
+                InfixOperator::Divide
+                | InfixOperator::Modulo
+                | InfixOperator::LessThan
+                | InfixOperator::LessThanEquals
+                | InfixOperator::GreaterThan
+                | InfixOperator::GreaterThanEquals
+                | InfixOperator::BitShiftRight
+                    if left.get_type().is_unsigned_integer() =>
+                {
+                    self.lower_unsigned_binary_expression(left_value, operator, right_value)
+                }
+                InfixOperator::Plus => LLVMBuildAdd(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("add_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Minus => LLVMBuildSub(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("sub_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Times => LLVMBuildMul(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("mul_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Divide => LLVMBuildSDiv(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("sdiv_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Modulo => LLVMBuildSRem(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("srem_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::DoubleEquals => LLVMBuildICmp(
+                    *self.builder,
+                    LLVMIntPredicate::LLVMIntEQ,
+                    left_value,
+                    right_value,
+                    string_to_c_string("eq_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::NotEquals => LLVMBuildICmp(
+                    *self.builder,
+                    LLVMIntPredicate::LLVMIntNE,
+                    left_value,
+                    right_value,
+                    string_to_c_string("ne_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::LessThan => LLVMBuildICmp(
+                    *self.builder,
+                    LLVMIntPredicate::LLVMIntSLT,
+                    left_value,
+                    right_value,
+                    string_to_c_string("slt_tmp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::LessThanEquals => LLVMBuildICmp(
+                    *self.builder,
+                    LLVMIntPredicate::LLVMIntSLE,
+                    left_value,
+                    right_value,
+                    string_to_c_string("sle_tmp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::GreaterThan => LLVMBuildICmp(
+                    *self.builder,
+                    LLVMIntPredicate::LLVMIntSGT,
+                    left_value,
+                    right_value,
+                    string_to_c_string("sgt_tmp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::GreaterThanEquals => LLVMBuildICmp(
+                    *self.builder,
+                    LLVMIntPredicate::LLVMIntSGE,
+                    left_value,
+                    right_value,
+                    string_to_c_string("sge_tmp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Exponent => {
+                    let (function, function_type) =
+                        self.get_integer_power_function(left.get_type());
+                    LLVMBuildCall2(
                         *self.builder,
-                        LLVMIntPredicate::LLVMIntSGE,
-                        self.lower_expression(left),
-                        self.lower_expression(right),
-                        string_to_c_string("sge_tmp".to_owned()).as_ptr(),
-                    ),
-                    _ => {
-                        unimplemented!()
-                    }
+                        function_type,
+                        function,
+                        [left_value, right_value].as_mut_ptr(),
+                        2,
+                        string_to_c_string("pow_temp".to_owned()).as_ptr(),
+                    )
+                }
+                InfixOperator::BitAnd => LLVMBuildAnd(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("and_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::BitOr => LLVMBuildOr(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("or_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::BitXor => LLVMBuildXor(
+                    *self.builder,
+                    left_value,
+                    right_value,
+                    string_to_c_string("xor_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::BitShiftLeft => LLVMBuildShl(
+                    *self.builder,
+                    left_value,
+                    self.lower_shift_count(right_value),
+                    string_to_c_string("shl_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::BitShiftRight => LLVMBuildAShr(
+                    *self.builder,
+                    left_value,
+                    self.lower_shift_count(right_value),
+                    string_to_c_string("ashr_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Dot => {
+                    unreachable!("the parser turns `.` into field accesses, not binary expressions")
                 }
-            },
+            }
         }
     }
 
-    pub fn lower_field_access(
+    /// Lowers the operators whose result depends on whether their integer operands are signed, for
+    /// unsigned operands.
+    fn lower_unsigned_binary_expression(
         &mut self,
-        field_access: &FieldAccess<TypedNodeCommonFields>,
+        left: LLVMValueRef,
+        operator: &InfixOperator,
+        right: LLVMValueRef,
+    ) -> LLVMValueRef {
+        unsafe {
+            let compare = |builder: &mut Self, predicate, name: &str| {
+                LLVMBuildICmp(
+                    *builder.builder,
+                    predicate,
+                    left,
+                    right,
+                    string_to_c_string(name.to_owned()).as_ptr(),
+                )
+            };
+            match operator {
+                InfixOperator::Divide => LLVMBuildUDiv(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("udiv_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Modulo => LLVMBuildURem(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("urem_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::LessThan => compare(self, LLVMIntPredicate::LLVMIntULT, "ult_temp"),
+                InfixOperator::LessThanEquals => {
+                    compare(self, LLVMIntPredicate::LLVMIntULE, "ule_temp")
+                }
+                InfixOperator::GreaterThan => {
+                    compare(self, LLVMIntPredicate::LLVMIntUGT, "ugt_temp")
+                }
+                InfixOperator::GreaterThanEquals => {
+                    compare(self, LLVMIntPredicate::LLVMIntUGE, "uge_temp")
+                }
+                InfixOperator::BitShiftRight => LLVMBuildLShr(
+                    *self.builder,
+                    left,
+                    self.lower_shift_count(right),
+                    string_to_c_string("lshr_temp".to_owned()).as_ptr(),
+                ),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Returns the function that raises integers of type `r#type` to a power, and its type, adding
+    /// it to the module the first time it is needed. The function computes the power by
+    /// squaring, and traps if a signed exponent is negative, since the result wouldn't be an
+    /// integer.
+    fn get_integer_power_function(&mut self, r#type: &Type) -> (LLVMValueRef, LLVMTypeRef) {
+        unsafe {
+            let integer_type = self.lower_type(r#type);
+            let function_type = LLVMFunctionType(
+                integer_type,
+                [integer_type, integer_type].as_mut_ptr(),
+                2,
+                0,
+            );
+            // `.` can't appear in identifiers, so this can't clash with a user's function.
+            let name = string_to_c_string(format!("dishsoap.pow.{}", r#type));
+            let function = LLVMGetNamedFunction(*self.module, name.as_ptr());
+            if !function.is_null() {
+                return (function, function_type);
+            }
+
+            let function = LLVMAddFunction(*self.module, name.as_ptr(), function_type);
+            LLVMSetLinkage(function, llvm_sys::LLVMLinkage::LLVMInternalLinkage);
+            let builder = LLVMCreateBuilderInContext(*self.context);
+            let append_block = |name: &str| {
+                LLVMAppendBasicBlockInContext(
+                    *self.context,
+                    function,
+                    string_to_c_string(name.to_owned()).as_ptr(),
+                )
+            };
+            let entry_bb = append_block("entry");
+            let loop_bb = append_block("loop");
+            let multiply_bb = append_block("multiply");
+            let exit_bb = append_block("exit");
+            let constant = |value: u64| LLVMConstInt(integer_type, value, false.into());
+            let base = LLVMGetParam(function, 0);
+            let exponent = LLVMGetParam(function, 1);
+
+            LLVMPositionBuilderAtEnd(builder, entry_bb);
+            // Unsigned exponents are never negative.
+            if r#type.is_unsigned_integer() {
+                LLVMBuildBr(builder, loop_bb);
+            } else {
+                let trap_bb = append_block("negative_exponent");
+                let is_negative = LLVMBuildICmp(
+                    builder,
+                    LLVMIntPredicate::LLVMIntSLT,
+                    exponent,
+                    constant(0),
+                    string_to_c_string("is_negative".to_owned()).as_ptr(),
+                );
+                LLVMBuildCondBr(builder, is_negative, trap_bb, loop_bb);
+
+                LLVMPositionBuilderAtEnd(builder, trap_bb);
+                self.build_trap(builder);
+            }
+
+            // Each iteration multiplies the result by the base if the lowest bit of the exponent
+            // is set, then squares the base and drops that bit.
+            LLVMPositionBuilderAtEnd(builder, loop_bb);
+            let phi = |name: &str| {
+                LLVMBuildPhi(
+                    builder,
+                    integer_type,
+                    string_to_c_string(name.to_owned()).as_ptr(),
+                )
+            };
+            let result_phi = phi("result");
+            let base_phi = phi("base");
+            let exponent_phi = phi("exponent");
+            let is_done = LLVMBuildICmp(
+                builder,
+                LLVMIntPredicate::LLVMIntEQ,
+                exponent_phi,
+                constant(0),
+                string_to_c_string("is_done".to_owned()).as_ptr(),
+            );
+            LLVMBuildCondBr(builder, is_done, exit_bb, multiply_bb);
+
+            LLVMPositionBuilderAtEnd(builder, multiply_bb);
+            let lowest_bit = LLVMBuildAnd(
+                builder,
+                exponent_phi,
+                constant(1),
+                string_to_c_string("lowest_bit".to_owned()).as_ptr(),
+            );
+            let is_odd = LLVMBuildICmp(
+                builder,
+                LLVMIntPredicate::LLVMIntNE,
+                lowest_bit,
+                constant(0),
+                string_to_c_string("is_odd".to_owned()).as_ptr(),
+            );
+            let multiplied = LLVMBuildMul(
+                builder,
+                result_phi,
+                base_phi,
+                string_to_c_string("multiplied".to_owned()).as_ptr(),
+            );
+            let next_result = LLVMBuildSelect(
+                builder,
+                is_odd,
+                multiplied,
+                result_phi,
+                string_to_c_string("next_result".to_owned()).as_ptr(),
+            );
+            let next_base = LLVMBuildMul(
+                builder,
+                base_phi,
+                base_phi,
+                string_to_c_string("next_base".to_owned()).as_ptr(),
+            );
+            let next_exponent = LLVMBuildLShr(
+                builder,
+                exponent_phi,
+                constant(1),
+                string_to_c_string("next_exponent".to_owned()).as_ptr(),
+            );
+            LLVMBuildBr(builder, loop_bb);
+
+            for (phi, initial, next) in [
+                (result_phi, constant(1), next_result),
+                (base_phi, base, next_base),
+                (exponent_phi, exponent, next_exponent),
+            ] {
+                LLVMAddIncoming(
+                    phi,
+                    [initial, next].as_mut_ptr(),
+                    [entry_bb, multiply_bb].as_mut_ptr(),
+                    2,
+                );
+            }
+
+            LLVMPositionBuilderAtEnd(builder, exit_bb);
+            LLVMBuildRet(builder, result_phi);
+            LLVMDisposeBuilder(builder);
+
+            (function, function_type)
+        }
+    }
+
+    /// Lowers the right operand of a shift. Shifting by at least the bit width of the operands
+    /// produces poison in LLVM, so the count is taken modulo the bit width instead, like on x86.
+    fn lower_shift_count(&mut self, count: LLVMValueRef) -> LLVMValueRef {
+        unsafe {
+            let count_type = LLVMTypeOf(count);
+            let bit_width = LLVMGetIntTypeWidth(count_type);
+            LLVMBuildAnd(
+                *self.builder,
+                count,
+                LLVMConstInt(count_type, (bit_width - 1) as u64, false.into()),
+                string_to_c_string("shift_count_temp".to_owned()).as_ptr(),
+            )
+        }
+    }
+
+    /// Lowers a binary expression whose operands are floats. Comparisons are false if either
+    /// operand is NaN, except for `!=`, which is always the negation of `==`.
+    fn lower_float_binary_expression(
+        &mut self,
+        left: LLVMValueRef,
+        operator: &InfixOperator,
+        right: LLVMValueRef,
     ) -> LLVMValueRef {
+        unsafe {
+            let compare = |predicate| {
+                LLVMBuildFCmp(
+                    *self.builder,
+                    predicate,
+                    left,
+                    right,
+                    string_to_c_string("fcmp_temp".to_owned()).as_ptr(),
+                )
+            };
+            match operator {
+                InfixOperator::Plus => LLVMBuildFAdd(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("fadd_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Minus => LLVMBuildFSub(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("fsub_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Times => LLVMBuildFMul(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("fmul_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Divide => LLVMBuildFDiv(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("fdiv_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::Modulo => LLVMBuildFRem(
+                    *self.builder,
+                    left,
+                    right,
+                    string_to_c_string("frem_temp".to_owned()).as_ptr(),
+                ),
+                InfixOperator::DoubleEquals => compare(LLVMRealPredicate::LLVMRealOEQ),
+                InfixOperator::NotEquals => compare(LLVMRealPredicate::LLVMRealUNE),
+                InfixOperator::LessThan => compare(LLVMRealPredicate::LLVMRealOLT),
+                InfixOperator::LessThanEquals => compare(LLVMRealPredicate::LLVMRealOLE),
+                InfixOperator::GreaterThan => compare(LLVMRealPredicate::LLVMRealOGT),
+                InfixOperator::GreaterThanEquals => compare(LLVMRealPredicate::LLVMRealOGE),
+                // The type checker only allows arithmetic and comparisons on floats.
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Lowers `left && right` or `left || right`. The right operand is only evaluated if the left
+    /// one doesn't determine the result, which is merged from both paths with a phi.
+    fn lower_logical_expression(
+        &mut self,
+        left: &Expression<TypedNodeCommonFields>,
+        operator: &InfixOperator,
+        right: &Expression<TypedNodeCommonFields>,
+    ) -> LLVMValueRef {
+        unsafe {
+            let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(*self.builder));
+            let left_value = self.lower_expression(left);
+            if self.is_terminated() {
+                return LLVMGetUndef(LLVMInt1Type());
+            }
+            // Lowering the left operand may have moved the builder to a new block.
+            let left_bb = LLVMGetInsertBlock(*self.builder);
+            let right_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("logical_right".to_owned()).as_ptr(),
+            );
+            let merge_bb = LLVMCreateBasicBlockInContext(
+                *self.context,
+                string_to_c_string("logical_merge".to_owned()).as_ptr(),
+            );
+
+            // `false && right` is false and `true || right` is true, without evaluating `right`.
+            let short_circuit_value = match operator {
+                InfixOperator::LogicalAnd => {
+                    LLVMBuildCondBr(*self.builder, left_value, right_bb, merge_bb);
+                    LLVMConstInt(LLVMInt1Type(), 0, false.into())
+                }
+                _ => {
+                    LLVMBuildCondBr(*self.builder, left_value, merge_bb, right_bb);
+                    LLVMConstInt(LLVMInt1Type(), 1, false.into())
+                }
+            };
+
+            LLVMAppendExistingBasicBlock(function, right_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, right_bb);
+            let right_value = self.lower_expression(right);
+            let right_end_bb = LLVMGetInsertBlock(*self.builder);
+            // If the right operand returns or jumps elsewhere, the result is always the short
+            // circuit value.
+            let right_finishes = !self.is_terminated();
+            if right_finishes {
+                LLVMBuildBr(*self.builder, merge_bb);
+            }
+
+            LLVMAppendExistingBasicBlock(function, merge_bb);
+            LLVMPositionBuilderAtEnd(*self.builder, merge_bb);
+            if !right_finishes {
+                return short_circuit_value;
+            }
+            let phi = LLVMBuildPhi(
+                *self.builder,
+                LLVMInt1Type(),
+                string_to_c_string("logical_temp".to_owned()).as_ptr(),
+            );
+            let mut incoming_values = [short_circuit_value, right_value];
+            let mut incoming_blocks = [left_bb, right_end_bb];
+            LLVMAddIncoming(
+                phi,
+                incoming_values.as_mut_ptr(),
+                incoming_blocks.as_mut_ptr(),
+                2,
+            );
+            phi
+        }
+    }
+
+    /// Stores `value` into the variable or field that `target` refers to. Assignments evaluate to
+    /// unit.
+    pub fn lower_assignment(
+        &mut self,
+        target: &Expression<TypedNodeCommonFields>,
+        value: &Expression<TypedNodeCommonFields>,
+    ) -> LLVMValueRef {
+        let pointer = match target {
+            Expression::VariableReference(r) => *self.variables.get(&r.identifier.name).unwrap(),
+            Expression::FieldAccess(a) => self.lower_field_pointer(a).0,
+            // The type checker only allows variables and fields to be assigned to.
+            _ => unreachable!(),
+        };
+        let value = self.lower_expression(value);
+        // A value that returns or jumps elsewhere is never stored.
+        if !self.is_terminated() {
+            unsafe {
+                LLVMBuildStore(*self.builder, value, pointer);
+            }
+        }
+        self.lower_unit_literal()
+    }
+
+    /// Returns a pointer to the field that `field_access` refers to, along with the type of the
+    /// field.
+    fn lower_field_pointer(
+        &mut self,
+        field_access: &FieldAccess<TypedNodeCommonFields>,
+    ) -> (LLVMValueRef, LLVMTypeRef) {
         unsafe {
             let target_type = match field_access.target.get_type() {
                 Type::RecordType(t) => t,
@@ -584,23 +1616,60 @@ impl<'a> Builder<'a> {
                 field_index,
                 string_to_c_string("field_access_pointer_temp".to_owned()).as_ptr(),
             );
+            (
+                element_pointer,
+                LLVMStructGetTypeAtIndex(llvm_target_type, field_index),
+            )
+        }
+    }
+
+    pub fn lower_field_access(
+        &mut self,
+        field_access: &FieldAccess<TypedNodeCommonFields>,
+    ) -> LLVMValueRef {
+        let (element_pointer, field_type) = self.lower_field_pointer(field_access);
+        unsafe {
             LLVMBuildLoad2(
                 *self.builder,
-                LLVMStructGetTypeAtIndex(llvm_target_type, field_index),
+                field_type,
                 element_pointer,
                 string_to_c_string("field_access_temp".to_owned()).as_ptr(),
             )
         }
     }
 
+    /// Lowers `expression as T`, which truncates integers that are converted to a narrower type,
+    /// and extends integers that are converted to a wider type according to their own signedness.
+    pub fn lower_cast_expression(
+        &mut self,
+        cast_expression: &CastExpression<TypedNodeCommonFields>,
+    ) -> LLVMValueRef {
+        let CastExpression {
+            common_fields,
+            expression,
+            target_type: _,
+        } = cast_expression;
+        let target_type = self.lower_type(&common_fields.r#type);
+        unsafe {
+            LLVMBuildIntCast2(
+                *self.builder,
+                self.lower_expression(expression),
+                target_type,
+                expression.get_type().is_signed_integer().into(),
+                string_to_c_string("cast_temp".to_owned()).as_ptr(),
+            )
+        }
+    }
+
     pub fn lower_expression(
         &mut self,
         expression: &Expression<TypedNodeCommonFields>,
     ) -> LLVMValueRef {
         match expression {
-            Expression::UnitLiteral(_u) => unimplemented!(),
+            Expression::UnitLiteral(_u) => self.lower_unit_literal(),
             Expression::BooleanLiteral(b) => self.lower_boolean_literal(b),
             Expression::IntegerLiteral(i) => self.lower_integer_literal(i),
+            Expression::FloatLiteral(f) => self.lower_float_literal(f),
             Expression::ObjectLiteral(r) => self.lower_object_literal(r),
             Expression::VariableReference(r) => self.lower_variable_reference(r),
             Expression::FunctionCall(c) => self.lower_function_call(c),
@@ -608,91 +1677,109 @@ impl<'a> Builder<'a> {
             Expression::PrefixExpression(e) => self.lower_prefix_expression(e),
             Expression::BinaryExpression(e) => self.lower_binary_expression(e),
             Expression::FieldAccess(a) => self.lower_field_access(a),
+            Expression::CastExpression(c) => self.lower_cast_expression(c),
         }
     }
 }
 
 impl<'a> PreOrderVisitor<TypedNodeCommonFields> for Builder<'a> {
+    fn process_block(&mut self, block: &Block<TypedNodeCommonFields>) -> PreOrderVisitorResponse {
+        // The final expression is lowered by whatever uses the value of the block.
+        self.lower_statements(&block.statements);
+        *PreOrderVisitorResponse::new(true)
+    }
+
     fn process_return_statement(
         &mut self,
         return_statement: &ReturnStatement<TypedNodeCommonFields>,
     ) -> PreOrderVisitorResponse {
-        match return_statement {
-            ReturnStatement { expression } => {
-                let lowered_expression = self.lower_expression(expression);
-                unsafe {
-                    LLVMBuildRet(*self.builder, lowered_expression);
-                }
-                *PreOrderVisitorResponse::new(true)
+        let ReturnStatement {
+            expression,
+            span: _,
+        } = return_statement;
+        let lowered_expression = self.lower_expression(expression);
+        // An expression that never finishes, like an if-expression whose branches both return,
+        // leaves nothing to return.
+        if !self.is_terminated() {
+            unsafe {
+                LLVMBuildRet(*self.builder, lowered_expression);
             }
         }
+        *PreOrderVisitorResponse::new(true)
     }
 
-    fn process_function_declaration(
+    fn process_while_statement(
         &mut self,
-        function_declaration: &FunctionDeclaration<TypedNodeCommonFields>,
+        while_statement: &WhileStatement<TypedNodeCommonFields>,
     ) -> PreOrderVisitorResponse {
-        match function_declaration {
-            FunctionDeclaration {
-                common_fields,
-                identifier,
-                return_type: _,
-                parameters,
-                body,
-            } => unsafe {
-                let parameter_count = parameters.len();
-                let function_type = self.lower_type(&common_fields.r#type);
+        self.lower_while_statement(while_statement);
+        *PreOrderVisitorResponse::new(true)
+    }
 
-                let function = LLVMAddFunction(
-                    *self.module,
-                    identifier_to_c_string(identifier).as_ptr(),
-                    function_type,
-                );
-                let block = LLVMAppendBasicBlockInContext(
-                    *self.context,
-                    function,
-                    identifier_to_c_string(identifier).as_ptr(),
-                );
-                LLVMPositionBuilderAtEnd(*self.builder, block);
-
-                let mut raw_vec: Vec<LLVMValueRef> = Vec::with_capacity(parameter_count);
-                let raw_vec_as_ptr = raw_vec.as_mut_ptr();
-                forget(raw_vec);
-
-                let params = {
-                    LLVMGetParams(function, raw_vec_as_ptr);
-                    Vec::from_raw_parts(raw_vec_as_ptr, parameter_count, parameter_count)
-                };
-
-                // Global variables and nested function definitions currently aren't supported,
-                // so it's safe to clear the variable "symbol table" when entering a new
-                // function scope.
-                self.variables.clear();
-                params.iter().enumerate().for_each(|(i, llvm_p)| {
-                    let p = &function_declaration.parameters[i];
-                    let identifier = &p.variable_declarator.identifier;
-                    let var = LLVMBuildAlloca(
-                        *self.builder,
-                        self.lower_type(&p.common_fields.r#type),
-                        identifier_to_c_string(identifier).as_ptr(),
-                    );
-                    LLVMBuildStore(*self.builder, *llvm_p, var);
-                    self.variables.insert(identifier_to_string(identifier), var);
-                });
+    fn process_for_statement(
+        &mut self,
+        for_statement: &ForStatement<TypedNodeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        self.lower_for_statement(for_statement);
+        *PreOrderVisitorResponse::new(true)
+    }
 
-                body.statements.iter().for_each(|s| {
-                    self.visit(&Node::Statement(s.clone()));
-                });
-                match &body.final_expression {
-                    Some(e) => {
-                        self.visit(&Node::Statement(Statement::ReturnStatement(Rc::new(
-                            ReturnStatement::new(e.clone()),
-                        ))));
-                    }
-                    None => (),
-                };
-            },
+    fn process_break_statement(&mut self, _break_statement: &BreakStatement) {
+        // The type checker only allows `break` inside loops.
+        let break_block = self.loop_targets.last().unwrap().break_block;
+        unsafe {
+            LLVMBuildBr(*self.builder, break_block);
+        }
+    }
+
+    fn process_continue_statement(&mut self, _continue_statement: &ContinueStatement) {
+        let continue_block = self.loop_targets.last().unwrap().continue_block;
+        unsafe {
+            LLVMBuildBr(*self.builder, continue_block);
         }
+    }
+
+    fn process_source_file(
+        &mut self,
+        source_file: &SourceFile<TypedNodeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        source_file.declarations.iter().for_each(|d| {
+            if let Declaration::FunctionDeclaration(fd) = d {
+                self.declared_functions.insert(fd.identifier.name.clone());
+                if !fd.type_parameters.is_empty() {
+                    self.generic_functions
+                        .insert(fd.identifier.name.clone(), fd.clone());
+                }
+            }
+        });
+        source_file.declarations.iter().for_each(|d| match d {
+            Declaration::FunctionDeclaration(fd) if !fd.type_parameters.is_empty() => {}
+            _ => self.visit(&Node::Statement(Statement::Declaration(d.clone()))),
+        });
+
+        // Lowering an instantiation may instantiate further generic functions.
+        while let Some(instantiation) = self.pending_instantiations.pop() {
+            self.type_arguments = instantiation.type_arguments;
+            self.lower_function(&instantiation.name, &instantiation.function_declaration);
+        }
+        self.type_arguments.clear();
+
+        *PreOrderVisitorResponse::new(true)
+    }
+
+    fn process_expression_statement(
+        &mut self,
+        expression_statement: &ExpressionStatement<TypedNodeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        self.lower_expression(&expression_statement.expression);
+        *PreOrderVisitorResponse::new(true)
+    }
+
+    fn process_function_declaration(
+        &mut self,
+        function_declaration: &FunctionDeclaration<TypedNodeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        self.lower_function(&function_declaration.identifier.name, function_declaration);
 
         *PreOrderVisitorResponse::new(true)
     }
@@ -701,25 +1788,23 @@ impl<'a> PreOrderVisitor<TypedNodeCommonFields> for Builder<'a> {
         &mut self,
         variable_declaration: &VariableDeclaration<TypedNodeCommonFields>,
     ) -> PreOrderVisitorResponse {
-        match variable_declaration {
-            VariableDeclaration {
-                common_fields: _,
-                variable_declarator,
-                initial_value,
-            } => {
-                let identifier = &variable_declarator.identifier;
-                unsafe {
-                    let var = LLVMBuildAlloca(
-                        *self.builder,
-                        self.lower_type(&variable_declaration.common_fields.r#type),
-                        identifier_to_c_string(identifier).as_ptr(),
-                    );
-                    LLVMBuildStore(*self.builder, self.lower_expression(&initial_value), var);
-                    self.variables.insert(identifier_to_string(identifier), var);
-                }
-
-                *PreOrderVisitorResponse::new(true)
+        let VariableDeclaration {
+            common_fields: _,
+            kind: _,
+            variable_declarator,
+            initial_value,
+        } = variable_declaration;
+        let identifier = &variable_declarator.identifier;
+        unsafe {
+            let variable_type = self.lower_type(&variable_declaration.common_fields.r#type);
+            let var = self.build_entry_alloca(variable_type, &identifier.name);
+            let value = self.lower_expression(initial_value);
+            if !self.is_terminated() {
+                LLVMBuildStore(*self.builder, value, var);
             }
+            self.variables.insert(identifier_to_string(identifier), var);
         }
+
+        *PreOrderVisitorResponse::new(true)
     }
 }
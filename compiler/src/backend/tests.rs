@@ -11,13 +11,16 @@ use serial_test::serial;
 use std::ffi::CString;
 use std::mem::MaybeUninit;
 
+#[allow(clippy::module_inception)]
 mod tests {
     use super::*;
 
     fn interpret(source: &str) -> i64 {
         unsafe {
             let context = LLVMContextCreate();
-            let module = get_llvm_module_from_file(context, &source.to_string());
+            let module = get_llvm_module_from_file(context, source, FileId::default())
+                .unwrap()
+                .0;
 
             LLVM_InitializeNativeTarget();
             LLVM_InitializeNativeAsmPrinter();
@@ -80,6 +83,28 @@ mod tests {
         assert_eq!(result, 4);
     }
 
+    #[test]
+    #[serial]
+    fn if_expressions_with_other_types() {
+        assert_eq!(interpret(test_inputs::IF_EXPRESSION_BOOL), 2);
+        assert_eq!(interpret(test_inputs::IF_EXPRESSION_RECORD), 5);
+        assert_eq!(interpret(test_inputs::IF_EXPRESSION_WITHOUT_ELSE), 7);
+    }
+
+    #[test]
+    #[serial]
+    fn comparison_and_equality() {
+        let result = interpret(test_inputs::COMPARISON_AND_EQUALITY);
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    #[serial]
+    fn not_equals() {
+        let result = interpret(test_inputs::NOT_EQUALS);
+        assert_eq!(result, 110101);
+    }
+
     #[test]
     #[serial]
     fn object_initialization_with_type_arguments_and_field_access() {
@@ -116,6 +141,179 @@ mod tests {
         assert_eq!(result, 1);
     }
 
+    #[test]
+    #[serial]
+    fn function_call_unit() {
+        let result = interpret(test_inputs::FUNCTION_CALL_UNIT);
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    #[serial]
+    fn assignment() {
+        let result = interpret(test_inputs::ASSIGNMENT);
+        assert_eq!(result, 1311);
+    }
+
+    #[test]
+    #[serial]
+    fn while_loops() {
+        let result = interpret(test_inputs::WHILE_LOOPS);
+        assert_eq!(result, 1306);
+    }
+
+    #[test]
+    #[serial]
+    fn for_loops() {
+        let result = interpret(test_inputs::FOR_LOOPS);
+        assert_eq!(result, 4510);
+    }
+
+    #[test]
+    #[serial]
+    fn for_loop_scopes() {
+        let result = interpret(test_inputs::FOR_LOOP_SCOPES);
+        assert_eq!(result, 3106);
+    }
+
+    #[test]
+    #[serial]
+    fn block_scopes() {
+        let result = interpret(test_inputs::BLOCK_SCOPES);
+        assert_eq!(result, 131);
+    }
+
+    #[test]
+    #[serial]
+    fn for_loops_at_type_limits() {
+        // Each loop stops once stepping would go past the largest value of its type.
+        let result = interpret(test_inputs::FOR_LOOPS_AT_TYPE_LIMITS);
+        assert_eq!(result, 203256);
+    }
+
+    #[test]
+    #[serial]
+    fn short_circuit_evaluation() {
+        let result = interpret(test_inputs::SHORT_CIRCUIT_EVALUATION);
+        assert_eq!(result, 210110);
+    }
+
+    #[test]
+    #[serial]
+    fn bitwise_operators() {
+        // Shift counts wrap around at the bit width, so `1 << 65` is 2.
+        let result = interpret(test_inputs::BITWISE_OPERATORS);
+        assert_eq!(result, 812362);
+    }
+
+    #[test]
+    #[serial]
+    fn exponents() {
+        let result = interpret(test_inputs::EXPONENTS);
+        assert_eq!(result, 1808746);
+    }
+
+    #[test]
+    #[serial]
+    fn float_arithmetic() {
+        let result = interpret(test_inputs::FLOAT_ARITHMETIC);
+        assert_eq!(result, 25011250);
+    }
+
+    #[test]
+    #[serial]
+    fn float_comparisons() {
+        let result = interpret(test_inputs::FLOAT_COMPARISONS);
+        assert_eq!(result, 100111);
+    }
+
+    #[test]
+    #[serial]
+    fn float_conversions() {
+        let result = interpret(test_inputs::FLOAT_CONVERSIONS);
+        assert_eq!(result, -27);
+    }
+
+    #[test]
+    #[serial]
+    fn shadowed_builtin_function() {
+        // `i64ToF64` is declared in the program, but `f64ToI64` is still the builtin function.
+        let result = interpret(test_inputs::SHADOWED_BUILTIN_FUNCTION);
+        assert_eq!(result, 5);
+    }
+
+    #[test]
+    #[serial]
+    fn sized_integer_arithmetic() {
+        let result = interpret(test_inputs::SIZED_INTEGER_ARITHMETIC);
+        assert_eq!(result, -2147489183871872956);
+    }
+
+    #[test]
+    #[serial]
+    fn unsigned_integers() {
+        let result = interpret(test_inputs::UNSIGNED_INTEGERS);
+        assert_eq!(result, 1511111);
+    }
+
+    #[test]
+    #[serial]
+    fn integer_casts() {
+        let result = interpret(test_inputs::INTEGER_CASTS);
+        assert_eq!(result, 8534189579);
+    }
+
+    #[test]
+    #[serial]
+    fn constant_integer_wrapping() {
+        // Constant expressions that overflow aren't rejected, and wrap like any other arithmetic.
+        let result = interpret(test_inputs::CONSTANT_INTEGER_WRAPPING);
+        assert_eq!(result, -127956);
+    }
+
+    #[test]
+    #[serial]
+    fn integer_literal_bases() {
+        let result = interpret(test_inputs::INTEGER_LITERAL_BASES);
+        assert_eq!(result, 2146204927);
+    }
+
+    #[test]
+    #[serial]
+    fn diverging_if_expressions() {
+        let result = interpret(test_inputs::DIVERGING_IF_EXPRESSIONS);
+        assert_eq!(result, 7504099);
+    }
+
+    #[test]
+    #[serial]
+    fn diverging_operands() {
+        // Each function returns from inside an argument, a field, an operand or a condition.
+        let result = interpret(test_inputs::DIVERGING_OPERANDS);
+        assert_eq!(result, 5391765432);
+    }
+
+    #[test]
+    #[serial]
+    fn return_statements() {
+        let result = interpret(test_inputs::RETURN_STATEMENTS);
+        assert_eq!(result, -99);
+    }
+
+    #[test]
+    #[serial]
+    fn let_type_inference() {
+        let result = interpret(test_inputs::LET_TYPE_INFERENCE);
+        assert_eq!(result, 6);
+    }
+
+    #[test]
+    #[serial]
+    fn generic_functions() {
+        let result = interpret(test_inputs::GENERIC_FUNCTIONS);
+        assert_eq!(result, 12);
+    }
+
     #[test]
     #[serial]
     fn raw_vec() {
@@ -0,0 +1,120 @@
+use dishsoap_parser::ast::{ParseError, ParseErrorKind, Span, SuggestedFix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    /// A problem that does not stop the program from being compiled.
+    Warning,
+}
+
+/// A message attached to a particular piece of source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: String) -> Self {
+        Label { span, message }
+    }
+}
+
+/// A problem found in the user's program, in a form that does not depend on the phase of
+/// compilation that found it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Identifies the kind of problem. Codes never change meaning once they have been assigned.
+    pub code: &'static str,
+    pub message: String,
+    /// Points at the code that caused the problem.
+    pub primary_label: Label,
+    /// Points at other code that helps explain the problem.
+    pub secondary_labels: Vec<Label>,
+    /// Extra information that is not tied to any particular piece of code.
+    pub notes: Vec<String>,
+    pub suggested_fixes: Vec<SuggestedFix>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: String, primary_label: Label) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message,
+            primary_label,
+            secondary_labels: vec![],
+            notes: vec![],
+            suggested_fixes: vec![],
+        }
+    }
+
+    pub fn warning(code: &'static str, message: String, primary_label: Label) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(code, message, primary_label)
+        }
+    }
+
+    pub fn with_secondary_label(mut self, label: Label) -> Self {
+        self.secondary_labels.push(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn with_suggested_fix(mut self, suggested_fix: SuggestedFix) -> Self {
+        self.suggested_fixes.push(suggested_fix);
+        self
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(e: &ParseError) -> Self {
+        let label = match e.kind {
+            ParseErrorKind::UnexpectedToken | ParseErrorKind::MissingTypeAnnotation => {
+                match e.expected.as_slice() {
+                    [expected] => format!("expected {}", expected),
+                    _ => "unexpected token".to_owned(),
+                }
+            }
+            ParseErrorKind::InvalidToken => "unrecognized character".to_owned(),
+            ParseErrorKind::UnterminatedBlock => "unclosed delimiter".to_owned(),
+            ParseErrorKind::ExpectedExpression => "expected expression".to_owned(),
+            ParseErrorKind::IntegerLiteralOutOfRange => "literal out of range".to_owned(),
+            ParseErrorKind::UnsupportedSyntax(_) => "not supported yet".to_owned(),
+            ParseErrorKind::FloatLiteralOutOfRange => "literal out of range".to_owned(),
+        };
+
+        let code = match e.kind {
+            ParseErrorKind::UnexpectedToken => "E0001",
+            ParseErrorKind::InvalidToken => "E0002",
+            ParseErrorKind::UnterminatedBlock => "E0003",
+            ParseErrorKind::MissingTypeAnnotation => "E0004",
+            ParseErrorKind::ExpectedExpression => "E0005",
+            ParseErrorKind::IntegerLiteralOutOfRange => "E0006",
+            ParseErrorKind::UnsupportedSyntax(_) => "E0007",
+            ParseErrorKind::FloatLiteralOutOfRange => "E0008",
+        };
+
+        let mut diagnostic = Diagnostic::error(code, e.message(), Label::new(e.span, label));
+        if let Some(suggested_fix) = &e.suggested_fix {
+            diagnostic = diagnostic.with_suggested_fix((**suggested_fix).clone());
+        }
+        match e.kind {
+            ParseErrorKind::MissingTypeAnnotation => diagnostic
+                .with_note("parameters must be declared with a type, like `x: P_i64`".to_owned()),
+            ParseErrorKind::IntegerLiteralOutOfRange => {
+                diagnostic.with_note(format!("the largest `P_u64` is {}", u64::MAX))
+            }
+            ParseErrorKind::FloatLiteralOutOfRange => {
+                diagnostic.with_note(format!("the largest `P_f64` is {:e}", f64::MAX))
+            }
+            _ => diagnostic,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+use dishsoap_parser::ast::{SourceMap, Span};
+
+use super::{Diagnostic, Label, Severity};
+
+/// Renders [Diagnostic]s as single-line JSON objects, for tools that consume compiler output.
+///
+/// The format is documented in the README, and fields are only ever added to it, never removed
+/// or changed.
+pub struct JsonDiagnosticRenderer<'a> {
+    source_map: &'a SourceMap,
+}
+
+impl<'a> JsonDiagnosticRenderer<'a> {
+    pub fn new(source_map: &'a SourceMap) -> Self {
+        JsonDiagnosticRenderer { source_map }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        let secondary_labels = diagnostic
+            .secondary_labels
+            .iter()
+            .map(|l| self.render_label(l))
+            .collect::<Vec<String>>();
+        let notes = diagnostic
+            .notes
+            .iter()
+            .map(|n| quote(n))
+            .collect::<Vec<String>>();
+        let suggested_fixes = diagnostic
+            .suggested_fixes
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"message\":{},\"span\":{},\"replacement\":{}}}",
+                    quote(&f.message),
+                    self.render_span(&f.span),
+                    quote(&f.replacement)
+                )
+            })
+            .collect::<Vec<String>>();
+
+        format!(
+            "{{\"severity\":{},\"code\":{},\"message\":{},\"primary_span\":{},\"secondary_labels\":[{}],\"notes\":[{}],\"suggested_fixes\":[{}]}}",
+            quote(severity),
+            quote(diagnostic.code),
+            quote(&diagnostic.message),
+            self.render_label(&diagnostic.primary_label),
+            secondary_labels.join(","),
+            notes.join(","),
+            suggested_fixes.join(",")
+        )
+    }
+
+    /// Renders `label` as a span object with an extra `label` field.
+    fn render_label(&self, label: &Label) -> String {
+        format!(
+            "{{{},\"label\":{}}}",
+            self.render_span_fields(&label.span),
+            quote(&label.message)
+        )
+    }
+
+    fn render_span(&self, span: &Span) -> String {
+        format!("{{{}}}", self.render_span_fields(span))
+    }
+
+    fn render_span_fields(&self, span: &Span) -> String {
+        let file = self.source_map.get_file(span.file_id);
+        let start = file.line_column(span.start);
+        let end = file.line_column(span.end);
+
+        format!(
+            "\"file\":{},\"byte_start\":{},\"byte_end\":{},\"line_start\":{},\"column_start\":{},\"line_end\":{},\"column_end\":{}",
+            quote(&file.name),
+            span.start,
+            span.end,
+            start.line,
+            start.column,
+            end.line,
+            end.column
+        )
+    }
+}
+
+/// Returns `s` as a JSON string literal.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
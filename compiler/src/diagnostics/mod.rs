@@ -0,0 +1,10 @@
+mod diagnostic;
+mod json;
+mod renderer;
+
+pub use diagnostic::*;
+pub use json::*;
+pub use renderer::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use dishsoap_parser::ast::{SourceMap, SourceMapFile};
+
+use super::{Diagnostic, Label, Severity};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+
+/// Renders [Diagnostic]s as text, in the style of:
+///
+/// ```text
+/// error[E0100]: mismatched types: expected `P_bool`, found `P_i64`
+///  --> main.soap:2:21
+///   |
+/// 2 |     let a: P_bool = 1;
+///   |            ------   ^ expected `P_bool`, found `P_i64`
+///   |            |
+///   |            expected due to this
+/// ```
+pub struct DiagnosticRenderer<'a> {
+    source_map: &'a SourceMap,
+    /// Whether to emit ANSI colour codes.
+    colour: bool,
+}
+
+impl<'a> DiagnosticRenderer<'a> {
+    pub fn new(source_map: &'a SourceMap, colour: bool) -> Self {
+        DiagnosticRenderer { source_map, colour }
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.colour {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+
+    fn severity_style(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Error => BOLD_RED,
+            Severity::Warning => BOLD_YELLOW,
+        }
+    }
+
+    fn severity_name(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut output = String::new();
+        let severity_style = Self::severity_style(diagnostic.severity);
+
+        writeln!(
+            output,
+            "{}{}",
+            self.paint(
+                severity_style,
+                &format!(
+                    "{}[{}]",
+                    Self::severity_name(diagnostic.severity),
+                    diagnostic.code
+                )
+            ),
+            self.paint(BOLD, &format!(": {}", diagnostic.message))
+        )
+        .unwrap();
+
+        let primary_span = diagnostic.primary_label.span;
+        let file = self.source_map.get_file(primary_span.file_id);
+        let start = file.line_column(primary_span.start);
+
+        // Group the labels by the line that they start on. Each label is paired with the
+        // character used to underline it.
+        let mut labels_by_line: BTreeMap<usize, Vec<(&Label, char)>> = BTreeMap::new();
+        for (label, underline) in std::iter::once((&diagnostic.primary_label, '^')).chain(
+            diagnostic
+                .secondary_labels
+                .iter()
+                .filter(|l| l.span.file_id == primary_span.file_id)
+                .map(|l| (l, '-')),
+        ) {
+            labels_by_line
+                .entry(file.line_column(label.span.start).line)
+                .or_default()
+                .push((label, underline));
+        }
+
+        let gutter_width = labels_by_line.keys().last().unwrap().to_string().len();
+        let empty_gutter = self.paint(BOLD_BLUE, &format!("{} |", " ".repeat(gutter_width)));
+
+        writeln!(
+            output,
+            "{}{} {}:{}:{}",
+            " ".repeat(gutter_width),
+            self.paint(BOLD_BLUE, "-->"),
+            file.name,
+            start.line,
+            start.column
+        )
+        .unwrap();
+        writeln!(output, "{}", empty_gutter).unwrap();
+
+        let mut previous_line = None;
+        for (line, mut labels) in labels_by_line {
+            if matches!(previous_line, Some(p) if line > p + 1) {
+                writeln!(output, "{}", self.paint(BOLD_BLUE, "...")).unwrap();
+            }
+            previous_line = Some(line);
+
+            let line_text = file.line(line);
+            writeln!(
+                output,
+                "{} {}",
+                self.paint(
+                    BOLD_BLUE,
+                    &format!("{:>width$} |", line, width = gutter_width)
+                ),
+                line_text
+            )
+            .unwrap();
+
+            // Labels further to the right are drawn first, so that the messages of labels further
+            // to the left can hang below them.
+            labels.sort_by_key(|(label, _)| std::cmp::Reverse(label.span.start));
+            let underlines = labels
+                .iter()
+                .map(|(label, _)| underline_columns(file, line, label))
+                .collect::<Vec<(usize, usize)>>();
+
+            // The rightmost label gets its message on the same row as the underlines, and every
+            // other label gets a row of its own below them.
+            let mut row = String::new();
+            let mut column = 0;
+            for ((start_column, width), (_, underline)) in
+                underlines.iter().zip(labels.iter()).rev()
+            {
+                row.push_str(&padding(line_text, column, *start_column));
+                row.push_str(&self.paint_label(
+                    diagnostic.severity,
+                    *underline,
+                    &underline.to_string().repeat(*width),
+                ));
+                column = start_column + width;
+            }
+            let (rightmost_label, rightmost_underline) = labels[0];
+            if !rightmost_label.message.is_empty() {
+                row.push(' ');
+                row.push_str(&self.paint_label(
+                    diagnostic.severity,
+                    rightmost_underline,
+                    &rightmost_label.message,
+                ));
+            }
+            writeln!(output, "{} {}", empty_gutter, row.trim_end()).unwrap();
+
+            for (i, (label, underline)) in labels.iter().enumerate().skip(1) {
+                if label.message.is_empty() {
+                    continue;
+                }
+                let start_column = underlines[i].0;
+                let connector = padding(line_text, 0, start_column);
+                writeln!(
+                    output,
+                    "{} {}{}",
+                    empty_gutter,
+                    connector,
+                    self.paint_label(diagnostic.severity, *underline, "|")
+                )
+                .unwrap();
+                writeln!(
+                    output,
+                    "{} {}{}",
+                    empty_gutter,
+                    connector,
+                    self.paint_label(diagnostic.severity, *underline, &label.message)
+                )
+                .unwrap();
+            }
+        }
+
+        if !diagnostic.notes.is_empty() || !diagnostic.suggested_fixes.is_empty() {
+            writeln!(output, "{}", empty_gutter).unwrap();
+        }
+        for note in diagnostic.notes.iter() {
+            writeln!(
+                output,
+                "{} {} {}",
+                " ".repeat(gutter_width),
+                self.paint(BOLD_BLUE, "="),
+                self.paint(BOLD, &format!("note: {}", note))
+            )
+            .unwrap();
+        }
+
+        for suggested_fix in diagnostic.suggested_fixes.iter() {
+            writeln!(
+                output,
+                "{} {} {}",
+                " ".repeat(gutter_width),
+                self.paint(BOLD_BLUE, "="),
+                self.paint(BOLD, &format!("help: {}", suggested_fix.message))
+            )
+            .unwrap();
+        }
+
+        output
+    }
+
+    fn paint_label(&self, severity: Severity, underline: char, text: &str) -> String {
+        match underline {
+            '^' => self.paint(Self::severity_style(severity), text),
+            _ => self.paint(BOLD_BLUE, text),
+        }
+    }
+}
+
+/// Returns the 0-based column where the underline for `label` starts, along with the width of the
+/// underline. Labels that span several lines are underlined up to the end of their first line.
+fn underline_columns(file: &SourceMapFile, line: usize, label: &Label) -> (usize, usize) {
+    let start_column = file.line_column(label.span.start).column - 1;
+    let end = file.line_column(label.span.end);
+    let end_column = if end.line == line {
+        end.column - 1
+    } else {
+        file.line(line).chars().count()
+    };
+
+    // Empty spans, like the end of the file, still get a single character of underline.
+    (start_column, end_column.saturating_sub(start_column).max(1))
+}
+
+/// Returns the whitespace that lines up text in the columns `from..to` of `line_text`. Tabs are
+/// kept, so that the result lines up however wide the terminal draws them.
+fn padding(line_text: &str, from: usize, to: usize) -> String {
+    let mut characters = line_text.chars().skip(from);
+    (from..to)
+        .map(|_| match characters.next() {
+            Some('\t') => '\t',
+            _ => ' ',
+        })
+        .collect()
+}
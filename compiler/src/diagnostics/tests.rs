@@ -0,0 +1,214 @@
+use super::{Diagnostic, DiagnosticRenderer, JsonDiagnosticRenderer, Label};
+use crate::types::{build_environment_from_top_level_declarations, EnvironmentStack, TypeChecker};
+use dishsoap_parser::ast::{SourceMap, Span};
+use dishsoap_parser::Parser;
+
+#[allow(clippy::module_inception)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn diagnostics(source_map: &SourceMap) -> Vec<Diagnostic> {
+        let file = source_map.get_file(Default::default());
+        let mut parser = Parser::new(&file.source);
+        let untyped_ast = match parser.parse() {
+            Ok(ast) => ast,
+            Err(errors) => return errors.iter().map(Diagnostic::from).collect(),
+        };
+        let mut environment_stack =
+            EnvironmentStack::new(build_environment_from_top_level_declarations(&untyped_ast));
+        let mut type_checker = TypeChecker::new(&untyped_ast, &mut environment_stack);
+        let errors = match type_checker.check(&untyped_ast) {
+            Ok(_) => vec![],
+            Err(errors) => errors.iter().map(Diagnostic::from).collect(),
+        };
+        let warnings = type_checker.take_warnings();
+        errors
+            .into_iter()
+            .chain(warnings.iter().map(Diagnostic::from))
+            .collect()
+    }
+
+    fn render(source: &str) -> String {
+        let mut source_map = SourceMap::new();
+        source_map.add_file("test.soap".to_owned(), source.to_owned());
+        let renderer = DiagnosticRenderer::new(&source_map, false);
+        diagnostics(&source_map)
+            .iter()
+            .map(|d| renderer.render(d))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            render(
+                "func test() -> P_i64 {
+    let a: P_i64 = 1
+    a
+}
+
+func f() -> P_i64 {
+    99999999999999999999
+}
+"
+            ),
+            "error[E0001]: expected `;`, found identifier
+ --> test.soap:3:5
+  |
+3 |     a
+  |     ^ expected `;`
+  |
+  = help: insert `;`
+
+error[E0006]: integer literal is too large to fit in `P_u64`
+ --> test.soap:7:5
+  |
+7 |     99999999999999999999
+  |     ^^^^^^^^^^^^^^^^^^^^ literal out of range
+  |
+  = note: the largest `P_u64` is 18446744073709551615
+"
+        );
+    }
+
+    #[test]
+    fn type_errors() {
+        assert_eq!(
+            render(
+                "class X {a: P_i64, b: P_bool}
+
+func test() -> P_i64 {
+    let a: P_bool = 1;
+    let x: X = X {a: 1, b: true};
+    x.c
+}
+"
+            ),
+            "error[E0100]: mismatched types: expected `P_bool`, found `P_i64`
+ --> test.soap:4:21
+  |
+4 |     let a: P_bool = 1;
+  |         ---------   ^ expected `P_bool`, found `P_i64`
+  |         |
+  |         expected due to this
+
+error[E0104]: no field `c` on type `{a: P_i64, b: P_bool}`
+ --> test.soap:6:5
+  |
+6 |     x.c
+  |     ^ unknown field
+  |
+  = note: available fields are: `a`, `b`
+"
+        );
+    }
+
+    #[test]
+    fn warnings() {
+        assert_eq!(
+            render(
+                "func test() -> P_i64 {
+    return 1;
+    let a: P_i64 = 2;
+    a
+}
+"
+            ),
+            "warning[W0100]: unreachable code
+ --> test.soap:3:5
+  |
+2 |     return 1;
+  |     --------- any code following this `return` is unreachable
+3 |     let a: P_i64 = 2;
+  |     ^^^^^^^^^^^^^^^^^ unreachable code
+"
+        );
+    }
+
+    #[test]
+    fn labels_on_separate_lines() {
+        let source = "func test() -> P_i64 {\n\t1 +\n\n\n\ttrue\n}\n";
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add_file("test.soap".to_owned(), source.to_owned());
+        let diagnostic = Diagnostic::error(
+            "E0100",
+            "mismatched types".to_owned(),
+            Label::new(Span::new(file_id, 31, 35), "found `P_bool`".to_owned()),
+        )
+        .with_secondary_label(Label::new(
+            Span::new(file_id, 24, 27),
+            "this is `P_i64`".to_owned(),
+        ));
+
+        assert_eq!(
+            DiagnosticRenderer::new(&source_map, false).render(&diagnostic),
+            "error[E0100]: mismatched types
+ --> test.soap:5:2
+  |
+2 | \t1 +
+  | \t--- this is `P_i64`
+...
+5 | \ttrue
+  | \t^^^^ found `P_bool`
+"
+        );
+    }
+
+    #[test]
+    fn colour() {
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add_file("test.soap".to_owned(), "1".to_owned());
+        let diagnostic = Diagnostic::error(
+            "E0001",
+            "oops".to_owned(),
+            Label::new(Span::new(file_id, 0, 1), String::new()),
+        );
+
+        let rendered = DiagnosticRenderer::new(&source_map, true).render(&diagnostic);
+        assert!(rendered.starts_with("\x1b[1;31merror[E0001]\x1b[0m\x1b[1m: oops\x1b[0m\n"));
+        assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+    }
+
+    /// Compares the JSON output for `golden/{name}.soap` against `golden/{name}.json`. Run the
+    /// tests with `UPDATE_GOLDEN=1` to rewrite the expected output after an intentional change.
+    fn check_json_golden(name: &str, source: &str, expected: &str) {
+        let mut source_map = SourceMap::new();
+        source_map.add_file(format!("{}.soap", name), source.to_owned());
+        let renderer = JsonDiagnosticRenderer::new(&source_map);
+        let actual = diagnostics(&source_map)
+            .iter()
+            .map(|d| renderer.render(d) + "\n")
+            .collect::<String>();
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            let path = format!(
+                "{}/src/diagnostics/golden/{}.json",
+                env!("CARGO_MANIFEST_DIR"),
+                name
+            );
+            std::fs::write(path, &actual).unwrap();
+        } else {
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn json_parse_errors() {
+        check_json_golden(
+            "parse_errors",
+            include_str!("golden/parse_errors.soap"),
+            include_str!("golden/parse_errors.json"),
+        );
+    }
+
+    #[test]
+    fn json_type_errors() {
+        check_json_golden(
+            "type_errors",
+            include_str!("golden/type_errors.soap"),
+            include_str!("golden/type_errors.json"),
+        );
+    }
+}
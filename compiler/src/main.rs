@@ -1,13 +1,15 @@
 mod backend;
+mod diagnostics;
 mod types;
 mod utils;
 mod visitor;
 
 use std::ffi::CString;
+use std::io::IsTerminal;
 
 use clap::Parser as clap_Parser;
 use dishsoap_parser::{
-    ast::{Node, UntypedNodeCommonFields},
+    ast::{FileId, Node, SourceMap, UntypedNodeCommonFields},
     Parser,
 };
 use llvm_sys::bit_writer::LLVMWriteBitcodeToFile;
@@ -16,8 +18,17 @@ use llvm_sys::prelude::{LLVMContextRef, LLVMModuleRef};
 use types::{build_environment_from_top_level_declarations, EnvironmentStack, TypeChecker};
 // use llvm_sys::target_machine::LLVMGetDefaultTargetTriple;
 use backend::builder::Builder;
+use diagnostics::{Diagnostic, DiagnosticRenderer, JsonDiagnosticRenderer};
 use utils::string_to_c_string;
-use visitor::{PostOrderVisitor, PreOrderVisitor};
+use visitor::PreOrderVisitor;
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum ErrorFormat {
+    /// Source snippets with underlined code, meant to be read by people.
+    Human,
+    /// One JSON object per line, meant to be read by tools. See the README for the format.
+    Json,
+}
 
 #[derive(clap_Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -25,21 +36,51 @@ struct Cli {
     #[clap(long)]
     dump_ir: bool,
 
+    /// How to print errors found in the source file.
+    #[clap(long, value_enum, default_value = "human")]
+    error_format: ErrorFormat,
+
     file_path: String,
 }
 
-fn parse_file(file_content: &String) -> Node<UntypedNodeCommonFields> {
-    let mut parser = Parser::new(file_content);
+fn parse_file(
+    file_content: &str,
+    file_id: FileId,
+) -> Result<Node<UntypedNodeCommonFields>, Vec<Diagnostic>> {
+    let mut parser = Parser::new_with_file_id(file_content, file_id);
 
-    parser.parse()
+    parser
+        .parse()
+        .map_err(|errors| errors.iter().map(Diagnostic::from).collect())
 }
 
-pub fn get_llvm_module_from_file(context: LLVMContextRef, file_content: &String) -> LLVMModuleRef {
-    let untyped_ast = parse_file(file_content);
+/// Compiles the file `file_id`, whose text is `file_content`, into an LLVM module, which is
+/// returned along with any warnings. If the file has errors, they are returned as [Diagnostic]s
+/// instead, followed by any warnings.
+// `context` is only handed to LLVM, which is trusted to use it correctly.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn get_llvm_module_from_file(
+    context: LLVMContextRef,
+    file_content: &str,
+    file_id: FileId,
+) -> Result<(LLVMModuleRef, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let untyped_ast = parse_file(file_content, file_id)?;
     let mut environment_stack =
         EnvironmentStack::new(build_environment_from_top_level_declarations(&untyped_ast));
     let mut type_checker = TypeChecker::new(&untyped_ast, &mut environment_stack);
-    let typed_ast = type_checker.visit(&untyped_ast);
+    let result = type_checker.check(&untyped_ast);
+    let warnings = type_checker
+        .take_warnings()
+        .iter()
+        .map(Diagnostic::from)
+        .collect::<Vec<Diagnostic>>();
+    let typed_ast = result.map_err(|errors| {
+        errors
+            .iter()
+            .map(Diagnostic::from)
+            .chain(warnings.iter().cloned())
+            .collect::<Vec<Diagnostic>>()
+    })?;
 
     unsafe {
         let module_name = CString::new("main").unwrap();
@@ -59,18 +100,57 @@ pub fn get_llvm_module_from_file(context: LLVMContextRef, file_content: &String)
 
         LLVMDisposeBuilder(llvm_builder);
 
-        module
+        Ok((module, warnings))
+    }
+}
+
+/// Prints `diagnostics` to stderr in the requested format.
+fn print_diagnostics(diagnostics: &[Diagnostic], source_map: &SourceMap, format: &ErrorFormat) {
+    match format {
+        ErrorFormat::Human => {
+            let colour = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+            let renderer = DiagnosticRenderer::new(source_map, colour);
+            for diagnostic in diagnostics.iter() {
+                eprintln!("{}", renderer.render(diagnostic));
+            }
+        }
+        ErrorFormat::Json => {
+            let renderer = JsonDiagnosticRenderer::new(source_map);
+            for diagnostic in diagnostics.iter() {
+                eprintln!("{}", renderer.render(diagnostic));
+            }
+        }
     }
 }
 
 fn main() {
     let cli = Cli::parse();
-    let file_content =
-        std::fs::read_to_string(cli.file_path).expect("Error: could not read source file");
+    let file_content = match std::fs::read_to_string(&cli.file_path) {
+        Ok(file_content) => file_content,
+        Err(e) => {
+            eprintln!("error: could not read `{}`: {}", cli.file_path, e);
+            std::process::exit(1)
+        }
+    };
+
+    let mut source_map = SourceMap::new();
+    let file_id = source_map.add_file(cli.file_path.clone(), file_content);
 
     unsafe {
         let context = LLVMContextCreate();
-        let module = get_llvm_module_from_file(context, &file_content);
+        let module =
+            match get_llvm_module_from_file(context, &source_map.get_file(file_id).source, file_id)
+            {
+                Ok((module, warnings)) => {
+                    print_diagnostics(&warnings, &source_map, &cli.error_format);
+                    module
+                }
+                Err(diagnostics) => {
+                    print_diagnostics(&diagnostics, &source_map, &cli.error_format);
+                    LLVMContextDispose(context);
+                    std::process::exit(1)
+                }
+            };
         LLVMSetTarget(
             module,
             string_to_c_string("wasm32-unknown-unknown-wasm".to_owned()).as_ptr(),
@@ -1,8 +1,8 @@
 use dishsoap_parser::ast::{
-    ClassDeclaration, Declaration, FunctionType, Node, RecordType, Type, TypeReference,
-    UntypedNodeCommonFields,
+    ClassDeclaration, Declaration, FunctionType, Identifier, Node, RecordType, Type,
+    TypeReference, UntypedNodeCommonFields,
 };
-use std::{cell::RefCell, collections::HashMap, iter::FromIterator, rc::Rc, ops::Index};
+use std::{cell::RefCell, collections::HashMap, iter::FromIterator, rc::Rc};
 
 pub type Environment = HashMap<String, Type>;
 
@@ -25,14 +25,14 @@ impl EnvironmentStack {
 
     pub fn enter_scope(&mut self) -> &mut Environment {
         self.stack.push(match self.stack.last() {
-            Some(e) => HashMap::from(e.clone()),
+            Some(e) => e.clone(),
             None => HashMap::new(),
         });
 
         self.top()
     }
 
-    pub fn exit_scope(&mut self) -> () {
+    pub fn exit_scope(&mut self) {
         self.stack.pop();
     }
 
@@ -41,54 +41,86 @@ impl EnvironmentStack {
     }
 }
 
+/// A function that is built into the language. Calls to it are lowered by the compiler instead of
+/// calling a function declared in the program. A function that the program declares with the same
+/// name shadows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinFunction {
+    Malloc,
+    Free,
+    MemMove,
+    MemStore,
+    MemLoad,
+    I64ToF64,
+    F64ToI64,
+}
+
+impl BuiltinFunction {
+    const ALL: [BuiltinFunction; 7] = [
+        BuiltinFunction::Malloc,
+        BuiltinFunction::Free,
+        BuiltinFunction::MemMove,
+        BuiltinFunction::MemStore,
+        BuiltinFunction::MemLoad,
+        BuiltinFunction::I64ToF64,
+        BuiltinFunction::F64ToI64,
+    ];
+
+    /// Returns the builtin function called `name`, if there is one.
+    pub fn from_name(name: &str) -> Option<BuiltinFunction> {
+        Self::ALL.iter().copied().find(|b| b.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltinFunction::Malloc => "__malloc",
+            BuiltinFunction::Free => "__free",
+            BuiltinFunction::MemMove => "__memMove",
+            BuiltinFunction::MemStore => "__memStore",
+            BuiltinFunction::MemLoad => "__memLoad",
+            BuiltinFunction::I64ToF64 => "i64ToF64",
+            BuiltinFunction::F64ToI64 => "f64ToI64",
+        }
+    }
+
+    fn function_type(&self) -> FunctionType {
+        match self {
+            BuiltinFunction::Malloc => FunctionType::new(vec![Type::I64Type], Type::I64Type),
+            BuiltinFunction::Free => FunctionType::new(vec![Type::I64Type], Type::UnitType),
+            BuiltinFunction::MemMove | BuiltinFunction::MemStore => FunctionType::new(
+                vec![Type::I64Type, Type::I64Type, Type::I64Type],
+                Type::UnitType,
+            ),
+            BuiltinFunction::MemLoad => {
+                FunctionType::new(vec![Type::I64Type, Type::I64Type], Type::I64Type)
+            }
+            // Integers and floats are never converted implicitly.
+            BuiltinFunction::I64ToF64 => FunctionType::new(vec![Type::I64Type], Type::F64Type),
+            BuiltinFunction::F64ToI64 => FunctionType::new(vec![Type::F64Type], Type::I64Type),
+        }
+    }
+}
+
 pub fn build_environment_from_top_level_declarations(
     source_file_node: &Node<UntypedNodeCommonFields>,
 ) -> Environment {
     // TODO(derekxu16): Add `extern func`s to the language instead.
-    let mut environment: Environment = HashMap::from([
-        (
-            "__malloc".to_string(),
-            Type::FunctionType(Rc::new(FunctionType::new(
-                vec![Type::I64Type],
-                Type::I64Type,
-            )))
-        ),
-        (
-            "__free".to_string(),
-            Type::FunctionType(Rc::new(FunctionType::new(
-                vec![Type::I64Type],
-                Type::UnitType,
-            )))
-        ),
-        (
-            "__memMove".to_string(),
-            Type::FunctionType(Rc::new(FunctionType::new(
-                vec![Type::I64Type, Type::I64Type, Type::I64Type],
-                Type::UnitType,
-            )))
-        ),
-        (
-            "__memStore".to_string(),
-            Type::FunctionType(Rc::new(FunctionType::new(
-                vec![Type::I64Type, Type::I64Type, Type::I64Type],
-                Type::UnitType,
-            )))
-        ),
-        (
-            "__memLoad".to_string(),
-            Type::FunctionType(Rc::new(FunctionType::new(
-                vec![Type::I64Type, Type::I64Type],
-                Type::I64Type,
-            )))
-        ),
-    ]);
+    let mut environment: Environment = BuiltinFunction::ALL
+        .iter()
+        .map(|b| {
+            (
+                b.name().to_owned(),
+                Type::FunctionType(Rc::new(b.function_type())),
+            )
+        })
+        .collect();
     match source_file_node {
         Node::SourceFile(source_file) => source_file.declarations.iter().for_each(|d| match d {
             Declaration::FunctionDeclaration(fd) => {
                 let parameter_types = fd
                     .parameters
                     .iter()
-                    .map(|p| p.variable_declarator.variable_type.clone())
+                    .map(|p| p.variable_declarator.variable_type.clone().unwrap())
                     .collect();
                 environment.insert(
                     fd.identifier.name.clone(),
@@ -106,13 +138,42 @@ pub fn build_environment_from_top_level_declarations(
     environment
 }
 
+/// Returns `r#type` with every reference to one of `type_parameters` replaced by the
+/// corresponding type in `type_arguments`. Type parameters without a corresponding argument are
+/// replaced by [Type::ErrorType], since the type checker reports the wrong number of arguments.
+fn substitute_type_arguments(
+    r#type: &Type,
+    type_parameters: &[Identifier],
+    type_arguments: &[Type],
+) -> Type {
+    match r#type {
+        Type::TypeReference(r) => match type_parameters
+            .iter()
+            .position(|tp| tp.name == r.identifier.name)
+        {
+            Some(i) => type_arguments.get(i).cloned().unwrap_or(Type::ErrorType),
+            None => Type::TypeReference(Rc::new(
+                TypeReference::new(
+                    r.identifier.clone(),
+                    r.type_arguments
+                        .iter()
+                        .map(|a| substitute_type_arguments(a, type_parameters, type_arguments))
+                        .collect(),
+                )
+                .with_span(r.span),
+            )),
+        },
+        _ => r#type.clone(),
+    }
+}
+
 pub fn populate_type_environment_from_top_level_declarations(
     type_reference_to_record_type_converters: Rc<
         RefCell<HashMap<String, Rc<TypeReferenceToRecordTypeConverter>>>,
     >,
     class_name_to_declaration: Rc<RefCell<HashMap<String, ClassDeclaration>>>,
     source_file_node: &Node<UntypedNodeCommonFields>,
-) -> () {
+) {
     fn generate_type_reference_to_record_type_converter(
         type_reference_to_record_type_converters: Rc<
             RefCell<HashMap<String, Rc<TypeReferenceToRecordTypeConverter>>>,
@@ -143,39 +204,35 @@ pub fn populate_type_environment_from_top_level_declarations(
                         Box::new(Type::RecordType(Rc::new(RecordType::new(
                             HashMap::from_iter(class_declaration.fields.iter().map(
                                 |(field_name, field_type)| {
+                                    let field_type = substitute_type_arguments(
+                                        field_type,
+                                        &class_declaration.type_parameters,
+                                        &t.type_arguments,
+                                    );
+                                    // References to unknown classes, and references with the
+                                    // wrong number of type arguments, are reported by the type
+                                    // checker.
+                                    let is_valid_reference = match &field_type {
+                                        Type::TypeReference(r) => class_name_to_declaration
+                                            .borrow()
+                                            .get(&r.identifier.name)
+                                            .is_some_and(|d| {
+                                                d.type_parameters.len() == r.type_arguments.len()
+                                            }),
+                                        _ => false,
+                                    };
                                     (
                                         (*field_name).clone(),
                                         match field_type {
-                                            Type::TypeReference(field_type) => {
-                                                if class_declaration.type_parameters
-                                                    .contains(&(**field_type).identifier)
-                                                {
-                                                    match &t.type_arguments.index(class_declaration
-                                                        .type_parameters
-                                                        .iter()
-                                                        .position(|tp| 
-                                                            tp.name
-                                                                == (**field_type).identifier.name
-                                                        )
-                                                        .unwrap())
-                                                    {
-                                                        Type::TypeReference(tr) => {
-                                                            *(*generate_type_reference_to_record_type_converter(
-                                                                type_reference_to_record_type_converters
-                                                                .clone(),class_name_to_declaration.clone(),
-                                                                        (**field_type).identifier.name.clone()))(tr)
-                                                        }
-                                                        argument_type => (*argument_type).clone(),
-                                                    }
-                                                } else {
-                                                    *(*generate_type_reference_to_record_type_converter(
-                                                        type_reference_to_record_type_converters.clone(),
-                                                        class_name_to_declaration.clone(),
-                                                        (**field_type).identifier.name.clone(),
-                                                    ))(t)
-                                                }
+                                            Type::TypeReference(r) if is_valid_reference => {
+                                                *(*generate_type_reference_to_record_type_converter(
+                                                    type_reference_to_record_type_converters.clone(),
+                                                    class_name_to_declaration.clone(),
+                                                    r.identifier.name.clone(),
+                                                ))(&r)
                                             }
-                                            _ => (*field_type).clone(),
+                                            Type::TypeReference(_) => Type::ErrorType,
+                                            field_type => field_type,
                                         },
                                     )
                                 },
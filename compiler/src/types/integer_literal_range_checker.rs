@@ -0,0 +1,75 @@
+use dishsoap_parser::ast::*;
+
+use super::{TypeChecker, TypeError};
+use crate::visitor::{PreOrderVisitor, PreOrderVisitorResponse};
+
+/// Finds the integer literals in a typed AST that don't fit in their types, like `256u8` or `-129`
+/// where a `P_i8` is expected. Only literals and negated literals are checked. Constant expressions
+/// like `127i8 + 1i8` or `300 as P_u8` aren't folded, so they wrap around at run time without a
+/// diagnostic, like any other arithmetic or cast.
+pub(super) struct IntegerLiteralRangeChecker {
+    pub errors: Vec<TypeError>,
+}
+
+impl IntegerLiteralRangeChecker {
+    pub fn new() -> Self {
+        IntegerLiteralRangeChecker { errors: vec![] }
+    }
+
+    fn check(&mut self, value: i128, r#type: &Type, span: Span) {
+        if let Some((min, max)) = r#type.integer_range() {
+            if value < min || value > max {
+                self.errors.push(TypeError::IntegerLiteralOutOfRange {
+                    r#type: r#type.clone(),
+                    span,
+                });
+            }
+        }
+    }
+}
+
+impl PreOrderVisitor<TypedNodeCommonFields> for IntegerLiteralRangeChecker {
+    fn process_integer_literal(&mut self, integer_literal: &IntegerLiteral<TypedNodeCommonFields>) {
+        self.check(
+            integer_literal.value.into(),
+            &integer_literal.common_fields.r#type,
+            integer_literal.span(),
+        );
+    }
+
+    fn process_prefix_expression(
+        &mut self,
+        prefix_expression: &PrefixExpression<TypedNodeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        // `-128` fits in a `P_i8`, even though `128` doesn't.
+        let negated_value = match prefix_expression.operator {
+            PrefixOperator::Minus => {
+                TypeChecker::constant_integer_value(&prefix_expression.operand)
+            }
+            _ => None,
+        };
+        match negated_value {
+            Some(value) => {
+                self.check(
+                    -value,
+                    &prefix_expression.common_fields.r#type,
+                    prefix_expression.span(),
+                );
+                *PreOrderVisitorResponse::new(true)
+            }
+            None => *PreOrderVisitorResponse::new(false),
+        }
+    }
+
+    fn process_object_literal(&mut self, object_literal: &ObjectLiteral<TypedNodeCommonFields>) {
+        let mut field_values = object_literal.fields.values().collect::<Vec<_>>();
+        field_values.sort_by_key(|value| value.span().start);
+        for value in field_values {
+            self.visit(&Node::Expression(value.clone()));
+        }
+    }
+
+    fn process_field_access(&mut self, field_access: &FieldAccess<TypedNodeCommonFields>) {
+        self.visit(&Node::Expression(field_access.target.clone()));
+    }
+}
@@ -1,8 +1,14 @@
 mod environment;
+mod integer_literal_range_checker;
 mod type_checker;
+mod type_error;
+mod type_warning;
 
 pub use environment::*;
+use integer_literal_range_checker::*;
 pub use type_checker::*;
+pub use type_error::*;
+pub use type_warning::*;
 
 #[cfg(test)]
 mod tests;
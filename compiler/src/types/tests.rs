@@ -1,12 +1,14 @@
 use super::type_checker::TypeChecker;
-use crate::types::{build_environment_from_top_level_declarations, EnvironmentStack};
-use crate::visitor::PostOrderVisitor;
+use crate::types::{
+    build_environment_from_top_level_declarations, EnvironmentStack, TypeError, TypeWarning,
+};
 use dishsoap_parser::ast::*;
 use dishsoap_parser::test_inputs;
 use dishsoap_parser::Parser;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+#[allow(clippy::module_inception)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -28,13 +30,45 @@ mod tests {
 
     fn parse_and_check(source: &str) -> Node<TypedNodeCommonFields> {
         let mut parser = Parser::new(source);
-        let untyped_ast = parser.parse();
+        let untyped_ast = parser.parse().unwrap();
         let mut environment_stack =
             EnvironmentStack::new(build_environment_from_top_level_declarations(&untyped_ast));
         let mut type_checker = TypeChecker::new(&untyped_ast, &mut environment_stack);
-        let typed_ast = type_checker.visit(&untyped_ast).clone();
 
-        typed_ast
+        type_checker.check(&untyped_ast).unwrap()
+    }
+
+    fn check_errors(source: &str) -> Vec<TypeError> {
+        let mut parser = Parser::new(source);
+        let untyped_ast = parser.parse().unwrap();
+        let mut environment_stack =
+            EnvironmentStack::new(build_environment_from_top_level_declarations(&untyped_ast));
+        let mut type_checker = TypeChecker::new(&untyped_ast, &mut environment_stack);
+
+        type_checker.check(&untyped_ast).unwrap_err()
+    }
+
+    fn source_at(source: &str, span: Span) -> &str {
+        &source[span.start..span.end]
+    }
+
+    fn first_initial_value_type(block: &Block<TypedNodeCommonFields>) -> Type {
+        match &block.statements[0] {
+            Statement::Declaration(Declaration::VariableDeclaration(vd)) => {
+                vd.initial_value.get_type().clone()
+            }
+            _ => panic!("expected a variable declaration"),
+        }
+    }
+
+    fn test_body(node: &Node<TypedNodeCommonFields>) -> Rc<Block<TypedNodeCommonFields>> {
+        match node {
+            Node::SourceFile(sf) => match sf.declarations.last() {
+                Some(Declaration::FunctionDeclaration(fd)) => fd.body.clone(),
+                _ => panic!("expected a function declaration"),
+            },
+            _ => panic!("expected a source file"),
+        }
     }
 
     #[test]
@@ -126,6 +160,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn binary_operator_typing() {
+        let arithmetic_operators = ["+", "-", "*", "/", "%", "**", "&", "|", "^", "<<", ">>"];
+        let ordering_operators = ["<", "<=", ">", ">="];
+        let logical_operators = ["&&", "||"];
+        let operands = [
+            ("1", Type::I64Type),
+            ("1.5", Type::F64Type),
+            ("true", Type::BoolType),
+            (
+                "X {a: 1}",
+                Type::RecordType(Rc::new(RecordType::new(HashMap::from([(
+                    "a".to_string(),
+                    Type::I64Type,
+                )])))),
+            ),
+        ];
+
+        for operator in arithmetic_operators
+            .iter()
+            .chain(ordering_operators.iter())
+            .chain(["==", "!="].iter())
+            .chain(logical_operators.iter())
+        {
+            // Comparisons and logical operators produce a `P_bool`.
+            let is_comparison = !arithmetic_operators.contains(operator);
+            let supports = |t: &Type| match *operator {
+                "==" | "!=" => t.is_numeric() || *t == Type::BoolType,
+                "&&" | "||" => *t == Type::BoolType,
+                "+" | "-" | "*" | "/" | "%" | "<" | "<=" | ">" | ">=" => t.is_numeric(),
+                _ => *t == Type::I64Type,
+            };
+
+            for (left, left_type) in operands.iter() {
+                for (right, right_type) in operands.iter() {
+                    let result_type = if is_comparison {
+                        Type::BoolType
+                    } else {
+                        left_type.clone()
+                    };
+                    let source = format!(
+                        "
+class X {{a: P_i64}}
+
+func test() -> P_i64 {{
+    let r: {} = {} {} {};
+    0
+}}
+",
+                        if is_comparison {
+                            "P_bool"
+                        } else if *left_type == Type::F64Type {
+                            "P_f64"
+                        } else {
+                            "P_i64"
+                        },
+                        left,
+                        operator,
+                        right
+                    );
+                    let combination = format!("{} {} {}", left, operator, right);
+
+                    if !supports(left_type) || !supports(right_type) {
+                        let errors = check_errors(&source);
+                        let (operand, operand_type) = if supports(left_type) {
+                            (right, right_type)
+                        } else {
+                            (left, left_type)
+                        };
+                        assert_eq!(errors.len(), 1, "{}", combination);
+                        assert!(
+                            matches!(
+                                &errors[0],
+                                TypeError::UnsupportedOperandType { operand_type: t, .. }
+                                    if t == operand_type
+                            ),
+                            "{}: {:?}",
+                            combination,
+                            errors
+                        );
+                        assert_eq!(source_at(&source, errors[0].span()), *operand);
+                    } else if left_type != right_type {
+                        let errors = check_errors(&source);
+                        assert_eq!(errors.len(), 1, "{}", combination);
+                        assert!(
+                            matches!(&errors[0], TypeError::MismatchedTypes { .. }),
+                            "{}: {:?}",
+                            combination,
+                            errors
+                        );
+                        assert_eq!(source_at(&source, errors[0].span()), *right);
+                    } else {
+                        let body = test_body(&parse_and_check(&source));
+                        assert_eq!(
+                            first_initial_value_type(&body),
+                            result_type,
+                            "{}",
+                            combination
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn if_expressions() {
         let sf_node = parse_and_check(test_inputs::IF_EXPRESSION);
@@ -137,7 +276,7 @@ mod tests {
                     Type::I64Type,
                     Expression::BinaryExpression(Rc::new(
                         BinaryExpression::<TypedNodeCommonFields>::new(
-                            Type::I64Type,
+                            Type::BoolType,
                             Expression::IntegerLiteral(Rc::new(IntegerLiteral::<
                                 TypedNodeCommonFields,
                             >::new(
@@ -168,6 +307,87 @@ mod tests {
         )
     }
 
+    #[test]
+    fn if_expression_types_come_from_branches() {
+        let body = test_body(&parse_and_check(test_inputs::IF_EXPRESSION_BOOL));
+        assert_eq!(first_initial_value_type(&body), Type::BoolType);
+
+        let body = test_body(&parse_and_check(test_inputs::IF_EXPRESSION_RECORD));
+        assert_eq!(
+            first_initial_value_type(&body),
+            Type::RecordType(Rc::new(RecordType::new(HashMap::from([(
+                "c".to_string(),
+                Type::I64Type,
+            )]))))
+        );
+
+        let body = test_body(&parse_and_check(test_inputs::IF_EXPRESSION_WITHOUT_ELSE));
+        assert_eq!(first_initial_value_type(&body), Type::UnitType);
+    }
+
+    #[test]
+    fn if_expression_branches_must_agree() {
+        let source = "
+func test() -> P_i64 {
+    let a: P_i64 = if (true) { 1 } else { false };
+    let b: P_i64 = if (true) { 1 };
+    a
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "`if` and `else` have incompatible types",
+                "`if` and `else` have incompatible types",
+            ]
+        );
+        let spans = errors
+            .iter()
+            .map(|e| match e {
+                TypeError::IncompatibleIfBranches {
+                    span, then_span, ..
+                } => (source_at(source, *then_span), source_at(source, *span)),
+                _ => panic!("expected incompatible if branches, found {:?}", e),
+            })
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(spans, vec![("1", "false"), ("1", "")]);
+        // The missing else block sits right after the then block.
+        assert_eq!(errors[1].span().start, source.find("1 };").unwrap() + 3);
+    }
+
+    #[test]
+    fn if_conditions_must_be_booleans() {
+        let source = "
+class X {a: P_i64}
+
+func test() -> P_i64 {
+    let a: P_i64 = if (5) { 1 } else { 2 };
+    let b: P_i64 = if (X {a: 1}) { 1 } else { 2 };
+    let c: P_i64 = if (1 + 2) { 1 } else { 2 };
+    let d: P_i64 = if (1 < 2) { 1 } else { 2 };
+    let e: P_i64 = if (!true) { 1 } else { 2 };
+    let f: P_i64 = if (missing) { 1 } else { 2 };
+    a
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_bool`, found `P_i64`",
+                "mismatched types: expected `P_bool`, found `{a: P_i64}`",
+                "mismatched types: expected `P_bool`, found `P_i64`",
+                "cannot find variable `missing` in this scope",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "5");
+        assert_eq!(source_at(source, errors[1].span()), "X {a: 1}");
+        assert_eq!(source_at(source, errors[2].span()), "1 + 2");
+    }
+
     #[test]
     fn object_initialization_with_type_arguments_and_field_access() {
         let sf_node = parse_and_check(
@@ -770,4 +990,914 @@ mod tests {
             )))
         );
     }
+
+    #[test]
+    fn typed_nodes_keep_spans() {
+        let source = test_inputs::FUNCTION_CALL_ADD;
+        let sf_node = parse_and_check(source);
+
+        let function_declaration = match &sf_node {
+            Node::SourceFile(sf) => match &sf.declarations[1] {
+                Declaration::FunctionDeclaration(fd) => fd.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let span = function_declaration.span();
+        assert!(source[span.start..span.end].starts_with("func test()"));
+
+        let final_expression = function_declaration.body.final_expression.as_ref().unwrap();
+        let span = final_expression.span();
+        assert_eq!(&source[span.start..span.end], "add(11, 22)");
+    }
+
+    #[test]
+    fn reports_every_type_error() {
+        let source = "
+class X {a: P_i64}
+
+func add(a: P_i64, b: P_i64) -> P_i64 {
+    a + b
+}
+
+func test() -> P_i64 {
+    let a: P_bool = 1;
+    let b: P_i64 = add(1);
+    let c: P_i64 = add(true, 2);
+    let d: P_i64 = X {a: 1}.b;
+    let e: Y = y;
+    let f: P_i64 = a(1);
+    missing(1)
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_bool`, found `P_i64`",
+                "function `add` takes 2 arguments but 1 argument was supplied",
+                "mismatched types: expected `P_i64`, found `P_bool`",
+                "no field `b` on type `{a: P_i64}`",
+                "cannot find type `Y` in this scope",
+                "cannot find variable `y` in this scope",
+                "`a` is not a function, it has type `P_bool`",
+                "cannot find function `missing` in this scope",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "1");
+        assert_eq!(source_at(source, errors[2].span()), "true");
+        assert_eq!(source_at(source, errors[4].span()), "Y");
+    }
+
+    #[test]
+    fn function_bodies_match_return_types() {
+        let source = "
+func a() -> P_bool {
+    1
+}
+
+func b() -> P_i64 {
+    let x: P_i64 = 1;
+}
+
+func c() -> P_unit {
+    let x: P_i64 = 1;
+}
+
+func d() -> Missing {
+    1
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_bool`, found `P_i64`",
+                "function `b` must return a value of type `P_i64`",
+                "cannot find type `Missing` in this scope",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "1");
+        assert_eq!(errors[1].span().end, source.find("\n\nfunc c").unwrap());
+        assert_eq!(source_at(source, errors[1].span()), "}");
+    }
+
+    #[test]
+    fn object_literal_fields_match_class_declarations() {
+        let source = "
+class X<T> {c: T}
+
+class Y<T> {a: P_bool, b: X<T>, e: T}
+
+func test() -> P_i64 {
+    let y: Y<P_i64> = Y<P_i64> {a: 1, b: X<P_bool> {c: true}, d: 3};
+    let x: X<P_i64> = X<P_i64> {c: 4};
+    0
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_bool`, found `P_i64`",
+                "mismatched types: expected `{c: P_i64}`, found `{c: P_bool}`",
+                "no field `d` on type `{a: P_bool, b: {c: P_i64}, e: P_i64}`",
+                "missing field `e` in initializer of `Y`",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "1");
+        assert_eq!(source_at(source, errors[1].span()), "X<P_bool> {c: true}");
+        assert_eq!(source_at(source, errors[2].span()), "d");
+        assert_eq!(errors[3].span().start, source.find("Y<P_i64> {").unwrap());
+        assert_eq!(source_at(source, errors[3].span()), "Y");
+    }
+
+    #[test]
+    fn type_argument_counts_match_type_parameters() {
+        let source = "
+class X<T> {c: T}
+
+class Y<T> {a: X, b: T<P_i64>, d: X<X<P_i64> >}
+
+func f(x: X<P_i64, P_bool>) -> X {
+    X<P_bool> {c: true}
+}
+
+func test() -> P_i64 {
+    let x: X<P_i64> = X {c: 1};
+    let y: Y<P_i64> = Y<P_i64> {a: X<P_i64> {c: 1}, b: 2, d: X<X<P_i64> > {c: X<P_i64> {c: 3}}};
+    0
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "type `X` takes 1 type argument but 0 type arguments were supplied",
+                "type `T` takes 0 type arguments but 1 type argument was supplied",
+                "type `X` takes 1 type argument but 0 type arguments were supplied",
+                "type `X` takes 1 type argument but 2 type arguments were supplied",
+                "type `X` takes 1 type argument but 0 type arguments were supplied",
+            ]
+        );
+        assert_eq!(source_at(source, errors[1].span()), "T<P_i64>");
+        assert_eq!(source_at(source, errors[3].span()), "X<P_i64, P_bool>");
+        assert_eq!(errors[4].span().start, source.find("X {c: 1}").unwrap());
+    }
+
+    #[test]
+    fn loops_need_bool_conditions_and_enclose_break_and_continue() {
+        let source = "
+func test() -> P_i64 {
+    let i = 0;
+    while (i) {
+        i = i + 1;
+        while (i < 10) {
+            continue;
+        }
+        break;
+    }
+    break;
+    if (true) {
+        continue;
+    };
+    i
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_bool`, found `P_i64`",
+                "`break` outside of a loop",
+                "`continue` outside of a loop",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "i");
+        assert_eq!(source_at(source, errors[1].span()), "break;");
+        assert_eq!(source_at(source, errors[2].span()), "continue;");
+    }
+
+    #[test]
+    fn for_loops_need_integer_ranges_and_immutable_variables() {
+        let source = "
+func test() -> P_i64 {
+    let total = 0;
+    for (i in true..3) {}
+    for (i in 0..false step 2) {}
+    for (i in 0..=10) {
+        total = total + i;
+        i = i + 1;
+    }
+    total
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "ranges must be made of integers, found `P_bool`",
+                "mismatched types: expected `P_i64`, found `P_bool`",
+                "cannot assign to loop variable `i`",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "true");
+        assert_eq!(source_at(source, errors[1].span()), "false");
+        assert_eq!(source_at(source, errors[2].span()), "i");
+        assert_eq!(errors[2].span().start, source.find("i = i + 1").unwrap());
+    }
+
+    #[test]
+    fn for_loop_variables_are_scoped_to_the_loop() {
+        let source = "
+func test() -> P_bool {
+    const i = true;
+    for (i in 0..3) {
+        let j = i;
+    }
+    j;
+    i
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec!["cannot find variable `j` in this scope"]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "j");
+        assert_eq!(errors[0].span().start, source.find("j;").unwrap());
+    }
+
+    #[test]
+    fn variables_are_scoped_to_their_block() {
+        let source = "
+func test(c: P_bool) -> P_bool {
+    const x = true;
+    while (c) {
+        let x = 1;
+        let y = x;
+    }
+    if (c) {
+        let z = 2;
+        z;
+    } else {
+        let x = 3;
+    };
+    y;
+    z;
+    x = false;
+    x
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "cannot find variable `y` in this scope",
+                "cannot find variable `z` in this scope",
+                "cannot assign to constant `x`",
+            ]
+        );
+        assert_eq!(errors[0].span().start, source.find("y;").unwrap());
+        assert_eq!(errors[1].span().start, source.rfind("z;").unwrap());
+        assert_eq!(errors[2].span().start, source.find("x = false").unwrap());
+    }
+
+    #[test]
+    fn constant_steps_must_be_positive() {
+        let source = "
+func test(n: P_i64) -> P_i64 {
+    let total = 0;
+    for (i in 0..10 step 1) {}
+    for (i in 0..10 step n) {}
+    for (i in 0..10 step 0) {}
+    for (i in 0..10 step -2) {}
+    total
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec!["loop step must be positive", "loop step must be positive"]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "0");
+        assert_eq!(source_at(source, errors[1].span()), "-2");
+    }
+
+    #[test]
+    fn constant_exponents_must_not_be_negative() {
+        let source = "
+func test(n: P_i64) -> P_i64 {
+    let a = 2 ** 0;
+    let b = 2 ** n;
+    let c = 2 ** -1;
+    let d = 2 ** -(3);
+    a + b + c + d
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec!["negative integer exponent", "negative integer exponent"]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "-1");
+        assert_eq!(source_at(source, errors[1].span()), "-(3)");
+    }
+
+    #[test]
+    fn integer_literals_take_their_type_from_context() {
+        let cases = [
+            ("1", Type::I64Type),
+            ("1u8", Type::U8Type),
+            ("x + 1", Type::U32Type),
+            ("1 + x", Type::U32Type),
+            ("-(1 + 2i16)", Type::I16Type),
+            ("~1 * 2 - 3u64", Type::U64Type),
+            ("if (true) { 1 } else { x }", Type::U32Type),
+            ("x as P_i8", Type::I8Type),
+            ("300 as P_u8", Type::U8Type),
+            ("x < 10", Type::BoolType),
+        ];
+
+        for (expression, expected_type) in cases {
+            let source = format!(
+                "
+func test(x: P_u32) -> P_i64 {{
+    let r = {};
+    0
+}}
+",
+                expression
+            );
+            let body = test_body(&parse_and_check(&source));
+            assert_eq!(
+                first_initial_value_type(&body),
+                expected_type,
+                "{}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn sized_integers_need_explicit_conversions() {
+        let source = "
+func test(x: P_u8, y: P_i32) -> P_i64 {
+    let a = x + y;
+    let b = -x;
+    let c: P_u8 = -1;
+    let d = 1.5 as P_i32;
+    let e = x as P_bool;
+    let f = 1u8 + 1i8;
+    0
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_u8`, found `P_i32`",
+                "cannot negate unsigned integer type `P_u8`",
+                "mismatched types: expected `P_u8`, found `P_i64`",
+                "cannot cast `P_f64` as `P_i32`",
+                "cannot cast `P_u8` as `P_bool`",
+                "mismatched types: expected `P_u8`, found `P_i8`",
+            ]
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| source_at(source, e.span()))
+                .collect::<Vec<&str>>(),
+            vec!["y", "x", "-1", "1.5", "x", "1i8"]
+        );
+    }
+
+    #[test]
+    fn integer_literals_must_fit_their_type() {
+        let source = "
+class Color {r: P_u8}
+
+func test(x: P_u8) -> P_i64 {
+    let a: P_u8 = 256;
+    let b = 300u8;
+    let c: P_i8 = -129;
+    let d = Color {r: 0x1FF};
+    let e = x + 1000;
+    let f = 3000000000i32;
+    let g: P_i8 = -128;
+    let h = 18446744073709551615u64;
+    let i = -9223372036854775808;
+    let j = -0x80i8 + 0b0111_1111i8;
+    9223372036854775808
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "literal out of range for `P_u8`",
+                "literal out of range for `P_u8`",
+                "literal out of range for `P_i8`",
+                "literal out of range for `P_u8`",
+                "literal out of range for `P_u8`",
+                "literal out of range for `P_i32`",
+                "literal out of range for `P_i64`",
+            ]
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| source_at(source, e.span()))
+                .collect::<Vec<&str>>(),
+            vec![
+                "256",
+                "300u8",
+                "-129",
+                "0x1FF",
+                "1000",
+                "3000000000i32",
+                "9223372036854775808"
+            ]
+        );
+    }
+
+    #[test]
+    fn constant_expressions_are_not_range_checked() {
+        // Only literals are checked, so these wrap around at run time.
+        parse_and_check(
+            "
+func test() -> P_i64 {
+    let a = 127i8 + 1i8;
+    let b: P_u8 = 255 + 1;
+    let c = 300 as P_u8;
+    a as P_i64 + b as P_i64 + c as P_i64
+}
+",
+        );
+    }
+
+    #[test]
+    fn fields_of_constants_can_be_assigned_to() {
+        // `const` only stops a variable from referring to a different object.
+        parse_and_check(
+            "
+class Point {x: P_i64}
+
+class Line {start: Point, end: Point}
+
+func test(l: Line) -> P_i64 {
+    const m = l;
+    m.start = m.end;
+    m.end.x = 3;
+    m.start.x
+}
+",
+        );
+    }
+
+    #[test]
+    fn assignments_respect_mutability_and_types() {
+        let source = "
+class X {a: P_i64}
+
+func f() -> P_i64 {
+    1
+}
+
+func test(p: P_i64) -> P_i64 {
+    const c = X {a: 1};
+    let v = 2;
+    c.a = 3;
+    v = c.a;
+    p = 4;
+    c = X {a: 5};
+    v = true;
+    f = 6;
+    1 = 7;
+    let c = 8;
+    c = 9;
+    c
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "cannot assign to constant `c`",
+                "mismatched types: expected `P_i64`, found `P_bool`",
+                "invalid left-hand side of assignment",
+                "invalid left-hand side of assignment",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "c");
+        assert_eq!(
+            errors[0],
+            TypeError::AssignmentToConstant {
+                name: "c".to_owned(),
+                span: errors[0].span(),
+                const_span: Span::new(
+                    Default::default(),
+                    source.find("const").unwrap(),
+                    source.find("const").unwrap() + 5
+                ),
+            }
+        );
+        assert_eq!(source_at(source, errors[2].span()), "f");
+        assert_eq!(source_at(source, errors[3].span()), "1");
+    }
+
+    #[test]
+    fn return_statements_match_return_types() {
+        let source = "
+func f(a: P_bool) -> P_i64 {
+    let b = if (a) {
+        return true;
+    } else {
+        1
+    };
+    return;
+}
+
+func g() -> P_unit {
+    return;
+}
+
+func test() -> P_i64 {
+    0
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_i64`, found `P_bool`",
+                "mismatched types: expected `P_i64`, found `P_unit`",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "true");
+        assert_eq!(source_at(source, errors[1].span()), "return");
+    }
+
+    #[test]
+    fn code_after_return_break_and_continue_is_unreachable() {
+        let source = "
+func f() -> P_i64 {
+    return 1;
+    return 2;
+    let a = 3;
+    a
+}
+
+func g() -> P_unit {
+    while (true) {
+        break;
+        continue;
+    }
+}
+
+func test() -> P_i64 {
+    let a = if (true) {
+        return 1;
+        2
+    } else {
+        3
+    };
+    a
+}
+";
+        let mut parser = Parser::new(source);
+        let untyped_ast = parser.parse().unwrap();
+        let mut environment_stack =
+            EnvironmentStack::new(build_environment_from_top_level_declarations(&untyped_ast));
+        let mut type_checker = TypeChecker::new(&untyped_ast, &mut environment_stack);
+        type_checker.check(&untyped_ast).unwrap();
+        let warnings = type_checker.take_warnings();
+
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|w| match w {
+                    TypeWarning::UnreachableCode {
+                        span,
+                        diverging_span,
+                        ..
+                    } => {
+                        (source_at(source, *span), source_at(source, *diverging_span))
+                    }
+                })
+                .collect::<Vec<(&str, &str)>>(),
+            vec![
+                ("return 2;", "return 1;"),
+                ("continue;", "break;"),
+                ("2", "return 1;")
+            ]
+        );
+    }
+
+    #[test]
+    fn if_expressions_whose_branches_all_return_diverge() {
+        let source = "
+func f(a: P_bool) -> P_i64 {
+    if (a) {
+        return 1;
+    } else {
+        return 2;
+    }
+}
+
+func g(a: P_bool) -> P_i64 {
+    if (a) {
+        return 1;
+    } else {
+        return 2;
+    };
+}
+
+func h(a: P_bool, b: P_bool) -> P_i64 {
+    let c = if (a) {
+        3
+    } else {
+        if (b) {
+            return 1;
+        } else {
+            return 2;
+        }
+    };
+    if (b) {
+        if (a) {
+            return c;
+        } else {
+            return 4;
+        };
+    } else {
+        5
+    }
+}
+
+func test() -> P_i64 {
+    if (true) {
+        return 1;
+    } else {
+        return 2;
+    };
+    3
+}
+";
+        let mut parser = Parser::new(source);
+        let untyped_ast = parser.parse().unwrap();
+        let mut environment_stack =
+            EnvironmentStack::new(build_environment_from_top_level_declarations(&untyped_ast));
+        let mut type_checker = TypeChecker::new(&untyped_ast, &mut environment_stack);
+        let sf_node = type_checker.check(&untyped_ast).unwrap();
+        let warnings = type_checker.take_warnings();
+
+        let declarations = match &sf_node {
+            Node::SourceFile(sf) => sf.declarations.clone(),
+            _ => unreachable!(),
+        };
+        let body = |i: usize| match &declarations[i] {
+            Declaration::FunctionDeclaration(fd) => fd.body.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            *body(0).final_expression.as_ref().unwrap().get_type(),
+            Type::NeverType
+        );
+        assert_eq!(first_initial_value_type(&body(2)), Type::I64Type);
+        assert_eq!(
+            *body(2).final_expression.as_ref().unwrap().get_type(),
+            Type::I64Type
+        );
+
+        // Only the code after the if-statement in `test` is unreachable.
+        assert_eq!(warnings.len(), 1);
+        let TypeWarning::UnreachableCode {
+            span,
+            diverging_span,
+            keyword,
+        } = &warnings[0];
+        assert_eq!(source_at(source, *span), "3");
+        assert!(source_at(source, *diverging_span).starts_with("if (true) {"));
+        assert_eq!(keyword, "if");
+    }
+
+    #[test]
+    fn let_types_are_inferred_from_initial_values() {
+        let sf_node = parse_and_check(test_inputs::LET_TYPE_INFERENCE);
+        let body = test_body(&sf_node);
+
+        let y_type = Type::RecordType(Rc::new(RecordType::new(HashMap::from([(
+            "a".to_owned(),
+            Type::I64Type,
+        )]))));
+        assert_eq!(
+            body.statements
+                .iter()
+                .map(|s| match s {
+                    Statement::Declaration(Declaration::VariableDeclaration(vd)) => (
+                        vd.common_fields.r#type.clone(),
+                        vd.variable_declarator.common_fields.r#type.clone(),
+                    ),
+                    _ => panic!("expected a variable declaration"),
+                })
+                .collect::<Vec<(Type, Type)>>(),
+            vec![
+                (Type::I64Type, Type::I64Type),
+                (y_type.clone(), y_type),
+                (Type::BoolType, Type::BoolType),
+            ]
+        );
+    }
+
+    #[test]
+    fn let_type_annotations_are_checked_when_present() {
+        let source = "
+func test() -> P_i64 {
+    let a = true;
+    let b: P_i64 = a;
+    let c = c;
+    0
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "mismatched types: expected `P_i64`, found `P_bool`",
+                "cannot find variable `c` in this scope",
+            ]
+        );
+        assert_eq!(source_at(source, errors[0].span()), "a");
+    }
+
+    #[test]
+    fn generic_function_calls_are_instantiated() {
+        let sf_node = parse_and_check(test_inputs::GENERIC_FUNCTION_CALL);
+        let body = test_body(&sf_node);
+
+        let explicit_call = match &body.statements[0] {
+            Statement::Declaration(Declaration::VariableDeclaration(vd)) => {
+                match &vd.initial_value {
+                    Expression::BinaryExpression(e) => e.left.clone(),
+                    _ => panic!("expected a binary expression"),
+                }
+            }
+            _ => panic!("expected a variable declaration"),
+        };
+        match &explicit_call {
+            Expression::FunctionCall(c) => {
+                assert_eq!(c.common_fields.r#type, Type::I64Type);
+                assert_eq!(c.type_arguments, vec![Type::I64Type]);
+            }
+            _ => panic!("expected a function call"),
+        }
+        match &body.final_expression {
+            Some(Expression::FunctionCall(c)) => {
+                assert_eq!(c.common_fields.r#type, Type::BoolType);
+                assert_eq!(c.type_arguments, vec![Type::BoolType]);
+            }
+            _ => panic!("expected a function call"),
+        }
+    }
+
+    #[test]
+    fn generic_function_type_arguments_are_checked() {
+        let source = "
+class Box<T> {value: T}
+
+func identity<T>(x: T) -> T {
+    x
+}
+
+func same<T>(a: T, b: T) -> P_bool {
+    a == b
+}
+
+func empty<T>() -> Box<T> {
+    empty<T>()
+}
+
+func test() -> P_i64 {
+    let a: P_i64 = identity<P_i64, P_bool>(1);
+    let b: P_bool = same(1, true);
+    let c: Box<P_i64> = empty();
+    let d: P_bool = identity<P_i64>(1);
+    let e: P_i64 = identity(Box<P_i64> {value: 1}).value;
+    0
+}
+";
+        let errors = check_errors(source);
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "binary operation `==` cannot be applied to type `T`",
+                "function `identity` takes 1 type argument but 2 type arguments were supplied",
+                "mismatched types: expected `P_i64`, found `P_bool`",
+                "cannot infer type argument `T` of function `empty`",
+                "mismatched types: expected `P_bool`, found `P_i64`",
+            ]
+        );
+        assert_eq!(
+            errors[1],
+            TypeError::WrongFunctionTypeArgumentCount {
+                function_name: "identity".to_owned(),
+                expected: 1,
+                found: 2,
+                span: errors[1].span(),
+            }
+        );
+        assert_eq!(source_at(source, errors[2].span()), "true");
+        assert_eq!(source_at(source, errors[3].span()), "empty");
+        assert_eq!(source_at(source, errors[4].span()), "identity<P_i64>(1)");
+    }
+
+    #[test]
+    fn unknown_names_suggest_similar_names() {
+        let errors = check_errors(
+            "
+class Point<Item> {x: Itme, y: P_i64}
+
+func distance(count: P_i64) -> P_i64 {
+    let total: P_i64 = cuont + 1;
+    let p: Pointt<P_i64> = Point<P_i64> {x: 1, y: 2};
+    let q: Vector = Point<P_i64> {x: 1, y: 2};
+    distnce(totl) + zzz
+}
+",
+        );
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| match e {
+                    TypeError::UnknownVariable {
+                        name, suggestion, ..
+                    }
+                    | TypeError::UnknownFunction {
+                        name, suggestion, ..
+                    }
+                    | TypeError::UnknownClass {
+                        name, suggestion, ..
+                    } => (name.as_str(), suggestion.as_deref()),
+                    _ => panic!("expected an unknown name, found {:?}", e),
+                })
+                .collect::<Vec<(&str, Option<&str>)>>(),
+            vec![
+                ("Itme", Some("Item")),
+                ("cuont", Some("count")),
+                ("Pointt", Some("Point")),
+                ("Vector", None),
+                ("totl", Some("total")),
+                ("distnce", Some("distance")),
+                ("zzz", None),
+            ]
+        );
+        assert_eq!(errors[0].message(), "cannot find type `Itme` in this scope");
+    }
+
+    #[test]
+    fn type_errors_do_not_cascade() {
+        let errors = check_errors(
+            "
+func test() -> P_i64 {
+    let a: P_i64 = -(b + 1);
+    let c: P_bool = !a.x;
+    a
+}
+",
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                TypeError::UnknownVariable {
+                    name: "b".to_owned(),
+                    span: errors[0].span(),
+                    suggestion: Some("a".to_owned()),
+                },
+                TypeError::UnknownField {
+                    target_type: Type::I64Type,
+                    field_name: "x".to_owned(),
+                    span: errors[1].span(),
+                },
+            ]
+        );
+    }
 }
@@ -2,16 +2,41 @@ use std::iter::FromIterator;
 use std::rc::Rc;
 use std::{cell::RefCell, collections::HashMap};
 
-use crate::visitor::PostOrderVisitor;
+use crate::utils::find_similar_name;
+use crate::visitor::{PostOrderVisitor, PreOrderVisitor};
 use dishsoap_parser::ast::*;
 
 use super::{
-    populate_type_environment_from_top_level_declarations, EnvironmentStack, TypeEnvironment,
+    populate_type_environment_from_top_level_declarations, EnvironmentStack,
+    IntegerLiteralRangeChecker, TypeEnvironment, TypeError, TypeWarning,
 };
 
 pub struct TypeChecker<'a> {
     type_environment: TypeEnvironment,
     environment_stack: &'a mut EnvironmentStack,
+    /// The errors found so far. Checking continues after an error is found, so that every error
+    /// in a program can be reported at once.
+    diagnostics: Vec<TypeError>,
+    /// The class declarations in the program, by name.
+    class_declarations: HashMap<String, ClassDeclaration>,
+    /// The type parameters of every function in the program, by function name.
+    function_type_parameters: HashMap<String, Vec<Identifier>>,
+    /// The type parameters of the function being checked.
+    type_parameters: Vec<Identifier>,
+    /// The declared return type of the function being checked, if any.
+    return_type: Option<Type>,
+    /// The variables in the function being checked that are declared with `const`, along with the
+    /// span of their `const` keyword.
+    constants: HashMap<String, Span>,
+    /// The variables of the `for` loops enclosing the code being checked, along with the span of
+    /// their declarations. Like constants, they can't be assigned to.
+    loop_variables: HashMap<String, Span>,
+    /// The constants and loop variables outside each block and `for` loop enclosing the code being
+    /// checked, restored once the block or loop has been checked.
+    enclosing_bindings: Vec<(HashMap<String, Span>, HashMap<String, Span>)>,
+    /// How many loops enclose the code being checked.
+    loop_depth: usize,
+    warnings: Vec<TypeWarning>,
 }
 
 impl<'a> TypeChecker<'a> {
@@ -29,18 +54,645 @@ impl<'a> TypeChecker<'a> {
                 ))),
                 _ => unreachable!(),
             };
+        let function_type_parameters = match untyped_ast {
+            Node::SourceFile(source_file) => source_file
+                .declarations
+                .iter()
+                .filter_map(|d| match d {
+                    Declaration::FunctionDeclaration(fd) => {
+                        Some((fd.identifier.name.clone(), fd.type_parameters.clone()))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => unreachable!(),
+        };
         let type_reference_to_record_type_converters = Rc::new(RefCell::new(HashMap::new()));
+        let class_declarations = class_name_to_declaration.borrow().clone();
         populate_type_environment_from_top_level_declarations(
             type_reference_to_record_type_converters.clone(),
             class_name_to_declaration,
-            &untyped_ast,
+            untyped_ast,
         );
         TypeChecker {
             type_environment: TypeEnvironment {
                 type_reference_to_record_type_converters: type_reference_to_record_type_converters
                     .take(),
             },
-            environment_stack: environment_stack,
+            environment_stack,
+            diagnostics: vec![],
+            class_declarations,
+            function_type_parameters,
+            type_parameters: vec![],
+            return_type: None,
+            constants: HashMap::new(),
+            loop_variables: HashMap::new(),
+            enclosing_bindings: vec![],
+            loop_depth: 0,
+            warnings: vec![],
+        }
+    }
+
+    /// Returns the warnings found by [TypeChecker::check]. Warnings are found whether or not the
+    /// program has errors.
+    pub fn take_warnings(&mut self) -> Vec<TypeWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Type checks `untyped_ast`, returning the typed AST if no errors were found, and every error
+    /// that was found otherwise.
+    pub fn check(
+        &mut self,
+        untyped_ast: &Node<UntypedNodeCommonFields>,
+    ) -> Result<Node<TypedNodeCommonFields>, Vec<TypeError>> {
+        self.check_class_declarations();
+        let typed_ast = self.visit(untyped_ast);
+        // The type of an integer literal may come from the code around it, so literals can only be
+        // checked against their types once everything else has been checked.
+        let mut literal_range_checker = IntegerLiteralRangeChecker::new();
+        literal_range_checker.visit(&typed_ast);
+        self.diagnostics.extend(literal_range_checker.errors);
+        if self.diagnostics.is_empty() {
+            Ok(typed_ast)
+        } else {
+            Err(std::mem::take(&mut self.diagnostics))
+        }
+    }
+
+    fn report(&mut self, error: TypeError) {
+        self.diagnostics.push(error);
+    }
+
+    /// Reports an error if `found` is not `expected`. Returns whether the types are compatible.
+    /// `expected_span` points at the code that caused `expected` to be expected, if there is any.
+    fn expect_type(
+        &mut self,
+        expected: &Type,
+        found: &Type,
+        span: Span,
+        expected_span: Option<Span>,
+    ) -> bool {
+        if expected == found
+            || *expected == Type::ErrorType
+            || *found == Type::ErrorType
+            || *found == Type::NeverType
+        {
+            return true;
+        }
+        self.report(TypeError::MismatchedTypes {
+            expected: expected.clone(),
+            found: found.clone(),
+            span,
+            expected_span,
+        });
+        false
+    }
+
+    /// Resolves a reference to a class into the record type that it describes, and a reference to
+    /// a type parameter of the function being checked into a [Type::TypeParameter].
+    fn resolve_type_reference(&mut self, type_reference: &TypeReference) -> Type {
+        let type_parameters = self.type_parameters.clone();
+        if !self.check_type_reference(type_reference, &type_parameters) {
+            return Type::ErrorType;
+        }
+        if type_parameters.contains(&type_reference.identifier) {
+            return Type::TypeParameter(Rc::new(type_reference.identifier.clone()));
+        }
+
+        // Type parameters in the type arguments become fields of the record type, to be replaced
+        // when the function is instantiated.
+        let type_reference = match Self::mark_type_parameters(
+            &Type::TypeReference(Rc::new(type_reference.clone())),
+            &type_parameters,
+        ) {
+            Type::TypeReference(r) => r,
+            _ => unreachable!(),
+        };
+        match self
+            .type_environment
+            .type_reference_to_record_type_converters
+            .get(&type_reference.identifier.name)
+        {
+            Some(converter) => *(**converter)(&type_reference),
+            None => Type::ErrorType,
+        }
+    }
+
+    /// Resolves a type in the signature of a function that hasn't been checked yet. Problems with
+    /// the type are reported when the function itself is checked.
+    fn resolve_signature_type(&self, r#type: &Type) -> Type {
+        match r#type {
+            Type::TypeReference(r) => match self
+                .type_environment
+                .type_reference_to_record_type_converters
+                .get(&r.identifier.name)
+            {
+                Some(converter) => *(**converter)(r),
+                None => Type::ErrorType,
+            },
+            _ => r#type.clone(),
+        }
+    }
+
+    /// Returns `r#type` with every reference to one of `type_parameters`, including those in type
+    /// arguments, replaced by a [Type::TypeParameter].
+    fn mark_type_parameters(r#type: &Type, type_parameters: &[Identifier]) -> Type {
+        match r#type {
+            Type::TypeReference(r)
+                if r.type_arguments.is_empty() && type_parameters.contains(&r.identifier) =>
+            {
+                Type::TypeParameter(Rc::new(r.identifier.clone()))
+            }
+            Type::TypeReference(r) => Type::TypeReference(Rc::new(
+                TypeReference::new(
+                    r.identifier.clone(),
+                    r.type_arguments
+                        .iter()
+                        .map(|a| Self::mark_type_parameters(a, type_parameters))
+                        .collect(),
+                )
+                .with_span(r.span),
+            )),
+            Type::FunctionType(t) => Type::FunctionType(Rc::new(FunctionType::new(
+                t.parameter_types
+                    .iter()
+                    .map(|p| Self::mark_type_parameters(p, type_parameters))
+                    .collect(),
+                Self::mark_type_parameters(&t.return_type, type_parameters),
+            ))),
+            _ => r#type.clone(),
+        }
+    }
+
+    /// Returns the type arguments of a call to the generic function `identifier`, inferred from
+    /// the types of `arguments`, or [None] if some type argument cannot be inferred.
+    fn infer_type_arguments(
+        &mut self,
+        identifier: &Identifier,
+        type_parameters: &[Identifier],
+        parameter_types: &[Type],
+        arguments: &[Expression<TypedNodeCommonFields>],
+    ) -> Option<Vec<Type>> {
+        let mut bindings = HashMap::new();
+        for (parameter_type, argument) in Iterator::zip(parameter_types.iter(), arguments.iter()) {
+            Self::bind_type_parameters(parameter_type, argument.get_type(), &mut bindings);
+        }
+
+        let mut type_arguments = vec![];
+        for type_parameter in type_parameters.iter() {
+            match bindings.remove(&type_parameter.name) {
+                Some(t) => type_arguments.push(t),
+                None => {
+                    // An argument with an error may be what hid the type argument.
+                    if !arguments.iter().any(|a| *a.get_type() == Type::ErrorType) {
+                        self.report(TypeError::CannotInferTypeArgument {
+                            function_name: identifier.name.clone(),
+                            type_parameter: type_parameter.name.clone(),
+                            span: identifier.span,
+                        });
+                    }
+                    return None;
+                }
+            }
+        }
+        Some(type_arguments)
+    }
+
+    /// Binds the type parameters in `parameter_type` to the corresponding parts of
+    /// `argument_type`. The first binding of each type parameter wins, and the argument checks
+    /// that follow report any argument that disagrees with it.
+    fn bind_type_parameters(
+        parameter_type: &Type,
+        argument_type: &Type,
+        bindings: &mut HashMap<String, Type>,
+    ) {
+        match (parameter_type, argument_type) {
+            (_, Type::ErrorType) => {}
+            (Type::TypeParameter(p), _) => {
+                bindings
+                    .entry(p.name.clone())
+                    .or_insert_with(|| argument_type.clone());
+            }
+            (Type::RecordType(p), Type::RecordType(a)) => {
+                for (field_name, field_type) in p.fields.iter() {
+                    if let Some(argument_field_type) = a.fields.get(field_name) {
+                        Self::bind_type_parameters(field_type, argument_field_type, bindings);
+                    }
+                }
+            }
+            (Type::FunctionType(p), Type::FunctionType(a)) => {
+                for (p, a) in Iterator::zip(p.parameter_types.iter(), a.parameter_types.iter()) {
+                    Self::bind_type_parameters(p, a, bindings);
+                }
+                Self::bind_type_parameters(&p.return_type, &a.return_type, bindings);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports every reference to an unknown class in `type_reference`, and every reference that
+    /// supplies the wrong number of type arguments. `type_parameters` are the type parameters in
+    /// scope, which take no type arguments. Returns whether no problems were found.
+    fn check_type_reference(
+        &mut self,
+        type_reference: &TypeReference,
+        type_parameters: &[Identifier],
+    ) -> bool {
+        let name = &type_reference.identifier.name;
+        let expected = if type_parameters.contains(&type_reference.identifier) {
+            Some(0)
+        } else {
+            self.class_declarations
+                .get(name)
+                .map(|d| d.type_parameters.len())
+        };
+        let found = type_reference.type_arguments.len();
+
+        let mut is_valid = match expected {
+            None => {
+                let suggestion = find_similar_name(
+                    name,
+                    self.class_declarations
+                        .keys()
+                        .chain(type_parameters.iter().map(|tp| &tp.name)),
+                );
+                self.report(TypeError::UnknownClass {
+                    name: name.clone(),
+                    span: type_reference.identifier.span,
+                    suggestion,
+                });
+                false
+            }
+            Some(expected) if expected != found => {
+                self.report(TypeError::WrongTypeArgumentCount {
+                    type_name: name.clone(),
+                    expected,
+                    found,
+                    span: type_reference.span,
+                });
+                false
+            }
+            Some(_) => true,
+        };
+        for type_argument in type_reference.type_arguments.iter() {
+            if let Type::TypeReference(r) = type_argument {
+                is_valid &= self.check_type_reference(r, type_parameters);
+            }
+        }
+        is_valid
+    }
+
+    /// Checks the type references in the field types of every class declaration.
+    fn check_class_declarations(&mut self) {
+        let mut class_declarations = self
+            .class_declarations
+            .values()
+            .cloned()
+            .collect::<Vec<ClassDeclaration>>();
+        class_declarations.sort_by_key(|d| d.span.start);
+        for class_declaration in class_declarations.iter() {
+            let mut field_types = class_declaration
+                .fields
+                .values()
+                .filter_map(|t| match t {
+                    Type::TypeReference(r) => Some(r),
+                    _ => None,
+                })
+                .collect::<Vec<&Rc<TypeReference>>>();
+            field_types.sort_by_key(|r| r.span.start);
+            for field_type in field_types {
+                self.check_type_reference(field_type, &class_declaration.type_parameters);
+            }
+        }
+    }
+
+    /// Resolves any reference to a class in `r#type` into the record type that it describes.
+    fn resolve_type(&mut self, r#type: &Type) -> Type {
+        match r#type {
+            Type::TypeReference(r) => self.resolve_type_reference(r),
+            _ => r#type.clone(),
+        }
+    }
+
+    /// Returns whether the binary operator `operator` can be applied to operands of type
+    /// `r#type`. Equality is defined for numbers and booleans, logical operators for booleans,
+    /// arithmetic and ordering for numbers, and every other operator only for integers.
+    fn supports_operand_type(operator: &InfixOperator, r#type: &Type) -> bool {
+        match operator {
+            InfixOperator::DoubleEquals | InfixOperator::NotEquals => {
+                r#type.is_numeric() || *r#type == Type::BoolType
+            }
+            InfixOperator::LogicalAnd | InfixOperator::LogicalOr => *r#type == Type::BoolType,
+            InfixOperator::Plus
+            | InfixOperator::Minus
+            | InfixOperator::Times
+            | InfixOperator::Divide
+            | InfixOperator::Modulo
+            | InfixOperator::LessThan
+            | InfixOperator::LessThanEquals
+            | InfixOperator::GreaterThan
+            | InfixOperator::GreaterThanEquals => r#type.is_numeric(),
+            _ => r#type.is_integer(),
+        }
+    }
+
+    /// Checks the assignment of `value` to `target`, which must be a variable declared with `let`
+    /// or a field, and returns the type of the assignment, which is unit. `const` is shallow: the
+    /// fields of an object can be assigned to even through a constant, since objects are shared by
+    /// reference and `const` only stops the variable from referring to a different object.
+    fn check_assignment(
+        &mut self,
+        target: &Expression<TypedNodeCommonFields>,
+        value: &Expression<TypedNodeCommonFields>,
+    ) -> Type {
+        let is_assignable = match target {
+            Expression::VariableReference(r)
+                if self.loop_variables.contains_key(&r.identifier.name) =>
+            {
+                self.report(TypeError::AssignmentToLoopVariable {
+                    name: r.identifier.name.clone(),
+                    span: target.span(),
+                    declaration_span: self.loop_variables[&r.identifier.name],
+                });
+                false
+            }
+            Expression::VariableReference(r) => match self.constants.get(&r.identifier.name) {
+                Some(const_span) => {
+                    self.report(TypeError::AssignmentToConstant {
+                        name: r.identifier.name.clone(),
+                        span: target.span(),
+                        const_span: *const_span,
+                    });
+                    false
+                }
+                // Functions are not variables, even though they are in the environment.
+                None if matches!(target.get_type(), Type::FunctionType(_)) => {
+                    self.report(TypeError::InvalidAssignmentTarget {
+                        span: target.span(),
+                    });
+                    false
+                }
+                None => true,
+            },
+            // Even the fields of an object referred to by a constant.
+            Expression::FieldAccess(_) => true,
+            _ => {
+                self.report(TypeError::InvalidAssignmentTarget {
+                    span: target.span(),
+                });
+                false
+            }
+        };
+        if is_assignable {
+            self.expect_type(
+                target.get_type(),
+                value.get_type(),
+                value.span(),
+                Some(target.span()),
+            );
+        }
+        Type::UnitType
+    }
+
+    /// Returns the value of `expression` if it is an integer literal, or a negated one.
+    pub(super) fn constant_integer_value(
+        expression: &Expression<TypedNodeCommonFields>,
+    ) -> Option<i128> {
+        match expression {
+            Expression::IntegerLiteral(l) => Some(l.value.into()),
+            Expression::PrefixExpression(e) if e.operator == PrefixOperator::Minus => {
+                Self::constant_integer_value(&e.operand).map(|value| -value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `expression` with the type `expected` if it is an integer expression that is made
+    /// only of integer literals without suffixes, whose type comes from the context they are used
+    /// in, and `expression` itself otherwise.
+    fn with_contextual_type(
+        expression: &Expression<TypedNodeCommonFields>,
+        expected: &Type,
+    ) -> Expression<TypedNodeCommonFields> {
+        if !expected.is_integer() || expression.get_type() == expected {
+            return expression.clone();
+        }
+        Self::with_integer_type(expression, expected).unwrap_or_else(|| expression.clone())
+    }
+
+    /// Returns `expression` with the integer type `integer_type`, if every integer literal that
+    /// determines its type lacks a suffix.
+    fn with_integer_type(
+        expression: &Expression<TypedNodeCommonFields>,
+        integer_type: &Type,
+    ) -> Option<Expression<TypedNodeCommonFields>> {
+        match expression {
+            Expression::IntegerLiteral(l) if l.suffix.is_none() => Some(
+                Expression::IntegerLiteral(Rc::new((**l).clone().with_type(integer_type.clone()))),
+            ),
+            // Unsigned integers can't be negated.
+            Expression::PrefixExpression(e)
+                if e.operator == PrefixOperator::BitNot
+                    || (e.operator == PrefixOperator::Minus
+                        && integer_type.is_signed_integer()) =>
+            {
+                let operand = Self::with_integer_type(&e.operand, integer_type)?;
+                Some(Expression::PrefixExpression(Rc::new(
+                    PrefixExpression::<TypedNodeCommonFields>::new(
+                        integer_type.clone(),
+                        e.operator.clone(),
+                        operand,
+                    )
+                    .with_span(e.span()),
+                )))
+            }
+            Expression::BinaryExpression(e)
+                if e.operator != InfixOperator::Equals
+                    && !e.operator.is_comparison()
+                    && !e.operator.is_logical() =>
+            {
+                let left = Self::with_integer_type(&e.left, integer_type)?;
+                let right = Self::with_integer_type(&e.right, integer_type)?;
+                Some(Expression::BinaryExpression(Rc::new(
+                    BinaryExpression::<TypedNodeCommonFields>::new(
+                        integer_type.clone(),
+                        left,
+                        e.operator.clone(),
+                        right,
+                    )
+                    .with_span(e.span()),
+                )))
+            }
+            // An if-expression whose branches both jump elsewhere has no value to give a type.
+            Expression::IfExpression(e) if e.common_fields.r#type != Type::NeverType => {
+                let then_block = Self::block_with_integer_type(&e.then_block, integer_type)?;
+                let else_block = Self::block_with_integer_type(&e.else_block, integer_type)?;
+                Some(Expression::IfExpression(Rc::new(
+                    IfExpression::<TypedNodeCommonFields>::new(
+                        integer_type.clone(),
+                        e.condition.clone(),
+                        then_block,
+                        else_block,
+                    )
+                    .with_span(e.span()),
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `block` with its final expression given the integer type `integer_type`, like
+    /// [TypeChecker::with_integer_type]. A block that always jumps elsewhere is left as it is.
+    fn block_with_integer_type(
+        block: &Rc<Block<TypedNodeCommonFields>>,
+        integer_type: &Type,
+    ) -> Option<Rc<Block<TypedNodeCommonFields>>> {
+        match &block.final_expression {
+            Some(e) => Some(Rc::new(
+                Block::new(
+                    block.statements.clone(),
+                    Some(Self::with_integer_type(e, integer_type)?),
+                )
+                .with_span(block.span),
+            )),
+            None if Self::block_diverges(block) => Some(block.clone()),
+            None => None,
+        }
+    }
+
+    /// Returns `block` with its final expression given the type `expected` if it is made only of
+    /// integer literals without suffixes, like [TypeChecker::with_contextual_type].
+    fn block_with_contextual_type(
+        block: &Rc<Block<TypedNodeCommonFields>>,
+        expected: &Type,
+    ) -> Rc<Block<TypedNodeCommonFields>> {
+        match &block.final_expression {
+            Some(e) if expected.is_integer() && e.get_type() != expected => {
+                Self::block_with_integer_type(block, expected).unwrap_or_else(|| block.clone())
+            }
+            _ => block.clone(),
+        }
+    }
+
+    /// Gives the operands of a binary expression the same integer type if one of them is made
+    /// only of integer literals without suffixes, so that `a + 1` adds a `P_u8` to `a` if `a` is a
+    /// `P_u8`.
+    fn with_common_integer_type(
+        left: &Expression<TypedNodeCommonFields>,
+        right: &Expression<TypedNodeCommonFields>,
+    ) -> (
+        Expression<TypedNodeCommonFields>,
+        Expression<TypedNodeCommonFields>,
+    ) {
+        let right_with_left_type = Self::with_contextual_type(right, left.get_type());
+        if right_with_left_type.get_type() == left.get_type() {
+            (left.clone(), right_with_left_type)
+        } else {
+            (
+                Self::with_contextual_type(left, right.get_type()),
+                right.clone(),
+            )
+        }
+    }
+
+    /// Returns the type of the value that `block` evaluates to.
+    fn block_type(block: &Block<TypedNodeCommonFields>) -> Type {
+        match &block.final_expression {
+            _ if Self::block_diverges(block) => Type::NeverType,
+            Some(e) => e.get_type().clone(),
+            None => Type::UnitType,
+        }
+    }
+
+    /// Returns the keyword of `statement` if control never flows past it, because it returns
+    /// from the enclosing function or jumps elsewhere in the enclosing loop. An if-expression
+    /// whose branches all do so counts as well.
+    fn diverging_keyword(statement: &Statement<TypedNodeCommonFields>) -> Option<&'static str> {
+        match statement {
+            Statement::ReturnStatement(_) => Some("return"),
+            Statement::BreakStatement(_) => Some("break"),
+            Statement::ContinueStatement(_) => Some("continue"),
+            Statement::ExpressionStatement(s) => match &s.expression {
+                Expression::IfExpression(e) if e.common_fields.r#type == Type::NeverType => {
+                    Some("if")
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns whether `block` always returns or jumps elsewhere before it finishes evaluating.
+    fn block_diverges(block: &Block<TypedNodeCommonFields>) -> bool {
+        block
+            .statements
+            .iter()
+            .any(|s| Self::diverging_keyword(s).is_some())
+            || block
+                .final_expression
+                .as_ref()
+                .is_some_and(|e| *e.get_type() == Type::NeverType)
+    }
+
+    /// Returns the type of the integers in a range from `start` to `end`, which is also the type
+    /// of the variable of a `for` loop over the range. It is the type of `start`, unless `start`
+    /// is made only of integer literals without suffixes and `end` isn't.
+    fn range_type(
+        start: &Expression<TypedNodeCommonFields>,
+        end: &Expression<TypedNodeCommonFields>,
+    ) -> Type {
+        let r#type = Self::with_common_integer_type(start, end)
+            .0
+            .get_type()
+            .clone();
+        if r#type.is_integer() {
+            r#type
+        } else {
+            Type::ErrorType
+        }
+    }
+
+    /// Reports an error if `expression`, part of the range of a `for` loop, is not an integer.
+    fn expect_integer_range_bound(&mut self, expression: &Expression<TypedNodeCommonFields>) {
+        let r#type = expression.get_type();
+        if !r#type.is_integer() && *r#type != Type::ErrorType {
+            self.report(TypeError::NonIntegerRange {
+                found: r#type.clone(),
+                span: expression.span(),
+            });
+        }
+    }
+
+    /// Starts a scope for the variables declared in a block or a `for` loop.
+    fn enter_scope(&mut self) {
+        self.enclosing_bindings
+            .push((self.constants.clone(), self.loop_variables.clone()));
+        self.environment_stack.enter_scope();
+    }
+
+    /// Ends the scope started by [TypeChecker::enter_scope]. The variables declared in it go out of
+    /// scope, and the variables they shadow, along with whether those are constants or loop
+    /// variables, are restored.
+    fn exit_scope(&mut self) {
+        self.environment_stack.exit_scope();
+        (self.constants, self.loop_variables) = self.enclosing_bindings.pop().unwrap();
+    }
+
+    /// Reports an error if a `break` or `continue` statement is not inside a loop.
+    fn check_loop_control(&mut self, keyword: &str, span: Span) {
+        if self.loop_depth == 0 {
+            self.report(TypeError::LoopControlOutsideLoop {
+                keyword: keyword.to_owned(),
+                span,
+            });
+        }
+    }
+
+    /// Returns the span of the code that produces the value of `block`.
+    fn block_result_span(block: &Block<TypedNodeCommonFields>) -> Span {
+        match &block.final_expression {
+            Some(e) => e.span(),
+            None => block.span,
         }
     }
 }
@@ -54,60 +706,216 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
         BooleanLiteral::<TypedNodeCommonFields>::new(*value)
     }
 
-    fn process_integer_literal(&mut self, value: &i64) -> IntegerLiteral<TypedNodeCommonFields> {
+    fn process_integer_literal(
+        &mut self,
+        value: &u64,
+        suffix: &Option<Type>,
+    ) -> IntegerLiteral<TypedNodeCommonFields> {
         IntegerLiteral::<TypedNodeCommonFields>::new(*value)
+            .with_type(suffix.clone().unwrap_or(Type::I64Type))
+            .with_suffix(suffix.clone())
+    }
+
+    fn process_float_literal(&mut self, value: &f64) -> FloatLiteral<TypedNodeCommonFields> {
+        FloatLiteral::<TypedNodeCommonFields>::new(*value)
     }
 
     fn process_object_literal(
         &mut self,
         class: &TypeReference,
         fields: &HashMap<String, Expression<TypedNodeCommonFields>>,
+        field_spans: &HashMap<String, Span>,
     ) -> ObjectLiteral<TypedNodeCommonFields> {
-        let r#type = match self
-            .type_environment
-            .type_reference_to_record_type_converters
-            .get(&class.identifier.name)
-        {
-            Some(converter) => (**converter)(class),
-            None => panic!("Compilation error"),
-        };
-        ObjectLiteral::<TypedNodeCommonFields>::new(*r#type, class.clone(), fields.clone())
+        let r#type = self.resolve_type_reference(class);
+        let mut fields = fields.clone();
+        if let Type::RecordType(record_type) = &r#type {
+            let mut field_values = fields.clone().into_iter().collect::<Vec<_>>();
+            field_values.sort_by_key(|(_, value)| value.span().start);
+            for (field_name, value) in field_values {
+                match record_type.fields.get(&field_name) {
+                    Some(field_type) => {
+                        let value = Self::with_contextual_type(&value, field_type);
+                        self.expect_type(field_type, value.get_type(), value.span(), None);
+                        fields.insert(field_name, value);
+                    }
+                    None => self.report(TypeError::UnknownField {
+                        target_type: r#type.clone(),
+                        span: field_spans
+                            .get(&field_name)
+                            .copied()
+                            .unwrap_or_else(|| value.span()),
+                        field_name,
+                    }),
+                }
+            }
+
+            let mut missing_fields = record_type
+                .fields
+                .keys()
+                .filter(|name| !fields.contains_key(*name))
+                .collect::<Vec<&String>>();
+            missing_fields.sort();
+            for field_name in missing_fields {
+                self.report(TypeError::MissingField {
+                    class_name: class.identifier.name.clone(),
+                    field_name: field_name.clone(),
+                    span: class.identifier.span,
+                });
+            }
+        }
+
+        ObjectLiteral::<TypedNodeCommonFields>::new(r#type, class.clone(), fields)
     }
 
     fn process_variable_reference(
         &mut self,
         identifier: &Identifier,
     ) -> VariableReference<TypedNodeCommonFields> {
-        VariableReference::<TypedNodeCommonFields>::new(
-            self.environment_stack
-                .top()
-                .get(&identifier.name)
-                .unwrap()
-                .clone(),
-            identifier.clone(),
-        )
+        let r#type = match self.environment_stack.top().get(&identifier.name) {
+            Some(t) => t.clone(),
+            None => {
+                let suggestion =
+                    find_similar_name(&identifier.name, self.environment_stack.top().keys());
+                self.report(TypeError::UnknownVariable {
+                    name: identifier.name.clone(),
+                    span: identifier.span,
+                    suggestion,
+                });
+                Type::ErrorType
+            }
+        };
+        VariableReference::<TypedNodeCommonFields>::new(r#type, identifier.clone())
     }
 
     fn process_function_call(
         &mut self,
         identifier: &Identifier,
-        arguments: &Vec<Expression<TypedNodeCommonFields>>,
+        type_arguments: &[Type],
+        arguments: &[Expression<TypedNodeCommonFields>],
     ) -> FunctionCall<TypedNodeCommonFields> {
-        let signature = match self.environment_stack.top().get(&identifier.name).unwrap() {
-            Type::FunctionType(t) => t,
-            _ => unreachable!(),
+        let mut arguments = arguments.to_vec();
+        let explicit_type_arguments = type_arguments
+            .iter()
+            .map(|t| self.resolve_type(t))
+            .collect::<Vec<Type>>();
+        let callee_type_parameters = self
+            .function_type_parameters
+            .get(&identifier.name)
+            .cloned()
+            .unwrap_or_default();
+
+        let signature = match self.environment_stack.top().get(&identifier.name).cloned() {
+            Some(Type::FunctionType(t)) => Some(t),
+            Some(Type::ErrorType) => None,
+            Some(t) => {
+                self.report(TypeError::NotAFunction {
+                    name: identifier.name.clone(),
+                    found: t,
+                    span: identifier.span,
+                });
+                None
+            }
+            None => {
+                let suggestion = find_similar_name(
+                    &identifier.name,
+                    self.environment_stack
+                        .top()
+                        .iter()
+                        .filter(|(_, t)| matches!(t, Type::FunctionType(_)))
+                        .map(|(name, _)| name),
+                );
+                self.report(TypeError::UnknownFunction {
+                    name: identifier.name.clone(),
+                    span: identifier.span,
+                    suggestion,
+                });
+                None
+            }
         };
-        if !(signature.parameter_types.len() == arguments.len()
-            && Iterator::zip(signature.parameter_types.iter(), arguments.iter())
-                .all(|(p, a)| p == a.get_type()))
-        {
-            panic!("Compilation error: incompatible types")
-        }
-        FunctionCall::<TypedNodeCommonFields>::new(
-            signature.return_type.clone(),
-            identifier.clone(),
-            arguments.clone(),
-        )
+
+        // The signature of a generic function refers to its type parameters, which are replaced
+        // by the type arguments of this call.
+        let signature = signature.map(|s| {
+            match Self::mark_type_parameters(&Type::FunctionType(s), &callee_type_parameters) {
+                Type::FunctionType(s) => Rc::new(FunctionType::new(
+                    s.parameter_types
+                        .iter()
+                        .map(|t| self.resolve_signature_type(t))
+                        .collect(),
+                    self.resolve_signature_type(&s.return_type),
+                )),
+                _ => unreachable!(),
+            }
+        });
+        let mut instantiated_type_arguments = vec![];
+        let return_type = match signature {
+            Some(signature) if signature.parameter_types.len() != arguments.len() => {
+                self.report(TypeError::WrongArgumentCount {
+                    function_name: identifier.name.clone(),
+                    expected: signature.parameter_types.len(),
+                    found: arguments.len(),
+                    span: identifier.span,
+                });
+                if callee_type_parameters.is_empty() {
+                    signature.return_type.clone()
+                } else {
+                    Type::ErrorType
+                }
+            }
+            Some(signature) => {
+                let type_arguments =
+                    if explicit_type_arguments.is_empty() && !callee_type_parameters.is_empty() {
+                        self.infer_type_arguments(
+                            identifier,
+                            &callee_type_parameters,
+                            &signature.parameter_types,
+                            &arguments,
+                        )
+                    } else if explicit_type_arguments.len() != callee_type_parameters.len() {
+                        self.report(TypeError::WrongFunctionTypeArgumentCount {
+                            function_name: identifier.name.clone(),
+                            expected: callee_type_parameters.len(),
+                            found: explicit_type_arguments.len(),
+                            span: identifier.span,
+                        });
+                        None
+                    } else {
+                        Some(explicit_type_arguments)
+                    };
+
+                match type_arguments {
+                    Some(type_arguments) => {
+                        let substitutions = Iterator::zip(
+                            callee_type_parameters.iter().map(|p| p.name.clone()),
+                            type_arguments.iter().cloned(),
+                        )
+                        .collect::<HashMap<String, Type>>();
+                        for (parameter_type, argument) in
+                            Iterator::zip(signature.parameter_types.iter(), arguments.iter_mut())
+                        {
+                            let parameter_type =
+                                parameter_type.substitute_type_parameters(&substitutions);
+                            *argument = Self::with_contextual_type(argument, &parameter_type);
+                            self.expect_type(
+                                &parameter_type,
+                                argument.get_type(),
+                                argument.span(),
+                                None,
+                            );
+                        }
+                        instantiated_type_arguments = type_arguments;
+                        signature
+                            .return_type
+                            .substitute_type_parameters(&substitutions)
+                    }
+                    None => Type::ErrorType,
+                }
+            }
+            None => Type::ErrorType,
+        };
+
+        FunctionCall::<TypedNodeCommonFields>::new(return_type, identifier.clone(), arguments)
+            .with_type_arguments(instantiated_type_arguments)
     }
 
     fn process_if_expression(
@@ -116,8 +924,41 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
         then_block: &Rc<Block<TypedNodeCommonFields>>,
         else_block: &Rc<Block<TypedNodeCommonFields>>,
     ) -> IfExpression<TypedNodeCommonFields> {
+        self.expect_type(
+            &Type::BoolType,
+            condition.get_type(),
+            condition.span(),
+            None,
+        );
+
+        // A branch made only of integer literals takes its type from the other branch.
+        let then_block =
+            &Self::block_with_contextual_type(then_block, &Self::block_type(else_block));
+        let else_block =
+            &Self::block_with_contextual_type(else_block, &Self::block_type(then_block));
+        let then_type = Self::block_type(then_block);
+        let else_type = Self::block_type(else_block);
+        // A branch that returns early never produces a value, so it agrees with any other branch.
+        // If both branches do, the if-expression never produces a value either.
+        let r#type = if then_type == else_type
+            || else_type == Type::ErrorType
+            || else_type == Type::NeverType
+        {
+            then_type
+        } else if then_type == Type::ErrorType || then_type == Type::NeverType {
+            else_type
+        } else {
+            self.report(TypeError::IncompatibleIfBranches {
+                then_type,
+                else_type,
+                span: Self::block_result_span(else_block),
+                then_span: Self::block_result_span(then_block),
+            });
+            Type::ErrorType
+        };
+
         IfExpression::<TypedNodeCommonFields>::new(
-            Type::I64Type,
+            r#type,
             condition.clone(),
             then_block.clone(),
             else_block.clone(),
@@ -129,22 +970,23 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
         operator: &PrefixOperator,
         operand: &Expression<TypedNodeCommonFields>,
     ) -> PrefixExpression<TypedNodeCommonFields> {
-        match operator {
-            PrefixOperator::Bang => match operand.get_type() {
-                Type::BoolType => (),
-                _ => panic!("Compilation error: incompatible types"),
-            },
-            PrefixOperator::Minus => match operand.get_type() {
-                Type::I64Type => (),
-                _ => panic!("Compilation error: incompatible types"),
-            },
+        let operand_type = operand.get_type();
+        let r#type = match operator {
+            PrefixOperator::Bang => Type::BoolType,
+            PrefixOperator::Minus if operand_type.is_unsigned_integer() => {
+                self.report(TypeError::UnsignedNegation {
+                    operand_type: operand_type.clone(),
+                    span: operand.span(),
+                });
+                operand_type.clone()
+            }
+            PrefixOperator::Minus if operand_type.is_numeric() => operand_type.clone(),
+            PrefixOperator::BitNot if operand_type.is_integer() => operand_type.clone(),
+            PrefixOperator::Minus | PrefixOperator::BitNot => Type::I64Type,
         };
+        self.expect_type(&r#type, operand_type, operand.span(), None);
 
-        PrefixExpression::<TypedNodeCommonFields>::new(
-            operand.get_type().clone(),
-            operator.clone(),
-            operand.clone(),
-        )
+        PrefixExpression::<TypedNodeCommonFields>::new(r#type, operator.clone(), operand.clone())
     }
 
     fn process_binary_expression(
@@ -153,11 +995,63 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
         operator: &InfixOperator,
         right: &Expression<TypedNodeCommonFields>,
     ) -> BinaryExpression<TypedNodeCommonFields> {
-        if left.get_type() != right.get_type() {
-            panic!("Compilation error")
+        let (left, right) = &Self::with_common_integer_type(left, right);
+        if *operator == InfixOperator::Equals {
+            return BinaryExpression::<TypedNodeCommonFields>::new(
+                self.check_assignment(left, right),
+                left.clone(),
+                operator.clone(),
+                right.clone(),
+            );
         }
+
+        let unsupported_operand = [left, right].iter().copied().find(|operand| {
+            let operand_type = operand.get_type();
+            *operand_type != Type::ErrorType
+                && *operand_type != Type::NeverType
+                && !Self::supports_operand_type(operator, operand_type)
+        });
+        // An operand that never produces a value, like an if-expression whose branches all
+        // return, takes the type of the other operand.
+        let (typed_operand, other_operand) = if *left.get_type() == Type::NeverType {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        let operands_agree = match unsupported_operand {
+            Some(operand) => {
+                self.report(TypeError::UnsupportedOperandType {
+                    operator: operator.clone(),
+                    operand_type: operand.get_type().clone(),
+                    span: operand.span(),
+                });
+                false
+            }
+            None => self.expect_type(
+                typed_operand.get_type(),
+                other_operand.get_type(),
+                other_operand.span(),
+                Some(typed_operand.span()),
+            ),
+        };
+
+        if *operator == InfixOperator::Exponent
+            && Self::constant_integer_value(right).is_some_and(|value| value < 0)
+        {
+            self.report(TypeError::NegativeExponent { span: right.span() });
+        }
+
+        // Comparisons and logical operators always produce a `P_bool`, even when their operands
+        // are wrong.
+        let r#type = if operator.is_comparison() || operator.is_logical() {
+            Type::BoolType
+        } else if operands_agree {
+            typed_operand.get_type().clone()
+        } else {
+            Type::ErrorType
+        };
         BinaryExpression::<TypedNodeCommonFields>::new(
-            left.get_type().clone(),
+            r#type,
             left.clone(),
             operator.clone(),
             right.clone(),
@@ -167,53 +1061,85 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
     fn process_field_access(
         &mut self,
         target: &Expression<TypedNodeCommonFields>,
-        field_name: &String,
+        field_name: &str,
     ) -> FieldAccess<TypedNodeCommonFields> {
-        let target_type = match target.get_type() {
-            Type::RecordType(r) => &**r,
-            _ => unreachable!(),
-        };
-        let field_type = match target_type.fields.get(field_name) {
-            Some(t) => t,
-            None => panic!("Compilation error"),
+        let field_type = match target.get_type() {
+            Type::RecordType(r) if r.fields.contains_key(field_name) => {
+                r.fields[field_name].clone()
+            }
+            Type::ErrorType => Type::ErrorType,
+            target_type => {
+                self.report(TypeError::UnknownField {
+                    target_type: target_type.clone(),
+                    field_name: field_name.to_owned(),
+                    span: target.span(),
+                });
+                Type::ErrorType
+            }
         };
 
-        FieldAccess::<TypedNodeCommonFields>::new(
-            field_type.clone(),
-            target.clone(),
-            field_name.clone(),
+        FieldAccess::<TypedNodeCommonFields>::new(field_type, target.clone(), field_name.to_owned())
+    }
+
+    fn process_cast_expression(
+        &mut self,
+        expression: &Expression<TypedNodeCommonFields>,
+        target_type: &Type,
+    ) -> CastExpression<TypedNodeCommonFields> {
+        let r#type = self.resolve_type(target_type);
+        let expression_type = expression.get_type();
+        let is_valid = |t: &Type| t.is_integer() || *t == Type::ErrorType;
+        if !is_valid(expression_type) || !is_valid(&r#type) {
+            self.report(TypeError::InvalidCast {
+                from: expression_type.clone(),
+                to: r#type.clone(),
+                span: expression.span(),
+            });
+        }
+
+        CastExpression::<TypedNodeCommonFields>::new(
+            r#type,
+            expression.clone(),
+            target_type.clone(),
         )
     }
 
     fn process_variable_declarator(
         &mut self,
         identifier: &Identifier,
-        variable_type: &Type,
+        variable_type: Option<&Type>,
     ) -> VariableDeclarator<TypedNodeCommonFields> {
-        // TODO(derekxu16): This should probably happen in a process_type_reference method instead
-        // of here.
-        let r#type = match variable_type {
-            Type::TypeReference(r) => match self
-                .type_environment
-                .type_reference_to_record_type_converters
-                .get(&(**r).identifier.name)
-            {
-                Some(converter) => *(**converter)(r),
-                None => panic!("Compilation error"),
-            },
-            _ => (*variable_type).clone(),
-        };
-        VariableDeclarator::<TypedNodeCommonFields>::new(
-            r#type,
-            identifier.clone(),
-            variable_type.clone(),
-        )
+        match variable_type {
+            Some(variable_type) => {
+                // TODO(derekxu16): This should probably happen in a process_type_reference method
+                // instead of here.
+                let r#type = self.resolve_type(variable_type);
+                VariableDeclarator::<TypedNodeCommonFields>::new(
+                    r#type,
+                    identifier.clone(),
+                    variable_type.clone(),
+                )
+            }
+            // The type is inferred from the initial value in process_variable_declaration.
+            None => VariableDeclarator::<TypedNodeCommonFields>::new_without_type_annotation(
+                Type::ErrorType,
+                identifier.clone(),
+            ),
+        }
     }
 
     fn after_process_variable_declarator(
         &mut self,
         variable_declarator: &VariableDeclarator<TypedNodeCommonFields>,
-    ) -> () {
+    ) {
+        // The new variable shadows any constant or loop variable with the same name.
+        self.constants.remove(&variable_declarator.identifier.name);
+        self.loop_variables
+            .remove(&variable_declarator.identifier.name);
+        // A variable without a type annotation is only added once its type has been inferred.
+        if variable_declarator.variable_type.is_none() {
+            return;
+        }
         self.environment_stack.top().insert(
             variable_declarator.identifier.name.clone(),
             variable_declarator.common_fields.r#type.clone(),
@@ -230,34 +1156,176 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
         )
     }
 
+    fn before_process_block(&mut self, _block: &Block<UntypedNodeCommonFields>) {
+        self.enter_scope();
+    }
+
     fn process_block(
         &mut self,
-        statements: &Vec<Statement<TypedNodeCommonFields>>,
+        statements: &[Statement<TypedNodeCommonFields>],
         final_expression: &Option<Expression<TypedNodeCommonFields>>,
     ) -> Block<TypedNodeCommonFields> {
-        Block::<TypedNodeCommonFields>::new(statements.clone(), final_expression.clone())
+        self.exit_scope();
+        let first_diverging_statement = statements
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| Self::diverging_keyword(s).map(|keyword| (i, keyword)));
+        if let Some((i, keyword)) = first_diverging_statement {
+            let unreachable_span = match statements.get(i + 1) {
+                Some(s) => Some(s.span()),
+                None => final_expression.as_ref().map(|e| e.span()),
+            };
+            if let Some(span) = unreachable_span {
+                self.warnings.push(TypeWarning::UnreachableCode {
+                    span,
+                    diverging_span: statements[i].span(),
+                    keyword: keyword.to_owned(),
+                });
+            }
+        }
+
+        Block::<TypedNodeCommonFields>::new(statements.to_vec(), final_expression.clone())
+    }
+
+    fn process_expression_statement(
+        &mut self,
+        expression: &Expression<TypedNodeCommonFields>,
+    ) -> ExpressionStatement<TypedNodeCommonFields> {
+        ExpressionStatement::new(expression.clone())
     }
 
     fn process_return_statement(
         &mut self,
         expression: &Expression<TypedNodeCommonFields>,
     ) -> ReturnStatement<TypedNodeCommonFields> {
-        ReturnStatement::new(expression.clone())
+        let expression = match self.return_type.clone() {
+            Some(return_type) => {
+                let expression = Self::with_contextual_type(expression, &return_type);
+                self.expect_type(&return_type, expression.get_type(), expression.span(), None);
+                expression
+            }
+            None => expression.clone(),
+        };
+        ReturnStatement::new(expression)
+    }
+
+    fn before_process_while_statement(
+        &mut self,
+        _while_statement: &WhileStatement<UntypedNodeCommonFields>,
+    ) {
+        self.loop_depth += 1;
+    }
+
+    fn process_while_statement(
+        &mut self,
+        condition: &Expression<TypedNodeCommonFields>,
+        body: &Rc<Block<TypedNodeCommonFields>>,
+    ) -> WhileStatement<TypedNodeCommonFields> {
+        self.loop_depth -= 1;
+        self.expect_type(
+            &Type::BoolType,
+            condition.get_type(),
+            condition.span(),
+            None,
+        );
+        WhileStatement::new(condition.clone(), body.clone())
+    }
+
+    fn before_process_for_body(
+        &mut self,
+        variable_declarator: &VariableDeclarator<TypedNodeCommonFields>,
+        start: &Expression<TypedNodeCommonFields>,
+        end: &Expression<TypedNodeCommonFields>,
+    ) {
+        // The loop variable is only in scope inside the loop.
+        self.enter_scope();
+        let name = variable_declarator.identifier.name.clone();
+        self.environment_stack
+            .top()
+            .insert(name.clone(), Self::range_type(start, end));
+        self.constants.remove(&name);
+        self.loop_variables.insert(name, variable_declarator.span());
+        self.loop_depth += 1;
+    }
+
+    fn process_for_statement(
+        &mut self,
+        for_statement: &ForStatement<UntypedNodeCommonFields>,
+        variable_declarator: &Rc<VariableDeclarator<TypedNodeCommonFields>>,
+        start: &Expression<TypedNodeCommonFields>,
+        end: &Expression<TypedNodeCommonFields>,
+        step: &Option<Expression<TypedNodeCommonFields>>,
+        body: &Rc<Block<TypedNodeCommonFields>>,
+    ) -> ForStatement<TypedNodeCommonFields> {
+        self.loop_depth -= 1;
+        self.exit_scope();
+
+        let r#type = Self::range_type(start, end);
+        let start = &Self::with_contextual_type(start, &r#type);
+        let end = &Self::with_contextual_type(end, &r#type);
+        let step = &step
+            .as_ref()
+            .map(|e| Self::with_contextual_type(e, &r#type));
+        self.expect_integer_range_bound(start);
+        for e in std::iter::once(end).chain(step.iter()) {
+            if r#type == Type::ErrorType {
+                self.expect_integer_range_bound(e);
+            } else {
+                self.expect_type(&r#type, e.get_type(), e.span(), Some(start.span()));
+            }
+        }
+        if let Some(step) = step {
+            if Self::constant_integer_value(step).is_some_and(|value| value <= 0) {
+                self.report(TypeError::NonPositiveStep { span: step.span() });
+            }
+        }
+
+        ForStatement::new(
+            Rc::new(
+                VariableDeclarator::<TypedNodeCommonFields>::new_without_type_annotation(
+                    r#type,
+                    variable_declarator.identifier.clone(),
+                )
+                .with_span(variable_declarator.span()),
+            ),
+            start.clone(),
+            end.clone(),
+            body.clone(),
+        )
+        .with_kind(for_statement.kind)
+        .with_step(step.clone())
+    }
+
+    fn process_break_statement(&mut self, break_statement: &BreakStatement) -> BreakStatement {
+        self.check_loop_control("break", break_statement.span);
+        break_statement.clone()
+    }
+
+    fn process_continue_statement(
+        &mut self,
+        continue_statement: &ContinueStatement,
+    ) -> ContinueStatement {
+        self.check_loop_control("continue", continue_statement.span);
+        continue_statement.clone()
     }
 
     fn before_process_function_declaration(
         &mut self,
-        _function_declaration: &FunctionDeclaration<UntypedNodeCommonFields>,
-    ) -> () {
+        function_declaration: &FunctionDeclaration<UntypedNodeCommonFields>,
+    ) {
         self.environment_stack.enter_scope();
+        self.constants.clear();
+        self.loop_variables.clear();
+        self.type_parameters = function_declaration.type_parameters.clone();
+        self.return_type = Some(self.resolve_type(&function_declaration.return_type));
     }
 
     fn process_function_declaration(
         &mut self,
-        _function_declaration: &FunctionDeclaration<UntypedNodeCommonFields>,
+        function_declaration: &FunctionDeclaration<UntypedNodeCommonFields>,
         identifier: &Identifier,
         return_type: &Type,
-        parameters: &Vec<Rc<Parameter<TypedNodeCommonFields>>>,
+        parameters: &[Rc<Parameter<TypedNodeCommonFields>>],
         body: &Rc<Block<TypedNodeCommonFields>>,
     ) -> FunctionDeclaration<TypedNodeCommonFields> {
         let parameter_types = parameters
@@ -265,35 +1333,50 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
             .map(|p| p.variable_declarator.common_fields.r#type.clone())
             .collect();
 
+        let resolved_return_type = self.return_type.take().unwrap();
+        let body = &Self::block_with_contextual_type(body, &resolved_return_type);
+        match &body.final_expression {
+            Some(final_expression) => {
+                self.expect_type(
+                    &resolved_return_type,
+                    final_expression.get_type(),
+                    final_expression.span(),
+                    None,
+                );
+            }
+            // A body that always returns already returns a value.
+            None if Self::block_diverges(body) => {}
+            None if resolved_return_type == Type::UnitType
+                || resolved_return_type == Type::ErrorType => {}
+            None => self.report(TypeError::MissingReturnValue {
+                function_name: identifier.name.clone(),
+                return_type: resolved_return_type.clone(),
+                // The closing brace of the body.
+                span: Span::new(body.span.file_id, body.span.end - 1, body.span.end),
+            }),
+        }
+
         let r#type = Type::FunctionType(Rc::new(FunctionType::new(
             parameter_types,
-            match return_type {
-                Type::TypeReference(r) => match self
-                    .type_environment
-                    .type_reference_to_record_type_converters
-                    .get(&(**r).identifier.name)
-                {
-                    Some(converter) => *(**converter)(r),
-                    None => panic!("Compilation error"),
-                },
-                _ => (*return_type).clone(),
-            },
+            resolved_return_type,
         )));
 
         FunctionDeclaration::<TypedNodeCommonFields>::new(
             r#type,
             identifier.clone(),
             return_type.clone(),
-            parameters.clone(),
+            parameters.to_vec(),
             body.clone(),
         )
+        .with_type_parameters(function_declaration.type_parameters.clone())
     }
 
     fn after_process_function_declaration(
         &mut self,
         function_declaration: &FunctionDeclaration<TypedNodeCommonFields>,
-    ) -> () {
+    ) {
         self.environment_stack.exit_scope();
+        self.type_parameters.clear();
 
         self.environment_stack.top().insert(
             function_declaration.identifier.name.clone(),
@@ -303,20 +1386,68 @@ impl<'a> PostOrderVisitor<UntypedNodeCommonFields, TypedNodeCommonFields> for Ty
 
     fn process_variable_declaration(
         &mut self,
-        _variable_declaration: &VariableDeclaration<UntypedNodeCommonFields>,
+        variable_declaration: &VariableDeclaration<UntypedNodeCommonFields>,
         variable_declarator: &Rc<VariableDeclarator<TypedNodeCommonFields>>,
         initial_value: &Expression<TypedNodeCommonFields>,
     ) -> VariableDeclaration<TypedNodeCommonFields> {
-        let initial_value_type = initial_value.get_type();
-        if variable_declarator.common_fields.r#type != *initial_value_type {
-            panic!("Compilation error: incompatible types")
+        if variable_declarator.variable_type.is_none() {
+            let inferred_type = initial_value.get_type().clone();
+            return VariableDeclaration::<TypedNodeCommonFields>::new(
+                inferred_type.clone(),
+                Rc::new(
+                    VariableDeclarator::<TypedNodeCommonFields>::new_without_type_annotation(
+                        inferred_type,
+                        variable_declarator.identifier.clone(),
+                    )
+                    .with_span(variable_declarator.span()),
+                ),
+                initial_value.clone(),
+            )
+            .with_kind(variable_declaration.kind);
         }
 
+        let declared_type = &variable_declarator.common_fields.r#type;
+        let initial_value = &Self::with_contextual_type(initial_value, declared_type);
+        self.expect_type(
+            declared_type,
+            initial_value.get_type(),
+            initial_value.span(),
+            Some(variable_declarator.span()),
+        );
+
         VariableDeclaration::<TypedNodeCommonFields>::new(
-            initial_value_type.clone(),
+            declared_type.clone(),
             variable_declarator.clone(),
             initial_value.clone(),
         )
+        .with_kind(variable_declaration.kind)
+    }
+
+    fn after_process_variable_declaration(
+        &mut self,
+        variable_declaration: &VariableDeclaration<TypedNodeCommonFields>,
+    ) {
+        let variable_declarator = &variable_declaration.variable_declarator;
+        if variable_declarator.variable_type.is_none() {
+            self.environment_stack.top().insert(
+                variable_declarator.identifier.name.clone(),
+                variable_declarator.common_fields.r#type.clone(),
+            );
+        }
+
+        let name = variable_declarator.identifier.name.clone();
+        match variable_declaration.kind {
+            VariableDeclarationKind::Const => {
+                let span = variable_declaration.span();
+                self.constants.insert(
+                    name,
+                    Span::new(span.file_id, span.start, span.start + "const".len()),
+                );
+            }
+            VariableDeclarationKind::Let => {
+                self.constants.remove(&name);
+            }
+        }
     }
 
     fn process_source_file(
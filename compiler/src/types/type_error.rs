@@ -0,0 +1,533 @@
+use std::fmt;
+
+use dishsoap_parser::ast::{InfixOperator, Span, SuggestedFix, Type};
+
+use crate::diagnostics::{Diagnostic, Label};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeError {
+    /// An expression has a different type from the one that its context requires.
+    MismatchedTypes {
+        expected: Type,
+        found: Type,
+        span: Span,
+        /// The code that caused `expected` to be expected, if there is any.
+        expected_span: Option<Span>,
+    },
+    UnknownVariable {
+        name: String,
+        span: Span,
+        /// A name in scope that `name` is likely a misspelling of.
+        suggestion: Option<String>,
+    },
+    UnknownFunction {
+        name: String,
+        span: Span,
+        /// A function in scope that `name` is likely a misspelling of.
+        suggestion: Option<String>,
+    },
+    /// A type reference names neither a class nor a type parameter in scope.
+    UnknownClass {
+        name: String,
+        span: Span,
+        /// A class or type parameter that `name` is likely a misspelling of.
+        suggestion: Option<String>,
+    },
+    /// A field was accessed on a value whose type has no field with that name, or an object literal
+    /// initialized a field that its class doesn't declare. In object literals, `span` is the
+    /// field's name.
+    UnknownField {
+        target_type: Type,
+        field_name: String,
+        span: Span,
+    },
+    WrongArgumentCount {
+        function_name: String,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    /// A variable that does not hold a function was called.
+    NotAFunction {
+        name: String,
+        found: Type,
+        span: Span,
+    },
+    /// The branches of an if-expression evaluate to values of different types.
+    IncompatibleIfBranches {
+        then_type: Type,
+        else_type: Type,
+        /// The code that produces the value of the else branch.
+        span: Span,
+        /// The code that produces the value of the then branch.
+        then_span: Span,
+    },
+    /// An operand of a binary expression has a type that the operator is not defined for.
+    UnsupportedOperandType {
+        operator: InfixOperator,
+        operand_type: Type,
+        span: Span,
+    },
+    /// A function with a non-unit return type has a body without a final expression.
+    MissingReturnValue {
+        function_name: String,
+        return_type: Type,
+        /// The end of the body of the function.
+        span: Span,
+    },
+    /// An object literal does not initialize a field of its class.
+    MissingField {
+        class_name: String,
+        field_name: String,
+        /// The class name in the object literal.
+        span: Span,
+    },
+    /// A reference to a class or type parameter supplies the wrong number of type arguments.
+    WrongTypeArgumentCount {
+        type_name: String,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    /// A function call supplies the wrong number of type arguments.
+    WrongFunctionTypeArgumentCount {
+        function_name: String,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    /// A type argument of a call to a generic function was not supplied and could not be inferred
+    /// from the arguments.
+    CannotInferTypeArgument {
+        function_name: String,
+        type_parameter: String,
+        span: Span,
+    },
+    /// A variable declared with `const` is assigned to.
+    AssignmentToConstant {
+        name: String,
+        span: Span,
+        /// The `const` keyword that the variable is declared with.
+        const_span: Span,
+    },
+    /// The left-hand side of an assignment is neither a variable nor a field.
+    InvalidAssignmentTarget { span: Span },
+    /// A `break` or `continue` statement appears outside of a loop.
+    LoopControlOutsideLoop { keyword: String, span: Span },
+    /// A bound or the step of the range of a `for` loop is not an integer.
+    NonIntegerRange { found: Type, span: Span },
+    /// The variable of a `for` loop is assigned to inside the loop.
+    AssignmentToLoopVariable {
+        name: String,
+        span: Span,
+        /// Where the variable is declared, in the head of the loop.
+        declaration_span: Span,
+    },
+    /// An integer is raised to a constant negative power, which is not an integer.
+    NegativeExponent { span: Span },
+    /// `as` is used to convert something other than an integer, or to something other than an
+    /// integer type.
+    InvalidCast { from: Type, to: Type, span: Span },
+    /// An unsigned integer is negated.
+    UnsignedNegation { operand_type: Type, span: Span },
+    /// An integer literal, or a negated one, is outside the range of its type.
+    IntegerLiteralOutOfRange { r#type: Type, span: Span },
+    /// The step of the range of a `for` loop is a constant that is zero or negative.
+    NonPositiveStep { span: Span },
+}
+
+impl TypeError {
+    pub fn span(&self) -> Span {
+        match self {
+            TypeError::MismatchedTypes { span, .. }
+            | TypeError::UnknownVariable { span, .. }
+            | TypeError::UnknownFunction { span, .. }
+            | TypeError::UnknownClass { span, .. }
+            | TypeError::UnknownField { span, .. }
+            | TypeError::WrongArgumentCount { span, .. }
+            | TypeError::NotAFunction { span, .. }
+            | TypeError::IncompatibleIfBranches { span, .. }
+            | TypeError::UnsupportedOperandType { span, .. }
+            | TypeError::MissingReturnValue { span, .. }
+            | TypeError::MissingField { span, .. }
+            | TypeError::WrongTypeArgumentCount { span, .. }
+            | TypeError::WrongFunctionTypeArgumentCount { span, .. }
+            | TypeError::CannotInferTypeArgument { span, .. }
+            | TypeError::AssignmentToConstant { span, .. }
+            | TypeError::InvalidAssignmentTarget { span }
+            | TypeError::LoopControlOutsideLoop { span, .. }
+            | TypeError::NonIntegerRange { span, .. }
+            | TypeError::AssignmentToLoopVariable { span, .. }
+            | TypeError::NegativeExponent { span }
+            | TypeError::InvalidCast { span, .. }
+            | TypeError::UnsignedNegation { span, .. }
+            | TypeError::IntegerLiteralOutOfRange { span, .. }
+            | TypeError::NonPositiveStep { span } => *span,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            TypeError::MismatchedTypes {
+                expected, found, ..
+            } => format!(
+                "mismatched types: expected `{}`, found `{}`",
+                expected, found
+            ),
+            TypeError::UnknownVariable { name, .. } => {
+                format!("cannot find variable `{}` in this scope", name)
+            }
+            TypeError::UnknownFunction { name, .. } => {
+                format!("cannot find function `{}` in this scope", name)
+            }
+            TypeError::UnknownClass { name, .. } => {
+                format!("cannot find type `{}` in this scope", name)
+            }
+            TypeError::UnknownField {
+                target_type,
+                field_name,
+                ..
+            } => format!("no field `{}` on type `{}`", field_name, target_type),
+            TypeError::WrongArgumentCount {
+                function_name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "function `{}` takes {} argument{} but {} argument{} supplied",
+                function_name,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found,
+                if *found == 1 { " was" } else { "s were" }
+            ),
+            TypeError::NotAFunction { name, found, .. } => {
+                format!("`{}` is not a function, it has type `{}`", name, found)
+            }
+            TypeError::IncompatibleIfBranches { .. } => {
+                "`if` and `else` have incompatible types".to_owned()
+            }
+            TypeError::UnsupportedOperandType {
+                operator,
+                operand_type,
+                ..
+            } => format!(
+                "binary operation `{}` cannot be applied to type `{}`",
+                operator, operand_type
+            ),
+            TypeError::MissingReturnValue {
+                function_name,
+                return_type,
+                ..
+            } => format!(
+                "function `{}` must return a value of type `{}`",
+                function_name, return_type
+            ),
+            TypeError::MissingField {
+                class_name,
+                field_name,
+                ..
+            } => format!(
+                "missing field `{}` in initializer of `{}`",
+                field_name, class_name
+            ),
+            TypeError::WrongTypeArgumentCount {
+                type_name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "type `{}` takes {} type argument{} but {} type argument{} supplied",
+                type_name,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found,
+                if *found == 1 { " was" } else { "s were" }
+            ),
+            TypeError::WrongFunctionTypeArgumentCount {
+                function_name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "function `{}` takes {} type argument{} but {} type argument{} supplied",
+                function_name,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found,
+                if *found == 1 { " was" } else { "s were" }
+            ),
+            TypeError::CannotInferTypeArgument {
+                function_name,
+                type_parameter,
+                ..
+            } => format!(
+                "cannot infer type argument `{}` of function `{}`",
+                type_parameter, function_name
+            ),
+            TypeError::AssignmentToConstant { name, .. } => {
+                format!("cannot assign to constant `{}`", name)
+            }
+            TypeError::InvalidAssignmentTarget { .. } => {
+                "invalid left-hand side of assignment".to_owned()
+            }
+            TypeError::LoopControlOutsideLoop { keyword, .. } => {
+                format!("`{}` outside of a loop", keyword)
+            }
+            TypeError::NonIntegerRange { found, .. } => {
+                format!("ranges must be made of integers, found `{}`", found)
+            }
+            TypeError::AssignmentToLoopVariable { name, .. } => {
+                format!("cannot assign to loop variable `{}`", name)
+            }
+            TypeError::NegativeExponent { .. } => "negative integer exponent".to_owned(),
+            TypeError::InvalidCast { from, to, .. } => {
+                format!("cannot cast `{}` as `{}`", from, to)
+            }
+            TypeError::UnsignedNegation { operand_type, .. } => {
+                format!("cannot negate unsigned integer type `{}`", operand_type)
+            }
+            TypeError::IntegerLiteralOutOfRange { r#type, .. } => {
+                format!("literal out of range for `{}`", r#type)
+            }
+            TypeError::NonPositiveStep { .. } => "loop step must be positive".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<&TypeError> for Diagnostic {
+    fn from(e: &TypeError) -> Self {
+        let label = match e {
+            TypeError::MismatchedTypes {
+                expected, found, ..
+            } => format!("expected `{}`, found `{}`", expected, found),
+            TypeError::UnknownVariable { .. }
+            | TypeError::UnknownFunction { .. }
+            | TypeError::UnknownClass { .. } => "not found in this scope".to_owned(),
+            TypeError::UnknownField { .. } => "unknown field".to_owned(),
+            TypeError::WrongArgumentCount { expected, .. } => format!(
+                "expected {} argument{}",
+                expected,
+                if *expected == 1 { "" } else { "s" }
+            ),
+            TypeError::NotAFunction { .. } => "call expression requires a function".to_owned(),
+            TypeError::IncompatibleIfBranches {
+                then_type,
+                else_type,
+                ..
+            } => format!("expected `{}`, found `{}`", then_type, else_type),
+            TypeError::UnsupportedOperandType { operand_type, .. } => {
+                format!("this has type `{}`", operand_type)
+            }
+            TypeError::MissingReturnValue { return_type, .. } => format!(
+                "expected `{}` because of the return type, found `P_unit`",
+                return_type
+            ),
+            TypeError::MissingField { field_name, .. } => format!("missing `{}`", field_name),
+            TypeError::WrongTypeArgumentCount { expected, .. }
+            | TypeError::WrongFunctionTypeArgumentCount { expected, .. } => format!(
+                "expected {} type argument{}",
+                expected,
+                if *expected == 1 { "" } else { "s" }
+            ),
+            TypeError::CannotInferTypeArgument { type_parameter, .. } => {
+                format!("cannot infer a type for `{}`", type_parameter)
+            }
+            TypeError::AssignmentToConstant { .. } => "cannot assign to a constant".to_owned(),
+            TypeError::InvalidAssignmentTarget { .. } => {
+                "cannot assign to this expression".to_owned()
+            }
+            TypeError::LoopControlOutsideLoop { keyword, .. } => {
+                format!("cannot `{}` outside of a loop", keyword)
+            }
+            TypeError::NonIntegerRange { found, .. } => {
+                format!("expected an integer, found `{}`", found)
+            }
+            TypeError::AssignmentToLoopVariable { .. } => {
+                "cannot assign to a loop variable".to_owned()
+            }
+            TypeError::NegativeExponent { .. } => "this exponent is negative".to_owned(),
+            TypeError::InvalidCast { from, .. } => format!("this has type `{}`", from),
+            TypeError::UnsignedNegation { .. } => "this is unsigned".to_owned(),
+            TypeError::IntegerLiteralOutOfRange { r#type, .. } => {
+                format!("this doesn't fit in `{}`", r#type)
+            }
+            TypeError::NonPositiveStep { .. } => "this step is not positive".to_owned(),
+        };
+        let code = match e {
+            TypeError::MismatchedTypes { .. } => "E0100",
+            TypeError::UnknownVariable { .. } => "E0101",
+            TypeError::UnknownFunction { .. } => "E0102",
+            TypeError::UnknownClass { .. } => "E0103",
+            TypeError::UnknownField { .. } => "E0104",
+            TypeError::WrongArgumentCount { .. } => "E0105",
+            TypeError::NotAFunction { .. } => "E0106",
+            TypeError::IncompatibleIfBranches { .. } => "E0107",
+            TypeError::UnsupportedOperandType { .. } => "E0108",
+            TypeError::MissingReturnValue { .. } => "E0109",
+            TypeError::MissingField { .. } => "E0110",
+            TypeError::WrongTypeArgumentCount { .. } => "E0111",
+            TypeError::WrongFunctionTypeArgumentCount { .. } => "E0112",
+            TypeError::CannotInferTypeArgument { .. } => "E0113",
+            TypeError::AssignmentToConstant { .. } => "E0114",
+            TypeError::InvalidAssignmentTarget { .. } => "E0115",
+            TypeError::LoopControlOutsideLoop { .. } => "E0116",
+            TypeError::NonIntegerRange { .. } => "E0117",
+            TypeError::AssignmentToLoopVariable { .. } => "E0118",
+            TypeError::NegativeExponent { .. } => "E0119",
+            TypeError::InvalidCast { .. } => "E0120",
+            TypeError::UnsignedNegation { .. } => "E0121",
+            TypeError::IntegerLiteralOutOfRange { .. } => "E0122",
+            TypeError::NonPositiveStep { .. } => "E0123",
+        };
+        let diagnostic = Diagnostic::error(code, e.message(), Label::new(e.span(), label));
+
+        match e {
+            TypeError::MismatchedTypes {
+                expected_span: Some(expected_span),
+                ..
+            } => diagnostic.with_secondary_label(Label::new(
+                *expected_span,
+                "expected due to this".to_owned(),
+            )),
+            TypeError::UnknownField {
+                target_type: Type::RecordType(r),
+                ..
+            } => {
+                let mut field_names = r
+                    .fields
+                    .keys()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<String>>();
+                field_names.sort();
+                diagnostic.with_note(format!("available fields are: {}", field_names.join(", ")))
+            }
+            TypeError::IncompatibleIfBranches {
+                then_type,
+                else_type,
+                then_span,
+                ..
+            } => {
+                let diagnostic = diagnostic.with_secondary_label(Label::new(
+                    *then_span,
+                    "expected because of this".to_owned(),
+                ));
+                if *then_type == Type::UnitType || *else_type == Type::UnitType {
+                    diagnostic.with_note(
+                        "a block without a final expression, like a missing `else`, evaluates to `P_unit`"
+                            .to_owned(),
+                    )
+                } else {
+                    diagnostic
+                }
+            }
+            TypeError::UnsupportedOperandType { operator, .. } => {
+                diagnostic.with_note(match operator {
+                    InfixOperator::DoubleEquals | InfixOperator::NotEquals => {
+                        format!(
+                            "`{}` can only be applied to numbers and `P_bool`s",
+                            operator
+                        )
+                    }
+                    InfixOperator::LogicalAnd | InfixOperator::LogicalOr => {
+                        format!("`{}` can only be applied to `P_bool`s", operator)
+                    }
+                    InfixOperator::Plus
+                    | InfixOperator::Minus
+                    | InfixOperator::Times
+                    | InfixOperator::Divide
+                    | InfixOperator::Modulo
+                    | InfixOperator::LessThan
+                    | InfixOperator::LessThanEquals
+                    | InfixOperator::GreaterThan
+                    | InfixOperator::GreaterThanEquals => {
+                        format!("`{}` can only be applied to numbers", operator)
+                    }
+                    _ => format!("`{}` can only be applied to integers", operator),
+                })
+            }
+            TypeError::MissingReturnValue { .. } => diagnostic
+                .with_note("a block without a final expression evaluates to `P_unit`".to_owned()),
+            TypeError::AssignmentToConstant {
+                name, const_span, ..
+            } => diagnostic
+                .with_secondary_label(Label::new(
+                    *const_span,
+                    format!("`{}` is declared as a constant here", name),
+                ))
+                .with_suggested_fix(SuggestedFix {
+                    message: format!("declare `{}` with `let` to make it reassignable", name),
+                    span: *const_span,
+                    replacement: "let".to_owned(),
+                }),
+            TypeError::InvalidAssignmentTarget { .. } => {
+                diagnostic.with_note("only variables and fields can be assigned to".to_owned())
+            }
+            TypeError::NegativeExponent { .. } => diagnostic.with_note(
+                "integers can only be raised to powers of 0 or more, since negative powers of \
+                 integers are fractions"
+                    .to_owned(),
+            ),
+            TypeError::InvalidCast { from, to, .. } if from.is_numeric() && to.is_numeric() => {
+                diagnostic.with_note(
+                    "use `i64ToF64` and `f64ToI64` to convert between integers and floats"
+                        .to_owned(),
+                )
+            }
+            TypeError::InvalidCast { .. } => diagnostic
+                .with_note("`as` can only convert integers to other integer types".to_owned()),
+            TypeError::UnsignedNegation { operand_type, .. } => diagnostic.with_note(format!(
+                "`{}` has no negative values; cast it to a signed integer type with `as` first",
+                operand_type
+            )),
+            TypeError::IntegerLiteralOutOfRange { r#type, .. } => {
+                let (min, max) = r#type.integer_range().unwrap();
+                diagnostic.with_note(format!(
+                    "`{}` can hold values from {} to {}",
+                    r#type, min, max
+                ))
+            }
+            TypeError::AssignmentToLoopVariable {
+                name,
+                declaration_span,
+                ..
+            } => diagnostic.with_secondary_label(Label::new(
+                *declaration_span,
+                format!("`{}` is declared as the loop variable here", name),
+            )),
+            TypeError::UnknownVariable {
+                suggestion: Some(suggestion),
+                span,
+                ..
+            }
+            | TypeError::UnknownFunction {
+                suggestion: Some(suggestion),
+                span,
+                ..
+            }
+            | TypeError::UnknownClass {
+                suggestion: Some(suggestion),
+                span,
+                ..
+            } => {
+                let kind = match e {
+                    TypeError::UnknownVariable { .. } => "a variable",
+                    TypeError::UnknownFunction { .. } => "a function",
+                    _ => "a type",
+                };
+                diagnostic.with_suggested_fix(SuggestedFix {
+                    message: format!("{} with a similar name exists: `{}`", kind, suggestion),
+                    span: *span,
+                    replacement: suggestion.clone(),
+                })
+            }
+            _ => diagnostic,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use dishsoap_parser::ast::Span;
+
+use crate::diagnostics::{Diagnostic, Label};
+
+/// A problem in a program that type checks, but that is probably a mistake. Unlike
+/// [TypeError](super::TypeError)s, warnings do not stop the program from being compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeWarning {
+    /// Code follows a `return`, `break` or `continue` statement in the same block, or an
+    /// if-expression whose branches all end in one, so it never runs.
+    UnreachableCode {
+        span: Span,
+        /// The statement that makes `span` unreachable, and its keyword.
+        diverging_span: Span,
+        keyword: String,
+    },
+}
+
+impl TypeWarning {
+    pub fn span(&self) -> Span {
+        match self {
+            TypeWarning::UnreachableCode { span, .. } => *span,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            TypeWarning::UnreachableCode { .. } => "unreachable code".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for TypeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<&TypeWarning> for Diagnostic {
+    fn from(w: &TypeWarning) -> Self {
+        match w {
+            TypeWarning::UnreachableCode {
+                span,
+                diverging_span,
+                keyword,
+            } => Diagnostic::warning(
+                "W0100",
+                w.message(),
+                Label::new(*span, "unreachable code".to_owned()),
+            )
+            .with_secondary_label(Label::new(
+                *diverging_span,
+                format!("any code following this `{}` is unreachable", keyword),
+            )),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::ffi::CString;
 use dishsoap_parser::ast::Identifier;
 
 pub fn string_to_c_string(s: String) -> CString {
-    return CString::new(s).unwrap();
+    CString::new(s).unwrap()
 }
 
 pub fn identifier_to_string(identifier: &Identifier) -> String {
@@ -13,3 +13,45 @@ pub fn identifier_to_string(identifier: &Identifier) -> String {
 pub fn identifier_to_c_string(identifier: &Identifier) -> CString {
     string_to_c_string(identifier.name.clone())
 }
+
+/// Returns the number of single-character insertions, deletions, substitutions and transpositions
+/// of adjacent characters needed to turn `a` into `b`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    // `distances[i][j]` is the distance between the first `i` characters of `a` and the first `j`
+    // characters of `b`.
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    distances[0] = (0..=b.len()).collect();
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j - 1] + substitution_cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// Returns the name in `candidates` that `name` is most likely a misspelling of, if any of them is
+/// close enough. Ties are broken alphabetically, so that suggestions are deterministic.
+pub fn find_similar_name<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a String>,
+) -> Option<String> {
+    let max_distance = name.chars().count().max(3) / 3;
+    candidates
+        .filter(|c| c.as_str() != name)
+        .map(|c| (edit_distance(name, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, c)| c.clone())
+}
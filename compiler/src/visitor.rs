@@ -2,17 +2,30 @@ use std::{collections::HashMap, rc::Rc};
 
 use dishsoap_parser::ast::*;
 
-pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields: Clone> {
+/// Spans are carried over from each input node to the node that replaces it, so implementations
+/// of the `process` methods don't need to do it themselves.
+pub trait PostOrderVisitor<
+    InputTypeCommonFields: NodeCommonFields,
+    ReturnTypeCommonFields: NodeCommonFields,
+>
+{
     fn process_unit_literal(&mut self) -> UnitLiteral<ReturnTypeCommonFields>;
 
     fn process_boolean_literal(&mut self, value: &bool) -> BooleanLiteral<ReturnTypeCommonFields>;
 
-    fn process_integer_literal(&mut self, value: &i64) -> IntegerLiteral<ReturnTypeCommonFields>;
+    fn process_integer_literal(
+        &mut self,
+        value: &u64,
+        suffix: &Option<Type>,
+    ) -> IntegerLiteral<ReturnTypeCommonFields>;
+
+    fn process_float_literal(&mut self, value: &f64) -> FloatLiteral<ReturnTypeCommonFields>;
 
     fn process_object_literal(
         &mut self,
         class: &TypeReference,
         fields: &HashMap<String, Expression<ReturnTypeCommonFields>>,
+        field_spans: &HashMap<String, Span>,
     ) -> ObjectLiteral<ReturnTypeCommonFields>;
 
     fn process_variable_reference(
@@ -23,7 +36,8 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
     fn process_function_call(
         &mut self,
         identifier: &Identifier,
-        arguments: &Vec<Expression<ReturnTypeCommonFields>>,
+        type_arguments: &[Type],
+        arguments: &[Expression<ReturnTypeCommonFields>],
     ) -> FunctionCall<ReturnTypeCommonFields>;
 
     fn process_if_expression(
@@ -49,23 +63,33 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
     fn process_field_access(
         &mut self,
         target: &Expression<ReturnTypeCommonFields>,
-        field_name: &String,
+        field_name: &str,
     ) -> FieldAccess<ReturnTypeCommonFields>;
 
+    fn process_cast_expression(
+        &mut self,
+        expression: &Expression<ReturnTypeCommonFields>,
+        target_type: &Type,
+    ) -> CastExpression<ReturnTypeCommonFields>;
+
     fn process_expression(
         &mut self,
         expression: &Expression<InputTypeCommonFields>,
     ) -> Expression<ReturnTypeCommonFields> {
         match expression {
-            Expression::UnitLiteral(_) => {
-                Expression::UnitLiteral(Rc::new(self.process_unit_literal()))
-            }
-            Expression::BooleanLiteral(b) => {
-                Expression::BooleanLiteral(Rc::new(self.process_boolean_literal(&b.value)))
-            }
-            Expression::IntegerLiteral(i) => {
-                Expression::IntegerLiteral(Rc::new(self.process_integer_literal(&i.value)))
+            Expression::UnitLiteral(u) => {
+                Expression::UnitLiteral(Rc::new(self.process_unit_literal().with_span(u.span())))
             }
+            Expression::BooleanLiteral(b) => Expression::BooleanLiteral(Rc::new(
+                self.process_boolean_literal(&b.value).with_span(b.span()),
+            )),
+            Expression::IntegerLiteral(i) => Expression::IntegerLiteral(Rc::new(
+                self.process_integer_literal(&i.value, &i.suffix)
+                    .with_span(i.span()),
+            )),
+            Expression::FloatLiteral(f) => Expression::FloatLiteral(Rc::new(
+                self.process_float_literal(&f.value).with_span(f.span()),
+            )),
             Expression::ObjectLiteral(r) => {
                 let processed_fields = r
                     .fields
@@ -81,14 +105,17 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                     })
                     .collect();
                 Expression::ObjectLiteral(Rc::new(
-                    self.process_object_literal(&r.class, &processed_fields),
+                    self.process_object_literal(&r.class, &processed_fields, &r.field_spans)
+                        .with_field_spans(r.field_spans.clone())
+                        .with_span(r.span()),
                 ))
             }
             Expression::VariableReference(r) => Expression::VariableReference(Rc::new(
-                self.process_variable_reference(&r.identifier),
+                self.process_variable_reference(&r.identifier)
+                    .with_span(r.span()),
             )),
             Expression::FunctionCall(c) => {
-                let processed_arguments = c
+                let processed_arguments: Vec<Expression<ReturnTypeCommonFields>> = c
                     .arguments
                     .iter()
                     .map(|a| match self.visit(&Node::Expression(a.clone())) {
@@ -98,7 +125,12 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                     .collect();
 
                 Expression::FunctionCall(Rc::new(
-                    self.process_function_call(&c.identifier, &processed_arguments),
+                    self.process_function_call(
+                        &c.identifier,
+                        &c.type_arguments,
+                        &processed_arguments,
+                    )
+                    .with_span(c.span()),
                 ))
             }
             Expression::IfExpression(s) => {
@@ -115,11 +147,14 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                     _ => unreachable!(),
                 };
 
-                Expression::IfExpression(Rc::new(self.process_if_expression(
-                    &processed_condition,
-                    &processed_then_block,
-                    &processed_else_block,
-                )))
+                Expression::IfExpression(Rc::new(
+                    self.process_if_expression(
+                        &processed_condition,
+                        &processed_then_block,
+                        &processed_else_block,
+                    )
+                    .with_span(s.span()),
+                ))
             }
             Expression::PrefixExpression(e) => {
                 let processed_operand = match self.visit(&Node::Expression(e.operand.clone())) {
@@ -128,7 +163,8 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                 };
 
                 Expression::PrefixExpression(Rc::new(
-                    self.process_prefix_expression(&e.operator, &processed_operand),
+                    self.process_prefix_expression(&e.operator, &processed_operand)
+                        .with_span(e.span()),
                 ))
             }
             Expression::BinaryExpression(e) => {
@@ -141,11 +177,10 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                     _ => unreachable!(),
                 };
 
-                Expression::BinaryExpression(Rc::new(self.process_binary_expression(
-                    &Rc::new(processed_left),
-                    &e.operator,
-                    &Rc::new(processed_right),
-                )))
+                Expression::BinaryExpression(Rc::new(
+                    self.process_binary_expression(&processed_left, &e.operator, &processed_right)
+                        .with_span(e.span()),
+                ))
             }
             Expression::FieldAccess(a) => {
                 let processed_target = match self.visit(&Node::Expression(a.target.clone())) {
@@ -154,7 +189,20 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                 };
 
                 Expression::FieldAccess(Rc::new(
-                    self.process_field_access(&processed_target, &a.field_name),
+                    self.process_field_access(&processed_target, &a.field_name)
+                        .with_span(a.span()),
+                ))
+            }
+            Expression::CastExpression(c) => {
+                let processed_expression = match self.visit(&Node::Expression(c.expression.clone()))
+                {
+                    Node::Expression(e) => e,
+                    _ => unreachable!(),
+                };
+
+                Expression::CastExpression(Rc::new(
+                    self.process_cast_expression(&processed_expression, &c.target_type)
+                        .with_span(c.span()),
                 ))
             }
         }
@@ -163,13 +211,13 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
     fn process_variable_declarator(
         &mut self,
         identifier: &Identifier,
-        variable_type: &Type,
+        variable_type: Option<&Type>,
     ) -> VariableDeclarator<ReturnTypeCommonFields>;
 
     fn after_process_variable_declarator(
         &mut self,
         _variable_declarator: &VariableDeclarator<ReturnTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_parameter(
@@ -177,9 +225,12 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
         variable_declarator: &Rc<VariableDeclarator<ReturnTypeCommonFields>>,
     ) -> Parameter<ReturnTypeCommonFields>;
 
+    /// Called before the statements of a block are processed.
+    fn before_process_block(&mut self, _block: &Block<InputTypeCommonFields>) {}
+
     fn process_block(
         &mut self,
-        statements: &Vec<Statement<ReturnTypeCommonFields>>,
+        statements: &[Statement<ReturnTypeCommonFields>],
         final_expression: &Option<Expression<ReturnTypeCommonFields>>,
     ) -> Block<ReturnTypeCommonFields>;
 
@@ -188,10 +239,53 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
         expression: &Expression<ReturnTypeCommonFields>,
     ) -> ReturnStatement<ReturnTypeCommonFields>;
 
+    fn process_expression_statement(
+        &mut self,
+        expression: &Expression<ReturnTypeCommonFields>,
+    ) -> ExpressionStatement<ReturnTypeCommonFields>;
+
+    fn before_process_while_statement(
+        &mut self,
+        _while_statement: &WhileStatement<InputTypeCommonFields>,
+    ) {
+    }
+
+    fn process_while_statement(
+        &mut self,
+        condition: &Expression<ReturnTypeCommonFields>,
+        body: &Rc<Block<ReturnTypeCommonFields>>,
+    ) -> WhileStatement<ReturnTypeCommonFields>;
+
+    /// Called once the range of a `for` loop has been processed, before its body is.
+    fn before_process_for_body(
+        &mut self,
+        _variable_declarator: &VariableDeclarator<ReturnTypeCommonFields>,
+        _start: &Expression<ReturnTypeCommonFields>,
+        _end: &Expression<ReturnTypeCommonFields>,
+    ) {
+    }
+
+    fn process_for_statement(
+        &mut self,
+        for_statement: &ForStatement<InputTypeCommonFields>,
+        variable_declarator: &Rc<VariableDeclarator<ReturnTypeCommonFields>>,
+        start: &Expression<ReturnTypeCommonFields>,
+        end: &Expression<ReturnTypeCommonFields>,
+        step: &Option<Expression<ReturnTypeCommonFields>>,
+        body: &Rc<Block<ReturnTypeCommonFields>>,
+    ) -> ForStatement<ReturnTypeCommonFields>;
+
+    fn process_break_statement(&mut self, break_statement: &BreakStatement) -> BreakStatement;
+
+    fn process_continue_statement(
+        &mut self,
+        continue_statement: &ContinueStatement,
+    ) -> ContinueStatement;
+
     fn before_process_function_declaration(
         &mut self,
         _function_declaration: &FunctionDeclaration<InputTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_function_declaration(
@@ -199,20 +293,20 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
         function_declaration: &FunctionDeclaration<InputTypeCommonFields>,
         identifier: &Identifier,
         return_type: &Type,
-        parameters: &Vec<Rc<Parameter<ReturnTypeCommonFields>>>,
+        parameters: &[Rc<Parameter<ReturnTypeCommonFields>>],
         body: &Rc<Block<ReturnTypeCommonFields>>,
     ) -> FunctionDeclaration<ReturnTypeCommonFields>;
 
     fn after_process_function_declaration(
         &mut self,
         _function_declaration: &FunctionDeclaration<ReturnTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn before_process_variable_declaration(
         &mut self,
         _variable_declaration: &VariableDeclaration<InputTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_variable_declaration(
@@ -225,7 +319,7 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
     fn after_process_variable_declaration(
         &mut self,
         _variable_declaration: &VariableDeclaration<ReturnTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_statement(
@@ -249,11 +343,13 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                             _ => unreachable!(),
                         };
 
-                    let processed_variable_declaration = self.process_variable_declaration(
-                        &vd,
-                        &processed_variable_declarator,
-                        &processed_initial_value,
-                    );
+                    let processed_variable_declaration = self
+                        .process_variable_declaration(
+                            vd,
+                            &processed_variable_declarator,
+                            &processed_initial_value,
+                        )
+                        .with_span(vd.span());
 
                     self.after_process_variable_declaration(&processed_variable_declaration);
 
@@ -264,7 +360,7 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                 Declaration::FunctionDeclaration(fd) => {
                     self.before_process_function_declaration(fd);
 
-                    let processed_parameters = fd
+                    let processed_parameters: Vec<Rc<Parameter<ReturnTypeCommonFields>>> = fd
                         .parameters
                         .iter()
                         .map(|p| match self.visit(&Node::Parameter(p.clone())) {
@@ -277,13 +373,15 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                         _ => unreachable!(),
                     };
 
-                    let processed_function_declaration = self.process_function_declaration(
-                        fd,
-                        &fd.identifier,
-                        &fd.return_type,
-                        &processed_parameters,
-                        &processed_body,
-                    );
+                    let processed_function_declaration = self
+                        .process_function_declaration(
+                            fd,
+                            &fd.identifier,
+                            &fd.return_type,
+                            &processed_parameters,
+                            &processed_body,
+                        )
+                        .with_span(fd.span());
 
                     self.after_process_function_declaration(&processed_function_declaration);
 
@@ -291,7 +389,12 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                         processed_function_declaration,
                     )))
                 }
+                // Error nodes carry no type information, so they are passed through untouched.
+                Declaration::ErrorNode(e) => {
+                    Statement::Declaration(Declaration::ErrorNode(e.clone()))
+                }
             },
+            Statement::ErrorNode(e) => Statement::ErrorNode(e.clone()),
             Statement::ReturnStatement(s) => {
                 let processed_expression = match self.visit(&Node::Expression(s.expression.clone()))
                 {
@@ -299,9 +402,88 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                     _ => unreachable!(),
                 };
                 Statement::ReturnStatement(Rc::new(
-                    self.process_return_statement(&processed_expression),
+                    self.process_return_statement(&processed_expression)
+                        .with_span(s.span),
+                ))
+            }
+            Statement::ExpressionStatement(s) => {
+                let processed_expression = match self.visit(&Node::Expression(s.expression.clone()))
+                {
+                    Node::Expression(e) => e,
+                    _ => unreachable!(),
+                };
+                Statement::ExpressionStatement(Rc::new(
+                    self.process_expression_statement(&processed_expression)
+                        .with_span(s.span),
+                ))
+            }
+            Statement::WhileStatement(s) => {
+                self.before_process_while_statement(s);
+
+                let processed_condition = match self.visit(&Node::Expression(s.condition.clone())) {
+                    Node::Expression(e) => e,
+                    _ => unreachable!(),
+                };
+                let processed_body = match self.visit(&Node::Block(s.body.clone())) {
+                    Node::Block(b) => b,
+                    _ => unreachable!(),
+                };
+                Statement::WhileStatement(Rc::new(
+                    self.process_while_statement(&processed_condition, &processed_body)
+                        .with_span(s.span),
                 ))
             }
+            Statement::ForStatement(s) => {
+                let processed_start = match self.visit(&Node::Expression(s.start.clone())) {
+                    Node::Expression(e) => e,
+                    _ => unreachable!(),
+                };
+                let processed_end = match self.visit(&Node::Expression(s.end.clone())) {
+                    Node::Expression(e) => e,
+                    _ => unreachable!(),
+                };
+                let processed_step =
+                    s.step
+                        .as_ref()
+                        .map(|step| match self.visit(&Node::Expression(step.clone())) {
+                            Node::Expression(e) => e,
+                            _ => unreachable!(),
+                        });
+                // The loop variable is declared after the range, so the range can't refer to it.
+                let processed_variable_declarator =
+                    match self.visit(&Node::VariableDeclarator(s.variable_declarator.clone())) {
+                        Node::VariableDeclarator(vd) => vd,
+                        _ => unreachable!(),
+                    };
+
+                self.before_process_for_body(
+                    &processed_variable_declarator,
+                    &processed_start,
+                    &processed_end,
+                );
+
+                let processed_body = match self.visit(&Node::Block(s.body.clone())) {
+                    Node::Block(b) => b,
+                    _ => unreachable!(),
+                };
+                Statement::ForStatement(Rc::new(
+                    self.process_for_statement(
+                        s,
+                        &processed_variable_declarator,
+                        &processed_start,
+                        &processed_end,
+                        &processed_step,
+                        &processed_body,
+                    )
+                    .with_span(s.span),
+                ))
+            }
+            Statement::BreakStatement(s) => {
+                Statement::BreakStatement(Rc::new(self.process_break_statement(s)))
+            }
+            Statement::ContinueStatement(s) => {
+                Statement::ContinueStatement(Rc::new(self.process_continue_statement(s)))
+            }
         }
     }
 
@@ -317,10 +499,11 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
         match n {
             Node::Identifier(_) => unreachable!(),
             Node::Type(_) => unreachable!(),
-            Node::Expression(e) => Node::Expression(self.process_expression(&e)),
+            Node::Expression(e) => Node::Expression(self.process_expression(e)),
             Node::VariableDeclarator(d) => {
-                let processed_variable_declarator =
-                    self.process_variable_declarator(&d.identifier, &d.variable_type);
+                let processed_variable_declarator = self
+                    .process_variable_declarator(&d.identifier, d.variable_type.as_ref())
+                    .with_span(d.span());
 
                 self.after_process_variable_declarator(&processed_variable_declarator);
 
@@ -334,11 +517,14 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                     };
 
                 Node::Parameter(Rc::new(
-                    self.process_parameter(&processed_variable_declarator),
+                    self.process_parameter(&processed_variable_declarator)
+                        .with_span(p.span()),
                 ))
             }
             Node::Block(b) => {
-                let processed_statements = b
+                self.before_process_block(b);
+
+                let processed_statements: Vec<Statement<ReturnTypeCommonFields>> = b
                     .statements
                     .iter()
                     .map(|s| match self.visit(&Node::Statement(s.clone())) {
@@ -355,7 +541,8 @@ pub trait PostOrderVisitor<InputTypeCommonFields: Clone, ReturnTypeCommonFields:
                 };
 
                 Node::Block(Rc::new(
-                    self.process_block(&processed_statements, &processed_final_expresion),
+                    self.process_block(&processed_statements, &processed_final_expresion)
+                        .with_span(b.span),
                 ))
             }
             Node::Statement(s) => Node::Statement(self.process_statement(s)),
@@ -406,25 +593,23 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
     fn process_boolean_literal(
         &mut self,
         _boolean_literal: &BooleanLiteral<InputTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_integer_literal(
         &mut self,
         _integer_literal: &IntegerLiteral<InputTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
-    fn process_object_literal(
-        &mut self,
-        _object_literal: &ObjectLiteral<InputTypeCommonFields>,
-    ) -> () {
-    }
+    fn process_float_literal(&mut self, _float_literal: &FloatLiteral<InputTypeCommonFields>) {}
+
+    fn process_object_literal(&mut self, _object_literal: &ObjectLiteral<InputTypeCommonFields>) {}
 
     fn process_variable_reference(
         &mut self,
         _variable_reference: &VariableReference<InputTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_function_call(
@@ -455,13 +640,21 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
         *PreOrderVisitorResponse::new(false)
     }
 
-    fn process_field_access(&mut self, _field_access: &FieldAccess<InputTypeCommonFields>) -> () {}
+    fn process_field_access(&mut self, _field_access: &FieldAccess<InputTypeCommonFields>) {}
+
+    fn process_cast_expression(
+        &mut self,
+        _cast_expression: &CastExpression<InputTypeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        *PreOrderVisitorResponse::new(false)
+    }
 
-    fn process_expression(&mut self, expression: &Expression<InputTypeCommonFields>) -> () {
+    fn process_expression(&mut self, expression: &Expression<InputTypeCommonFields>) {
         match expression {
             Expression::UnitLiteral(_) => (),
             Expression::BooleanLiteral(b) => self.process_boolean_literal(&**b),
             Expression::IntegerLiteral(i) => self.process_integer_literal(&**i),
+            Expression::FloatLiteral(f) => self.process_float_literal(&**f),
             Expression::ObjectLiteral(r) => self.process_object_literal(&**r),
             Expression::VariableReference(r) => self.process_variable_reference(&**r),
             Expression::FunctionCall(c) => {
@@ -490,13 +683,18 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
                 }
             }
             Expression::FieldAccess(a) => self.process_field_access(&**a),
+            Expression::CastExpression(c) => {
+                if !self.process_cast_expression(&**c).should_stop_traversing {
+                    self.visit(&Node::Expression(c.expression.clone()));
+                }
+            }
         };
     }
 
     fn process_variable_declarator(
         &mut self,
         _variable_declarator: &VariableDeclarator<InputTypeCommonFields>,
-    ) -> () {
+    ) {
     }
 
     fn process_parameter(
@@ -517,6 +715,31 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
         *PreOrderVisitorResponse::new(false)
     }
 
+    fn process_expression_statement(
+        &mut self,
+        _expression_statement: &ExpressionStatement<InputTypeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        *PreOrderVisitorResponse::new(false)
+    }
+
+    fn process_while_statement(
+        &mut self,
+        _while_statement: &WhileStatement<InputTypeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        *PreOrderVisitorResponse::new(false)
+    }
+
+    fn process_for_statement(
+        &mut self,
+        _for_statement: &ForStatement<InputTypeCommonFields>,
+    ) -> PreOrderVisitorResponse {
+        *PreOrderVisitorResponse::new(false)
+    }
+
+    fn process_break_statement(&mut self, _break_statement: &BreakStatement) {}
+
+    fn process_continue_statement(&mut self, _continue_statement: &ContinueStatement) {}
+
     fn process_function_declaration(
         &mut self,
         _function_declaration: &FunctionDeclaration<InputTypeCommonFields>,
@@ -531,7 +754,7 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
         *PreOrderVisitorResponse::new(false)
     }
 
-    fn process_statement(&mut self, statement: &Statement<InputTypeCommonFields>) -> () {
+    fn process_statement(&mut self, statement: &Statement<InputTypeCommonFields>) {
         match statement {
             Statement::Declaration(d) => match d {
                 Declaration::VariableDeclaration(vd) => {
@@ -541,22 +764,48 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
                     }
                 }
                 Declaration::FunctionDeclaration(fd) => {
-                    if !self
-                        .process_function_declaration(&fd)
-                        .should_stop_traversing
-                    {
+                    if !self.process_function_declaration(fd).should_stop_traversing {
                         fd.parameters.iter().for_each(|p| {
                             self.visit(&Node::Parameter(p.clone()));
                         });
                         self.visit(&Node::Block(fd.body.clone()));
                     }
                 }
+                Declaration::ErrorNode(_) => {}
             },
+            Statement::ErrorNode(_) => {}
             Statement::ReturnStatement(s) => {
                 if !self.process_return_statement(&**s).should_stop_traversing {
                     self.visit(&Node::Expression(s.expression.clone()));
                 }
             }
+            Statement::ExpressionStatement(s) => {
+                if !self
+                    .process_expression_statement(&**s)
+                    .should_stop_traversing
+                {
+                    self.visit(&Node::Expression(s.expression.clone()));
+                }
+            }
+            Statement::WhileStatement(s) => {
+                if !self.process_while_statement(&**s).should_stop_traversing {
+                    self.visit(&Node::Expression(s.condition.clone()));
+                    self.visit(&Node::Block(s.body.clone()));
+                }
+            }
+            Statement::ForStatement(s) => {
+                if !self.process_for_statement(&**s).should_stop_traversing {
+                    self.visit(&Node::Expression(s.start.clone()));
+                    self.visit(&Node::Expression(s.end.clone()));
+                    if let Some(step) = &s.step {
+                        self.visit(&Node::Expression(step.clone()));
+                    }
+                    self.visit(&Node::VariableDeclarator(s.variable_declarator.clone()));
+                    self.visit(&Node::Block(s.body.clone()));
+                }
+            }
+            Statement::BreakStatement(s) => self.process_break_statement(s),
+            Statement::ContinueStatement(s) => self.process_continue_statement(s),
         };
     }
 
@@ -569,7 +818,7 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
 
     /// This method handles traversal of an AST subtree. This should never be re-implemented,
     /// functionality should only be implemented in `process` methods.
-    fn visit<'a>(&mut self, n: &Node<InputTypeCommonFields>) -> () {
+    fn visit(&mut self, n: &Node<InputTypeCommonFields>) {
         match n {
             Node::Identifier(_) => unreachable!(),
             Node::Type(_) => unreachable!(),
@@ -589,9 +838,8 @@ pub trait PreOrderVisitor<InputTypeCommonFields: Clone> {
                     b.statements
                         .iter()
                         .for_each(|s| self.visit(&Node::Statement(s.clone())));
-                    match &b.final_expression {
-                        Some(fe) => self.visit(&Node::Expression(fe.clone())),
-                        None => (),
+                    if let Some(fe) = &b.final_expression {
+                        self.visit(&Node::Expression(fe.clone()))
                     };
                 }
             }
@@ -1,27 +1,46 @@
+mod source_map;
+mod span;
 mod token;
 
+pub use self::source_map::*;
+pub use self::span::*;
 pub use self::token::Token;
 pub use logos::Logos;
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct LexerError {
     pub message: String,
+    pub expected: Token,
+    pub found: Option<Token>,
+    pub span: Span,
 }
 
 pub type LexerResult<'a, T> = Result<T, LexerError>;
 
+#[derive(Clone)]
 pub struct Lexer<'a> {
     logos_lexer: logos::Lexer<'a, Token>,
+    file_id: FileId,
     peeked_value: Option<Option<Token>>,
     peeked_slice: Option<&'a str>,
+    peeked_span: Option<logos::Span>,
+    /// The span of the token most recently returned by [Lexer::pop].
+    popped_span: logos::Span,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(source: &'a str) -> Self {
+        Lexer::new_with_file_id(source, FileId::default())
+    }
+
+    pub fn new_with_file_id(source: &'a str, file_id: FileId) -> Self {
         Lexer {
             logos_lexer: logos::Lexer::new(source),
+            file_id,
             peeked_value: None,
             peeked_slice: None,
+            peeked_span: None,
+            popped_span: 0..0,
         }
     }
 
@@ -30,9 +49,14 @@ impl<'a> Lexer<'a> {
             Some(t) => {
                 self.peeked_value = None;
                 self.peeked_slice = None;
+                self.popped_span = self.peeked_span.take().unwrap();
+                t
+            }
+            None => {
+                let t = self.logos_lexer.next();
+                self.popped_span = self.logos_lexer.span();
                 t
             }
-            None => self.logos_lexer.next(),
         }
     }
 
@@ -42,6 +66,7 @@ impl<'a> Lexer<'a> {
             None => {
                 self.peeked_value = Some(self.logos_lexer.next());
                 self.peeked_slice = Some(self.logos_lexer.slice());
+                self.peeked_span = Some(self.logos_lexer.span());
                 self.peeked_value.unwrap()
             }
         }
@@ -54,15 +79,33 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    pub fn consume(&mut self, token: Token) -> LexerResult<Token> {
+    /// Returns the span of the peeked token if there is one, and the span of the most recently
+    /// popped token otherwise.
+    pub fn span(&self) -> Span {
+        let span = match &self.peeked_span {
+            Some(s) => s.clone(),
+            None => self.logos_lexer.span(),
+        };
+        Span::new(self.file_id, span.start, span.end)
+    }
+
+    /// Returns the span of the most recently popped token, even if another token has been peeked
+    /// since.
+    pub fn popped_span(&self) -> Span {
+        Span::new(self.file_id, self.popped_span.start, self.popped_span.end)
+    }
+
+    pub fn consume(&mut self, token: Token) -> LexerResult<'_, Token> {
         let consumed: Option<Token> = self.pop();
         if consumed != Some(token) {
             Err(LexerError {
-                message: format!(
-                    "Compilation error: expected {:?}, but found {:?}",
-                    token, consumed
-                )
-                .to_owned(),
+                message: match consumed {
+                    Some(t) => format!("expected {}, found {}", token, t),
+                    None => format!("expected {}, found end of file", token),
+                },
+                expected: token,
+                found: consumed,
+                span: self.popped_span(),
             })
         } else {
             Ok(consumed.unwrap())
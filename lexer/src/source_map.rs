@@ -0,0 +1,94 @@
+use crate::{FileId, Span};
+
+/// A 1-based line and column. Columns are counted in characters, not bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+pub struct SourceMapFile {
+    pub name: String,
+    pub source: String,
+    /// Byte offset of the first character of every line.
+    line_starts: Vec<usize>,
+}
+
+impl SourceMapFile {
+    fn new(name: String, source: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        SourceMapFile {
+            name,
+            source,
+            line_starts,
+        }
+    }
+
+    /// Returns the 0-based index of the line containing `offset`.
+    fn line_index(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line_index) => line_index,
+            Err(next_line_index) => next_line_index - 1,
+        }
+    }
+
+    pub fn line_column(&self, offset: usize) -> LineColumn {
+        let offset = offset.min(self.source.len());
+        let line_index = self.line_index(offset);
+        let line_start = self.line_starts[line_index];
+
+        LineColumn {
+            line: line_index + 1,
+            column: self.source[line_start..offset].chars().count() + 1,
+        }
+    }
+
+    /// Returns the text of the 1-based line `line`, without its line terminator.
+    pub fn line(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = match self.line_starts.get(line) {
+            Some(next_line_start) => *next_line_start,
+            None => self.source.len(),
+        };
+
+        self.source[start..end].trim_end_matches(&['\n', '\r'][..])
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+}
+
+/// Owns the text of every file being compiled, and resolves [Span]s back to lines and columns.
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceMapFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap { files: vec![] }
+    }
+
+    pub fn add_file(&mut self, name: String, source: String) -> FileId {
+        self.files.push(SourceMapFile::new(name, source));
+        FileId(self.files.len() - 1)
+    }
+
+    pub fn get_file(&self, file_id: FileId) -> &SourceMapFile {
+        &self.files[file_id.0]
+    }
+
+    /// Returns the position of the first character of `span`.
+    pub fn start_line_column(&self, span: &Span) -> LineColumn {
+        self.get_file(span.file_id).line_column(span.start)
+    }
+
+    /// Returns the position just past the last character of `span`.
+    pub fn end_line_column(&self, span: &Span) -> LineColumn {
+        self.get_file(span.file_id).line_column(span.end)
+    }
+}
@@ -0,0 +1,38 @@
+/// Identifies a file that has been registered with a [crate::SourceMap].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FileId(pub usize);
+
+/// A byte range within a single source file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub file_id: FileId,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(file_id: FileId, start: usize, end: usize) -> Self {
+        Span {
+            file_id,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the smallest span that covers both `self` and `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span::new(
+            self.file_id,
+            self.start.min(other.start),
+            self.end.max(other.end),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
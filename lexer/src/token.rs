@@ -1,3 +1,5 @@
+use std::fmt;
+
 use logos::Logos;
 
 #[derive(Logos, Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -14,6 +16,12 @@ pub enum Token {
     #[token(".")]
     Dot,
 
+    #[token("..")]
+    DoubleDot,
+
+    #[token("..=")]
+    DoubleDotEquals,
+
     #[token("(")]
     ParenOpen,
 
@@ -62,6 +70,24 @@ pub enum Token {
     #[token("return")]
     ReturnKeyword,
 
+    #[token("while")]
+    WhileKeyword,
+
+    #[token("break")]
+    BreakKeyword,
+
+    #[token("continue")]
+    ContinueKeyword,
+
+    #[token("for")]
+    ForKeyword,
+
+    #[token("in")]
+    InKeyword,
+
+    #[token("as")]
+    AsKeyword,
+
     // Literals
     #[token("true")]
     TrueKeyword,
@@ -69,10 +95,18 @@ pub enum Token {
     #[token("false")]
     FalseKeyword,
 
-    #[regex("0[xX][0-9a-fA-F]+")]
+    // Integer literals can separate their digits with `_`, and can be followed by their type,
+    // like `255u8`.
+    #[regex("0[xX]_*[0-9a-fA-F][0-9a-fA-F_]*(i8|i16|i32|i64|u8|u32|u64)?")]
     HexLiteral,
 
-    #[regex("[0-9]+")]
+    #[regex("0[bB]_*[01][01_]*(i8|i16|i32|i64|u8|u32|u64)?")]
+    BinaryLiteral,
+
+    #[regex("0[oO]_*[0-7][0-7_]*(i8|i16|i32|i64|u8|u32|u64)?")]
+    OctalLiteral,
+
+    #[regex("[0-9][0-9_]*(i8|i16|i32|i64|u8|u32|u64)?")]
     IntegerLiteral,
 
     #[regex("[0-9]*\\.[0-9]+([eE][+-]?[0-9]+)?|[0-9]+[eE][+-]?[0-9]+")]
@@ -89,9 +123,30 @@ pub enum Token {
     #[token("P_bool")]
     BoolPrimitiveKeyword,
 
+    #[token("P_i8")]
+    I8PrimitiveKeyword,
+
+    #[token("P_i16")]
+    I16PrimitiveKeyword,
+
+    #[token("P_i32")]
+    I32PrimitiveKeyword,
+
     #[token("P_i64")]
     I64PrimitiveKeyword,
 
+    #[token("P_u8")]
+    U8PrimitiveKeyword,
+
+    #[token("P_u32")]
+    U32PrimitiveKeyword,
+
+    #[token("P_u64")]
+    U64PrimitiveKeyword,
+
+    #[token("P_f64")]
+    F64PrimitiveKeyword,
+
     // Operators
     #[token("++")]
     OperatorIncrement,
@@ -175,3 +230,85 @@ pub enum Token {
     #[error]
     UnexpectedToken,
 }
+
+impl fmt::Display for Token {
+    /// Describes the token the way it should appear in diagnostics.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Token::Semicolon => "`;`",
+            Token::Colon => "`:`",
+            Token::Comma => "`,`",
+            Token::Dot => "`.`",
+            Token::DoubleDot => "`..`",
+            Token::DoubleDotEquals => "`..=`",
+            Token::ParenOpen => "`(`",
+            Token::ParenClose => "`)`",
+            Token::BraceOpen => "`{`",
+            Token::BraceClose => "`}`",
+            Token::BracketOpen => "`[`",
+            Token::BracketClose => "`]`",
+            Token::Arrow => "`->`",
+            Token::FatArrow => "`=>`",
+            Token::Identifier => "identifier",
+            Token::ClassKeyword => "`class`",
+            Token::FuncKeyword => "`func`",
+            Token::LetKeyword => "`let`",
+            Token::ConstKeyword => "`const`",
+            Token::IfKeyword => "`if`",
+            Token::ElseKeyword => "`else`",
+            Token::ReturnKeyword => "`return`",
+            Token::WhileKeyword => "`while`",
+            Token::BreakKeyword => "`break`",
+            Token::ContinueKeyword => "`continue`",
+            Token::ForKeyword => "`for`",
+            Token::InKeyword => "`in`",
+            Token::AsKeyword => "`as`",
+            Token::TrueKeyword => "`true`",
+            Token::FalseKeyword => "`false`",
+            Token::HexLiteral => "hexadecimal literal",
+            Token::BinaryLiteral => "binary literal",
+            Token::OctalLiteral => "octal literal",
+            Token::IntegerLiteral => "integer literal",
+            Token::RationalLiteral => "rational literal",
+            Token::StringLiteral => "string literal",
+            Token::UnitPrimitiveKeyword => "`P_unit`",
+            Token::BoolPrimitiveKeyword => "`P_bool`",
+            Token::I8PrimitiveKeyword => "`P_i8`",
+            Token::I16PrimitiveKeyword => "`P_i16`",
+            Token::I32PrimitiveKeyword => "`P_i32`",
+            Token::I64PrimitiveKeyword => "`P_i64`",
+            Token::U8PrimitiveKeyword => "`P_u8`",
+            Token::U32PrimitiveKeyword => "`P_u32`",
+            Token::U64PrimitiveKeyword => "`P_u64`",
+            Token::F64PrimitiveKeyword => "`P_f64`",
+            Token::OperatorIncrement => "`++`",
+            Token::OperatorDecrement => "`--`",
+            Token::Bang => "`!`",
+            Token::OperatorBitNot => "`~`",
+            Token::Times => "`*`",
+            Token::Divide => "`/`",
+            Token::Percent => "`%`",
+            Token::DoubleAsterisk => "`**`",
+            Token::Plus => "`+`",
+            Token::Minus => "`-`",
+            Token::BitShiftLeft => "`<<`",
+            Token::BitShiftRight => "`>>`",
+            Token::LessThan => "`<`",
+            Token::LessThanEquals => "`<=`",
+            Token::GreaterThan => "`>`",
+            Token::GreaterThanEquals => "`>=`",
+            Token::DoubleEquals => "`==`",
+            Token::NotEquals => "`!=`",
+            Token::OperatorBitAnd => "`&`",
+            Token::OperatorBitXor => "`^`",
+            Token::OperatorBitOr => "`|`",
+            Token::OperatorLogicalAnd => "`&&`",
+            Token::OperatorLogicalOr => "`||`",
+            Token::OperatorConditional => "`?`",
+            Token::Equals => "`=`",
+            Token::Skip => "whitespace",
+            Token::UnexpectedToken => "unrecognized character",
+        };
+        write!(f, "{}", description)
+    }
+}
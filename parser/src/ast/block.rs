@@ -1,9 +1,10 @@
-use super::{Expression, Statement};
+use super::{Expression, Span, Statement};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct Block<CommonFields: Clone> {
     pub statements: Vec<Statement<CommonFields>>,
     pub final_expression: Option<Expression<CommonFields>>,
+    pub span: Span,
 }
 
 impl<CommonFields: Clone> Block<CommonFields> {
@@ -14,23 +15,29 @@ impl<CommonFields: Clone> Block<CommonFields> {
         Block::<CommonFields> {
             statements,
             final_expression,
+            span: Span::default(),
         }
     }
 
     pub fn new_no_final_expression(statements: Vec<Statement<CommonFields>>) -> Self {
-        Block::<CommonFields> {
-            statements,
-            final_expression: None,
-        }
+        Block::new(statements, None)
     }
 
     pub fn new_with_final_expression(
         statements: Vec<Statement<CommonFields>>,
         final_expression: Expression<CommonFields>,
     ) -> Self {
-        Block::<CommonFields> {
-            statements,
-            final_expression: Some(final_expression),
-        }
+        Block::new(statements, Some(final_expression))
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl<CommonFields: Clone + PartialEq> PartialEq for Block<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements && self.final_expression == other.final_expression
     }
 }
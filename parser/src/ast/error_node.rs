@@ -0,0 +1,27 @@
+use crate::Span;
+
+/// Stands in for a declaration or statement that could not be parsed. The parser reports an error
+/// for every error node it produces, so error nodes never reach later phases of compilation.
+#[derive(Debug, Clone, Default, Eq)]
+pub struct ErrorNode {
+    pub span: Span,
+}
+
+impl ErrorNode {
+    pub fn new() -> ErrorNode {
+        ErrorNode {
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl PartialEq for ErrorNode {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{Expression, InfixOperator, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Expression, InfixOperator, NodeCommonFields, Span, Type, TypedNodeCommonFields,
+    UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BinaryExpression<CommonFields: Clone> {
@@ -8,6 +11,17 @@ pub struct BinaryExpression<CommonFields: Clone> {
     pub right: Expression<CommonFields>,
 }
 
+impl<CommonFields: NodeCommonFields> BinaryExpression<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl BinaryExpression<UntypedNodeCommonFields> {
     pub fn new(
         left: Expression<UntypedNodeCommonFields>,
@@ -1,4 +1,4 @@
-use crate::{Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BooleanLiteral<CommonFields> {
@@ -6,6 +6,17 @@ pub struct BooleanLiteral<CommonFields> {
     pub value: bool,
 }
 
+impl<CommonFields: NodeCommonFields> BooleanLiteral<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl BooleanLiteral<UntypedNodeCommonFields> {
     pub fn new(value: bool) -> Self {
         BooleanLiteral::<UntypedNodeCommonFields> {
@@ -0,0 +1,46 @@
+use crate::{
+    Expression, NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+};
+
+/// An explicit conversion of an integer to another integer type, like `a as P_u8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastExpression<CommonFields: Clone> {
+    pub common_fields: CommonFields,
+    pub expression: Expression<CommonFields>,
+    pub target_type: Type,
+}
+
+impl<CommonFields: NodeCommonFields> CastExpression<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
+impl CastExpression<UntypedNodeCommonFields> {
+    pub fn new(expression: Expression<UntypedNodeCommonFields>, target_type: Type) -> Self {
+        CastExpression::<UntypedNodeCommonFields> {
+            common_fields: UntypedNodeCommonFields::new(),
+            expression,
+            target_type,
+        }
+    }
+}
+
+impl CastExpression<TypedNodeCommonFields> {
+    pub fn new(
+        r#type: Type,
+        expression: Expression<TypedNodeCommonFields>,
+        target_type: Type,
+    ) -> Self {
+        CastExpression::<TypedNodeCommonFields> {
+            common_fields: TypedNodeCommonFields::new(r#type),
+            expression,
+            target_type,
+        }
+    }
+}
@@ -1,16 +1,17 @@
 use std::rc::Rc;
 
 use super::{
-    BinaryExpression, BooleanLiteral, FieldAccess, FunctionCall, IfExpression, IntegerLiteral,
-    ObjectLiteral, PrefixExpression, UnitLiteral, VariableReference,
+    BinaryExpression, BooleanLiteral, CastExpression, FieldAccess, FloatLiteral, FunctionCall,
+    IfExpression, IntegerLiteral, ObjectLiteral, PrefixExpression, UnitLiteral, VariableReference,
 };
-use crate::{Type, TypedNodeCommonFields};
+use crate::{NodeCommonFields, Span, Type, TypedNodeCommonFields};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Expression<CommonFields: Clone> {
     UnitLiteral(Rc<UnitLiteral<CommonFields>>),
     BooleanLiteral(Rc<BooleanLiteral<CommonFields>>),
     IntegerLiteral(Rc<IntegerLiteral<CommonFields>>),
+    FloatLiteral(Rc<FloatLiteral<CommonFields>>),
     ObjectLiteral(Rc<ObjectLiteral<CommonFields>>),
     VariableReference(Rc<VariableReference<CommonFields>>),
     FunctionCall(Rc<FunctionCall<CommonFields>>),
@@ -18,6 +19,26 @@ pub enum Expression<CommonFields: Clone> {
     PrefixExpression(Rc<PrefixExpression<CommonFields>>),
     BinaryExpression(Rc<BinaryExpression<CommonFields>>),
     FieldAccess(Rc<FieldAccess<CommonFields>>),
+    CastExpression(Rc<CastExpression<CommonFields>>),
+}
+
+impl<CommonFields: NodeCommonFields> Expression<CommonFields> {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::UnitLiteral(u) => u.span(),
+            Expression::BooleanLiteral(b) => b.span(),
+            Expression::IntegerLiteral(i) => i.span(),
+            Expression::FloatLiteral(f) => f.span(),
+            Expression::ObjectLiteral(r) => r.span(),
+            Expression::VariableReference(r) => r.span(),
+            Expression::FunctionCall(c) => c.span(),
+            Expression::IfExpression(e) => e.span(),
+            Expression::PrefixExpression(e) => e.span(),
+            Expression::BinaryExpression(e) => e.span(),
+            Expression::FieldAccess(e) => e.span(),
+            Expression::CastExpression(e) => e.span(),
+        }
+    }
 }
 
 impl Expression<TypedNodeCommonFields> {
@@ -26,6 +47,7 @@ impl Expression<TypedNodeCommonFields> {
             Expression::UnitLiteral(u) => &u.common_fields.r#type,
             Expression::BooleanLiteral(b) => &b.common_fields.r#type,
             Expression::IntegerLiteral(i) => &i.common_fields.r#type,
+            Expression::FloatLiteral(f) => &f.common_fields.r#type,
             Expression::ObjectLiteral(r) => &r.common_fields.r#type,
             Expression::VariableReference(r) => &r.common_fields.r#type,
             Expression::FunctionCall(c) => &c.common_fields.r#type,
@@ -33,6 +55,7 @@ impl Expression<TypedNodeCommonFields> {
             Expression::PrefixExpression(e) => &e.common_fields.r#type,
             Expression::BinaryExpression(e) => &e.common_fields.r#type,
             Expression::FieldAccess(e) => &e.common_fields.r#type,
+            Expression::CastExpression(e) => &e.common_fields.r#type,
         }
     }
 }
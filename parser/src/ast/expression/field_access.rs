@@ -1,4 +1,6 @@
-use crate::{Expression, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Expression, NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FieldAccess<CommonFields: Clone> {
@@ -7,6 +9,17 @@ pub struct FieldAccess<CommonFields: Clone> {
     pub field_name: String,
 }
 
+impl<CommonFields: NodeCommonFields> FieldAccess<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl FieldAccess<UntypedNodeCommonFields> {
     pub fn new(target: Expression<UntypedNodeCommonFields>, field_name: String) -> Self {
         FieldAccess::<UntypedNodeCommonFields> {
@@ -0,0 +1,46 @@
+use crate::{NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+
+#[derive(Debug, Clone)]
+pub struct FloatLiteral<CommonFields> {
+    pub common_fields: CommonFields,
+    pub value: f64,
+}
+
+impl<CommonFields: NodeCommonFields> FloatLiteral<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
+impl FloatLiteral<UntypedNodeCommonFields> {
+    pub fn new(value: f64) -> Self {
+        FloatLiteral::<UntypedNodeCommonFields> {
+            common_fields: UntypedNodeCommonFields::new(),
+            value,
+        }
+    }
+}
+
+impl FloatLiteral<TypedNodeCommonFields> {
+    pub fn new(value: f64) -> Self {
+        FloatLiteral::<TypedNodeCommonFields> {
+            common_fields: TypedNodeCommonFields::new(Type::F64Type),
+            value,
+        }
+    }
+}
+
+/// Values are compared bit for bit, so that literals are equal to themselves even though `f64`
+/// isn't `Eq`.
+impl<CommonFields: PartialEq> PartialEq for FloatLiteral<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.common_fields == other.common_fields && self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+impl<CommonFields: Eq> Eq for FloatLiteral<CommonFields> {}
@@ -1,12 +1,35 @@
-use crate::{Expression, Identifier, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Expression, Identifier, NodeCommonFields, Span, Type, TypedNodeCommonFields,
+    UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionCall<CommonFields: Clone> {
     pub common_fields: CommonFields,
     pub identifier: Identifier,
+    /// The type arguments of a call to a generic function. Before type checking, these are the
+    /// type arguments written at the call site, if any. After type checking, these are the type
+    /// arguments that the function is instantiated with, whether written or inferred.
+    pub type_arguments: Vec<Type>,
     pub arguments: Vec<Expression<CommonFields>>,
 }
 
+impl<CommonFields: NodeCommonFields> FunctionCall<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+
+    pub fn with_type_arguments(mut self, type_arguments: Vec<Type>) -> Self {
+        self.type_arguments = type_arguments;
+        self
+    }
+}
+
 impl FunctionCall<UntypedNodeCommonFields> {
     pub fn new(
         identifier: Identifier,
@@ -15,6 +38,7 @@ impl FunctionCall<UntypedNodeCommonFields> {
         FunctionCall {
             common_fields: UntypedNodeCommonFields::new(),
             identifier,
+            type_arguments: vec![],
             arguments,
         }
     }
@@ -29,6 +53,7 @@ impl FunctionCall<TypedNodeCommonFields> {
         FunctionCall {
             common_fields: TypedNodeCommonFields::new(r#type),
             identifier,
+            type_arguments: vec![],
             arguments,
         }
     }
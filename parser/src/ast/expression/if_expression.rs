@@ -1,6 +1,8 @@
 use std::rc::Rc;
 
-use crate::{Block, Expression, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Block, Expression, NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IfExpression<CommonFields: Clone> {
@@ -10,6 +12,17 @@ pub struct IfExpression<CommonFields: Clone> {
     pub else_block: Rc<Block<CommonFields>>,
 }
 
+impl<CommonFields: NodeCommonFields> IfExpression<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl IfExpression<UntypedNodeCommonFields> {
     pub fn new(
         condition: Expression<UntypedNodeCommonFields>,
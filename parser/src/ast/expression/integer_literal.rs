@@ -1,25 +1,53 @@
-use crate::{Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IntegerLiteral<CommonFields> {
     pub common_fields: CommonFields,
-    pub value: i64,
+    /// Literals are never negative; `-1` negates the literal `1`.
+    pub value: u64,
+    /// The type written after the digits of the literal, like `P_u8` for `255u8`. A literal
+    /// without a suffix takes its type from the context it is used in, and is a `P_i64` if the
+    /// context doesn't require any particular integer type.
+    pub suffix: Option<Type>,
+}
+
+impl<CommonFields: NodeCommonFields> IntegerLiteral<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+
+    pub fn with_suffix(mut self, suffix: Option<Type>) -> Self {
+        self.suffix = suffix;
+        self
+    }
 }
 
 impl IntegerLiteral<UntypedNodeCommonFields> {
-    pub fn new(value: i64) -> Self {
+    pub fn new(value: u64) -> Self {
         IntegerLiteral::<UntypedNodeCommonFields> {
             common_fields: UntypedNodeCommonFields::new(),
             value,
+            suffix: None,
         }
     }
 }
 
 impl IntegerLiteral<TypedNodeCommonFields> {
-    pub fn new(value: i64) -> Self {
+    pub fn new(value: u64) -> Self {
         IntegerLiteral::<TypedNodeCommonFields> {
             common_fields: TypedNodeCommonFields::new(Type::I64Type),
             value,
+            suffix: None,
         }
     }
+
+    pub fn with_type(mut self, r#type: Type) -> Self {
+        self.common_fields.r#type = r#type;
+        self
+    }
 }
@@ -1,7 +1,10 @@
 mod binary_expression;
 mod boolean_literal;
+mod cast_expression;
+#[allow(clippy::module_inception)]
 mod expression;
 mod field_access;
+mod float_literal;
 mod function_call;
 mod if_expression;
 mod integer_literal;
@@ -12,8 +15,10 @@ mod variable_reference;
 
 pub use binary_expression::*;
 pub use boolean_literal::*;
+pub use cast_expression::*;
 pub use expression::*;
 pub use field_access::*;
+pub use float_literal::*;
 pub use function_call::*;
 pub use if_expression::*;
 pub use integer_literal::*;
@@ -2,14 +2,41 @@ use std::collections::HashMap;
 
 use crate::{
     ast::{Type, TypeReference},
-    Expression, TypedNodeCommonFields, UntypedNodeCommonFields,
+    Expression, NodeCommonFields, Span, TypedNodeCommonFields, UntypedNodeCommonFields,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct ObjectLiteral<CommonFields: Clone> {
     pub common_fields: CommonFields,
     pub class: TypeReference,
     pub fields: HashMap<String, Expression<CommonFields>>,
+    /// The span of the name of each field in `fields`.
+    pub field_spans: HashMap<String, Span>,
+}
+
+/// Like the spans of nodes, the spans of field names are ignored when comparing object literals.
+impl<CommonFields: Clone + PartialEq> PartialEq for ObjectLiteral<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.common_fields == other.common_fields
+            && self.class == other.class
+            && self.fields == other.fields
+    }
+}
+
+impl<CommonFields: NodeCommonFields> ObjectLiteral<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+
+    pub fn with_field_spans(mut self, field_spans: HashMap<String, Span>) -> Self {
+        self.field_spans = field_spans;
+        self
+    }
 }
 
 impl ObjectLiteral<UntypedNodeCommonFields> {
@@ -21,6 +48,7 @@ impl ObjectLiteral<UntypedNodeCommonFields> {
             common_fields: UntypedNodeCommonFields::new(),
             class,
             fields,
+            field_spans: HashMap::new(),
         }
     }
 }
@@ -35,6 +63,7 @@ impl ObjectLiteral<TypedNodeCommonFields> {
             common_fields: TypedNodeCommonFields::new(r#type),
             class,
             fields,
+            field_spans: HashMap::new(),
         }
     }
 }
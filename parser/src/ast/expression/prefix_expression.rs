@@ -1,4 +1,7 @@
-use crate::{Expression, PrefixOperator, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Expression, NodeCommonFields, PrefixOperator, Span, Type, TypedNodeCommonFields,
+    UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PrefixExpression<CommonFields: Clone> {
@@ -7,6 +10,17 @@ pub struct PrefixExpression<CommonFields: Clone> {
     pub operand: Expression<CommonFields>,
 }
 
+impl<CommonFields: NodeCommonFields> PrefixExpression<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl PrefixExpression<UntypedNodeCommonFields> {
     pub fn new(operator: PrefixOperator, operand: Expression<UntypedNodeCommonFields>) -> Self {
         PrefixExpression::<UntypedNodeCommonFields> {
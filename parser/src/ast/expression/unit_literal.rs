@@ -1,10 +1,27 @@
-use crate::{Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UnitLiteral<CommonFields> {
     pub common_fields: CommonFields,
 }
 
+impl<CommonFields: NodeCommonFields> UnitLiteral<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
+impl Default for UnitLiteral<UntypedNodeCommonFields> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UnitLiteral<UntypedNodeCommonFields> {
     pub fn new() -> Self {
         UnitLiteral::<UntypedNodeCommonFields> {
@@ -13,6 +30,12 @@ impl UnitLiteral<UntypedNodeCommonFields> {
     }
 }
 
+impl Default for UnitLiteral<TypedNodeCommonFields> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UnitLiteral<TypedNodeCommonFields> {
     pub fn new() -> Self {
         UnitLiteral::<TypedNodeCommonFields> {
@@ -1,4 +1,6 @@
-use crate::{Identifier, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Identifier, NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VariableReference<CommonFields> {
@@ -6,6 +8,17 @@ pub struct VariableReference<CommonFields> {
     pub identifier: Identifier,
 }
 
+impl<CommonFields: NodeCommonFields> VariableReference<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl VariableReference<UntypedNodeCommonFields> {
     pub fn new(identifier: Identifier) -> Self {
         VariableReference::<UntypedNodeCommonFields> {
@@ -1,10 +1,27 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::Span;
+
+#[derive(Debug, Clone, Eq)]
 pub struct Identifier {
     pub name: String,
+    pub span: Span,
 }
 
 impl Identifier {
     pub fn new(name: String) -> Identifier {
-        Identifier { name }
+        Identifier {
+            name,
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
     }
 }
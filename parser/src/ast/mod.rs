@@ -1,4 +1,5 @@
 mod block;
+mod error_node;
 mod expression;
 mod identifier;
 mod node;
@@ -11,6 +12,7 @@ mod r#type;
 mod variable_declarator;
 
 pub use self::block::*;
+pub use self::error_node::*;
 pub use self::expression::*;
 pub use self::identifier::*;
 pub use self::node::*;
@@ -19,5 +21,6 @@ pub use self::parameter::*;
 pub use self::parser_result::*;
 pub use self::source_file::*;
 pub use self::variable_declarator::*;
+pub use dishsoap_lexer::{FileId, LineColumn, SourceMap, SourceMapFile, Span};
 pub use r#type::*;
 pub use statement::*;
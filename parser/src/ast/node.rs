@@ -1,28 +1,72 @@
 use std::rc::Rc;
 
 use crate::{
-    Block, Expression, Identifier, Parameter, SourceFile, Statement, Type, VariableDeclarator,
+    Block, Expression, Identifier, Parameter, SourceFile, Span, Statement, Type, VariableDeclarator,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct UntypedNodeCommonFields {}
+/// Fields shared by every node in an AST, regardless of which compilation phase produced it.
+pub trait NodeCommonFields: Clone {
+    fn span(&self) -> Span;
 
-impl UntypedNodeCommonFields {
-    const FLYWEIGHT_FIELDS: UntypedNodeCommonFields = UntypedNodeCommonFields {};
+    fn set_span(&mut self, span: Span);
+}
 
+/// Spans are metadata about where a node came from, so they are ignored when comparing nodes.
+#[derive(Debug, Clone, Default, Eq)]
+pub struct UntypedNodeCommonFields {
+    pub span: Span,
+}
+
+impl UntypedNodeCommonFields {
     pub fn new() -> UntypedNodeCommonFields {
-        UntypedNodeCommonFields::FLYWEIGHT_FIELDS
+        UntypedNodeCommonFields::default()
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl PartialEq for UntypedNodeCommonFields {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl NodeCommonFields for UntypedNodeCommonFields {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+}
+
+#[derive(Debug, Clone, Eq)]
 pub struct TypedNodeCommonFields {
     pub r#type: Type,
+    pub span: Span,
 }
 
 impl TypedNodeCommonFields {
     pub fn new(r#type: Type) -> TypedNodeCommonFields {
-        TypedNodeCommonFields { r#type }
+        TypedNodeCommonFields {
+            r#type,
+            span: Span::default(),
+        }
+    }
+}
+
+impl PartialEq for TypedNodeCommonFields {
+    fn eq(&self, other: &Self) -> bool {
+        self.r#type == other.r#type
+    }
+}
+
+impl NodeCommonFields for TypedNodeCommonFields {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn set_span(&mut self, span: Span) {
+        self.span = span;
     }
 }
 
@@ -1,13 +1,17 @@
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PrefixOperator {
     Minus,
     Bang,
+    BitNot,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InfixOperator {
     Equals,
     DoubleEquals,
+    NotEquals,
     LessThan,
     LessThanEquals,
     GreaterThan,
@@ -19,5 +23,61 @@ pub enum InfixOperator {
     Times,
     Divide,
     Modulo,
+    Exponent,
+    BitAnd,
+    BitOr,
+    BitXor,
+    LogicalAnd,
+    LogicalOr,
     Dot,
 }
+
+impl InfixOperator {
+    /// Returns whether this operator compares its operands, producing a `P_bool`.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            InfixOperator::DoubleEquals
+                | InfixOperator::NotEquals
+                | InfixOperator::LessThan
+                | InfixOperator::LessThanEquals
+                | InfixOperator::GreaterThan
+                | InfixOperator::GreaterThanEquals
+        )
+    }
+
+    /// Returns whether this operator combines `P_bool`s, only evaluating its right operand if the
+    /// left one doesn't already determine the result.
+    pub fn is_logical(&self) -> bool {
+        matches!(self, InfixOperator::LogicalAnd | InfixOperator::LogicalOr)
+    }
+}
+
+impl fmt::Display for InfixOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            InfixOperator::Equals => "=",
+            InfixOperator::DoubleEquals => "==",
+            InfixOperator::NotEquals => "!=",
+            InfixOperator::LessThan => "<",
+            InfixOperator::LessThanEquals => "<=",
+            InfixOperator::GreaterThan => ">",
+            InfixOperator::GreaterThanEquals => ">=",
+            InfixOperator::BitShiftLeft => "<<",
+            InfixOperator::BitShiftRight => ">>",
+            InfixOperator::Plus => "+",
+            InfixOperator::Minus => "-",
+            InfixOperator::Times => "*",
+            InfixOperator::Divide => "/",
+            InfixOperator::Modulo => "%",
+            InfixOperator::Exponent => "**",
+            InfixOperator::BitAnd => "&",
+            InfixOperator::BitOr => "|",
+            InfixOperator::BitXor => "^",
+            InfixOperator::LogicalAnd => "&&",
+            InfixOperator::LogicalOr => "||",
+            InfixOperator::Dot => ".",
+        };
+        write!(f, "{}", symbol)
+    }
+}
@@ -1,6 +1,9 @@
 use std::rc::Rc;
 
-use super::{Type, TypedNodeCommonFields, UntypedNodeCommonFields, VariableDeclarator};
+use super::{
+    NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+    VariableDeclarator,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Parameter<CommonFields: Clone> {
@@ -8,6 +11,17 @@ pub struct Parameter<CommonFields: Clone> {
     pub variable_declarator: Rc<VariableDeclarator<CommonFields>>,
 }
 
+impl<CommonFields: NodeCommonFields> Parameter<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+}
+
 impl Parameter<UntypedNodeCommonFields> {
     pub fn new(variable_declarator: Rc<VariableDeclarator<UntypedNodeCommonFields>>) -> Self {
         Parameter::<UntypedNodeCommonFields> {
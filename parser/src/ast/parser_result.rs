@@ -1,5 +1,139 @@
-pub struct ParserError {
+use std::fmt;
+
+use dishsoap_lexer::{LexerError, Span, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A token other than one of the expected ones was found.
+    UnexpectedToken,
+    /// The lexer could not make sense of the characters at this location.
+    InvalidToken,
+    /// The file ended before a block was closed.
+    UnterminatedBlock,
+    /// A parameter was declared without a `: Type` annotation.
+    MissingTypeAnnotation,
+    /// An expression was required, but none was found.
+    ExpectedExpression,
+    /// An integer literal does not fit in 64 bits, even as an unsigned integer.
+    IntegerLiteralOutOfRange,
+    /// Syntax that is reserved, but that the parser does not understand yet.
+    UnsupportedSyntax(&'static str),
+    /// A float literal is too large to be represented as a finite `P_f64`.
+    FloatLiteralOutOfRange,
+}
+
+/// A change to the source code that would fix an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestedFix {
     pub message: String,
+    /// The code to replace. An empty span means that `replacement` should be inserted.
+    pub span: Span,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub expected: Vec<Token>,
+    /// The token that was found instead of one of the expected ones. [None] means that the end of
+    /// the file was reached.
+    pub found: Option<Token>,
+    pub span: Span,
+    pub suggested_fix: Option<Box<SuggestedFix>>,
+}
+
+impl ParseError {
+    pub fn new(
+        kind: ParseErrorKind,
+        expected: Vec<Token>,
+        found: Option<Token>,
+        span: Span,
+    ) -> Self {
+        ParseError {
+            kind,
+            expected,
+            found,
+            span,
+            suggested_fix: None,
+        }
+    }
+
+    pub fn with_suggested_fix(mut self, suggested_fix: SuggestedFix) -> Self {
+        self.suggested_fix = Some(Box::new(suggested_fix));
+        self
+    }
+
+    fn describe_expected(&self) -> String {
+        let descriptions = self
+            .expected
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<String>>();
+
+        match descriptions.len() {
+            0 => "something else".to_owned(),
+            1 => descriptions[0].clone(),
+            2 => format!("{} or {}", descriptions[0], descriptions[1]),
+            n => format!(
+                "one of {}, or {}",
+                descriptions[..n - 1].join(", "),
+                descriptions[n - 1]
+            ),
+        }
+    }
+
+    fn describe_found(&self) -> String {
+        match self.found {
+            Some(t) => t.to_string(),
+            None => "end of file".to_owned(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self.kind {
+            ParseErrorKind::UnexpectedToken => format!(
+                "expected {}, found {}",
+                self.describe_expected(),
+                self.describe_found()
+            ),
+            ParseErrorKind::InvalidToken => "unrecognized token".to_owned(),
+            ParseErrorKind::UnterminatedBlock => "this block is never closed".to_owned(),
+            ParseErrorKind::MissingTypeAnnotation => format!(
+                "missing type annotation: expected {}, found {}",
+                self.describe_expected(),
+                self.describe_found()
+            ),
+            ParseErrorKind::ExpectedExpression => {
+                format!("expected expression, found {}", self.describe_found())
+            }
+            ParseErrorKind::IntegerLiteralOutOfRange => {
+                "integer literal is too large to fit in `P_u64`".to_owned()
+            }
+            ParseErrorKind::UnsupportedSyntax(description) => {
+                format!("{} are not supported yet", description)
+            }
+            ParseErrorKind::FloatLiteralOutOfRange => {
+                "float literal is too large to fit in `P_f64`".to_owned()
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<LexerError> for ParseError {
+    fn from(e: LexerError) -> Self {
+        let kind = match e.found {
+            Some(Token::UnexpectedToken) => ParseErrorKind::InvalidToken,
+            _ => ParseErrorKind::UnexpectedToken,
+        };
+
+        ParseError::new(kind, vec![e.expected], e.found, e.span)
+    }
 }
 
-pub type ParserResult<'a, T> = Result<T, ParserError>;
+pub type ParserResult<T> = Result<T, ParseError>;
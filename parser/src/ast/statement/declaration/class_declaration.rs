@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
-use crate::{ast::Identifier, Type};
+use crate::{ast::Identifier, Span, Type};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct ClassDeclaration {
     pub identifier: Identifier,
     pub type_parameters: Vec<Identifier>,
     pub fields: HashMap<String, Type>,
+    pub span: Span,
 }
 
 impl ClassDeclaration {
@@ -19,6 +20,20 @@ impl ClassDeclaration {
             identifier,
             type_parameters,
             fields,
+            span: Span::default(),
         }
     }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl PartialEq for ClassDeclaration {
+    fn eq(&self, other: &Self) -> bool {
+        self.identifier == other.identifier
+            && self.type_parameters == other.type_parameters
+            && self.fields == other.fields
+    }
 }
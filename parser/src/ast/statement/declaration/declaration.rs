@@ -1,9 +1,21 @@
 use std::rc::Rc;
 
 use super::{FunctionDeclaration, VariableDeclaration};
+use crate::{ErrorNode, NodeCommonFields, Span};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Declaration<CommonFields: Clone> {
     FunctionDeclaration(Rc<FunctionDeclaration<CommonFields>>),
     VariableDeclaration(Rc<VariableDeclaration<CommonFields>>),
+    ErrorNode(Rc<ErrorNode>),
+}
+
+impl<CommonFields: NodeCommonFields> Declaration<CommonFields> {
+    pub fn span(&self) -> Span {
+        match self {
+            Declaration::FunctionDeclaration(d) => d.span(),
+            Declaration::VariableDeclaration(d) => d.span(),
+            Declaration::ErrorNode(e) => e.span,
+        }
+    }
 }
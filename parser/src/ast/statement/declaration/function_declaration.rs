@@ -1,18 +1,37 @@
 use std::rc::Rc;
 
 use crate::ast::{
-    Block, Identifier, Parameter, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+    Block, Identifier, NodeCommonFields, Parameter, Span, Type, TypedNodeCommonFields,
+    UntypedNodeCommonFields,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionDeclaration<CommonFields: Clone> {
     pub common_fields: CommonFields,
     pub identifier: Identifier,
+    /// The type parameters of a generic function, which is empty for other functions.
+    pub type_parameters: Vec<Identifier>,
     pub return_type: Type,
     pub parameters: Vec<Rc<Parameter<CommonFields>>>,
     pub body: Rc<Block<CommonFields>>,
 }
 
+impl<CommonFields: NodeCommonFields> FunctionDeclaration<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+
+    pub fn with_type_parameters(mut self, type_parameters: Vec<Identifier>) -> Self {
+        self.type_parameters = type_parameters;
+        self
+    }
+}
+
 impl FunctionDeclaration<UntypedNodeCommonFields> {
     pub fn new(
         identifier: Identifier,
@@ -23,6 +42,7 @@ impl FunctionDeclaration<UntypedNodeCommonFields> {
         FunctionDeclaration::<UntypedNodeCommonFields> {
             common_fields: UntypedNodeCommonFields::new(),
             identifier,
+            type_parameters: vec![],
             return_type,
             parameters,
             body,
@@ -41,6 +61,7 @@ impl FunctionDeclaration<TypedNodeCommonFields> {
         FunctionDeclaration::<TypedNodeCommonFields> {
             common_fields: TypedNodeCommonFields::new(r#type),
             identifier,
+            type_parameters: vec![],
             return_type,
             parameters,
             body,
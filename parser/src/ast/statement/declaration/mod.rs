@@ -1,4 +1,5 @@
 mod class_declaration;
+#[allow(clippy::module_inception)]
 mod declaration;
 mod function_declaration;
 mod variable_declaration;
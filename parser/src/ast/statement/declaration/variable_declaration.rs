@@ -1,16 +1,44 @@
 use std::rc::Rc;
 
 use crate::ast::{
-    Expression, Type, TypedNodeCommonFields, UntypedNodeCommonFields, VariableDeclarator,
+    Expression, NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+    VariableDeclarator,
 };
 
+/// The keyword that a variable is declared with, which determines whether it can be reassigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableDeclarationKind {
+    /// `let`, for variables that can be reassigned.
+    Let,
+    /// `const`, for variables that cannot be reassigned. The fields of a record stored in a
+    /// `const` variable can still be assigned to.
+    Const,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VariableDeclaration<CommonFields: Clone> {
     pub common_fields: CommonFields,
+    pub kind: VariableDeclarationKind,
     pub variable_declarator: Rc<VariableDeclarator<CommonFields>>,
     pub initial_value: Expression<CommonFields>,
 }
 
+impl<CommonFields: NodeCommonFields> VariableDeclaration<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
+
+    pub fn with_kind(mut self, kind: VariableDeclarationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+}
+
 impl VariableDeclaration<UntypedNodeCommonFields> {
     pub fn new(
         variable_declarator: Rc<VariableDeclarator<UntypedNodeCommonFields>>,
@@ -18,6 +46,7 @@ impl VariableDeclaration<UntypedNodeCommonFields> {
     ) -> Self {
         VariableDeclaration::<UntypedNodeCommonFields> {
             common_fields: UntypedNodeCommonFields::new(),
+            kind: VariableDeclarationKind::Let,
             variable_declarator,
             initial_value,
         }
@@ -32,6 +61,7 @@ impl VariableDeclaration<TypedNodeCommonFields> {
     ) -> Self {
         VariableDeclaration::<TypedNodeCommonFields> {
             common_fields: TypedNodeCommonFields::new(r#type),
+            kind: VariableDeclarationKind::Let,
             variable_declarator,
             initial_value,
         }
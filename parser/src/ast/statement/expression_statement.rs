@@ -0,0 +1,29 @@
+use crate::ast::{Expression, Span};
+
+/// An expression followed by a semicolon, which is evaluated for its side effects, like an
+/// assignment.
+#[derive(Debug, Clone, Eq)]
+pub struct ExpressionStatement<CommonFields: Clone> {
+    pub expression: Expression<CommonFields>,
+    pub span: Span,
+}
+
+impl<CommonFields: Clone> ExpressionStatement<CommonFields> {
+    pub fn new(expression: Expression<CommonFields>) -> Self {
+        ExpressionStatement::<CommonFields> {
+            expression,
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl<CommonFields: Clone + PartialEq> PartialEq for ExpressionStatement<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.expression == other.expression
+    }
+}
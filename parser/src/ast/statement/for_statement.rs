@@ -0,0 +1,68 @@
+use std::rc::Rc;
+
+use crate::ast::{Block, Expression, Span, VariableDeclarator};
+
+/// Whether a range includes its end, as in `0..=n`, or stops just before it, as in `0..n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeKind {
+    Exclusive,
+    Inclusive,
+}
+
+/// `for (i in start..end step s) { ... }`, which runs its body once for every integer in the
+/// range, counting up from `start` by `step`, or by 1 if there is no step.
+#[derive(Debug, Clone, Eq)]
+pub struct ForStatement<CommonFields: Clone> {
+    pub variable_declarator: Rc<VariableDeclarator<CommonFields>>,
+    pub start: Expression<CommonFields>,
+    pub end: Expression<CommonFields>,
+    pub kind: RangeKind,
+    pub step: Option<Expression<CommonFields>>,
+    pub body: Rc<Block<CommonFields>>,
+    pub span: Span,
+}
+
+impl<CommonFields: Clone> ForStatement<CommonFields> {
+    pub fn new(
+        variable_declarator: Rc<VariableDeclarator<CommonFields>>,
+        start: Expression<CommonFields>,
+        end: Expression<CommonFields>,
+        body: Rc<Block<CommonFields>>,
+    ) -> Self {
+        ForStatement::<CommonFields> {
+            variable_declarator,
+            start,
+            end,
+            kind: RangeKind::Exclusive,
+            step: None,
+            body,
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_kind(mut self, kind: RangeKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_step(mut self, step: Option<Expression<CommonFields>>) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl<CommonFields: Clone + PartialEq> PartialEq for ForStatement<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.variable_declarator == other.variable_declarator
+            && self.start == other.start
+            && self.end == other.end
+            && self.kind == other.kind
+            && self.step == other.step
+            && self.body == other.body
+    }
+}
@@ -0,0 +1,47 @@
+use crate::ast::Span;
+
+/// `break;`, which jumps out of the innermost enclosing loop.
+#[derive(Debug, Clone, Default, Eq)]
+pub struct BreakStatement {
+    pub span: Span,
+}
+
+impl BreakStatement {
+    pub fn new() -> Self {
+        BreakStatement::default()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl PartialEq for BreakStatement {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// `continue;`, which jumps to the next iteration of the innermost enclosing loop.
+#[derive(Debug, Clone, Default, Eq)]
+pub struct ContinueStatement {
+    pub span: Span,
+}
+
+impl ContinueStatement {
+    pub fn new() -> Self {
+        ContinueStatement::default()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl PartialEq for ContinueStatement {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
@@ -1,7 +1,16 @@
 mod declaration;
+mod expression_statement;
+mod for_statement;
+mod loop_control_statement;
 mod return_statement;
+#[allow(clippy::module_inception)]
 mod statement;
+mod while_statement;
 
 pub use declaration::*;
+pub use expression_statement::*;
+pub use for_statement::*;
+pub use loop_control_statement::*;
 pub use return_statement::*;
 pub use statement::*;
+pub use while_statement::*;
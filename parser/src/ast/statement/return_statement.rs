@@ -1,12 +1,27 @@
-use crate::ast::Expression;
+use crate::ast::{Expression, Span};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct ReturnStatement<CommonFields: Clone> {
     pub expression: Expression<CommonFields>,
+    pub span: Span,
 }
 
 impl<CommonFields: Clone> ReturnStatement<CommonFields> {
     pub fn new(expression: Expression<CommonFields>) -> Self {
-        ReturnStatement::<CommonFields> { expression }
+        ReturnStatement::<CommonFields> {
+            expression,
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl<CommonFields: Clone + PartialEq> PartialEq for ReturnStatement<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.expression == other.expression
     }
 }
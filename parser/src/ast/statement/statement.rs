@@ -1,15 +1,33 @@
 use std::rc::Rc;
 
-use crate::{Declaration, ReturnStatement};
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct WhileStatement {}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ForStatement {}
+use crate::{
+    BreakStatement, ContinueStatement, Declaration, ErrorNode, ExpressionStatement, ForStatement,
+    NodeCommonFields, ReturnStatement, Span, WhileStatement,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Statement<CommonFields: Clone> {
     ReturnStatement(Rc<ReturnStatement<CommonFields>>),
+    ExpressionStatement(Rc<ExpressionStatement<CommonFields>>),
+    WhileStatement(Rc<WhileStatement<CommonFields>>),
+    ForStatement(Rc<ForStatement<CommonFields>>),
+    BreakStatement(Rc<BreakStatement>),
+    ContinueStatement(Rc<ContinueStatement>),
     Declaration(Declaration<CommonFields>),
+    ErrorNode(Rc<ErrorNode>),
+}
+
+impl<CommonFields: NodeCommonFields> Statement<CommonFields> {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::ReturnStatement(s) => s.span,
+            Statement::ExpressionStatement(s) => s.span,
+            Statement::WhileStatement(s) => s.span,
+            Statement::ForStatement(s) => s.span,
+            Statement::BreakStatement(s) => s.span,
+            Statement::ContinueStatement(s) => s.span,
+            Statement::Declaration(d) => d.span(),
+            Statement::ErrorNode(e) => e.span,
+        }
+    }
 }
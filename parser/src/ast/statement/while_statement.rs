@@ -0,0 +1,31 @@
+use std::rc::Rc;
+
+use crate::ast::{Block, Expression, Span};
+
+#[derive(Debug, Clone, Eq)]
+pub struct WhileStatement<CommonFields: Clone> {
+    pub condition: Expression<CommonFields>,
+    pub body: Rc<Block<CommonFields>>,
+    pub span: Span,
+}
+
+impl<CommonFields: Clone> WhileStatement<CommonFields> {
+    pub fn new(condition: Expression<CommonFields>, body: Rc<Block<CommonFields>>) -> Self {
+        WhileStatement::<CommonFields> {
+            condition,
+            body,
+            span: Span::default(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl<CommonFields: Clone + PartialEq> PartialEq for WhileStatement<CommonFields> {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition == other.condition && self.body == other.body
+    }
+}
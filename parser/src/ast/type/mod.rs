@@ -1,5 +1,6 @@
 mod function_type;
 mod record_type;
+#[allow(clippy::module_inception)]
 mod r#type;
 mod type_reference;
 
@@ -1,13 +1,147 @@
-use std::rc::Rc;
+use std::{collections::HashMap, fmt, rc::Rc};
 
 use super::{FunctionType, RecordType, TypeReference};
+use crate::ast::Identifier;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Type {
     UnitType,
     BoolType,
+    I8Type,
+    I16Type,
+    I32Type,
     I64Type,
+    U8Type,
+    U32Type,
+    U64Type,
+    F64Type,
     RecordType(Rc<RecordType>),
     FunctionType(Rc<FunctionType>),
     TypeReference(Rc<TypeReference>),
+    /// A type parameter of the generic function that is being type checked.
+    TypeParameter(Rc<Identifier>),
+    /// The type of an expression that could not be type checked. It is compatible with every other
+    /// type, so that a single mistake does not cause a cascade of errors.
+    ErrorType,
+    /// The type of an expression that never finishes evaluating, like an if-expression whose
+    /// branches both return. It is compatible with every other type, since it never produces a
+    /// value.
+    NeverType,
+}
+
+impl Type {
+    pub fn is_integer(&self) -> bool {
+        self.is_signed_integer() || self.is_unsigned_integer()
+    }
+
+    pub fn is_signed_integer(&self) -> bool {
+        matches!(
+            self,
+            Type::I8Type | Type::I16Type | Type::I32Type | Type::I64Type
+        )
+    }
+
+    pub fn is_unsigned_integer(&self) -> bool {
+        matches!(self, Type::U8Type | Type::U32Type | Type::U64Type)
+    }
+
+    /// Returns the smallest and largest values of this type, if it is an integer type.
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        match self {
+            Type::I8Type => Some((i8::MIN.into(), i8::MAX.into())),
+            Type::I16Type => Some((i16::MIN.into(), i16::MAX.into())),
+            Type::I32Type => Some((i32::MIN.into(), i32::MAX.into())),
+            Type::I64Type => Some((i64::MIN.into(), i64::MAX.into())),
+            Type::U8Type => Some((0, u8::MAX.into())),
+            Type::U32Type => Some((0, u32::MAX.into())),
+            Type::U64Type => Some((0, u64::MAX.into())),
+            _ => None,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::F64Type)
+    }
+
+    /// Returns whether this type supports arithmetic and ordering comparisons.
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    /// Returns this type with every type parameter named in `type_arguments` replaced by the type
+    /// that it maps to.
+    pub fn substitute_type_parameters(&self, type_arguments: &HashMap<String, Type>) -> Type {
+        match self {
+            Type::TypeParameter(p) => match type_arguments.get(&p.name) {
+                Some(t) => t.clone(),
+                None => self.clone(),
+            },
+            Type::RecordType(t) => Type::RecordType(Rc::new(RecordType::new(
+                t.fields
+                    .iter()
+                    .map(|(name, field_type)| {
+                        (
+                            name.clone(),
+                            field_type.substitute_type_parameters(type_arguments),
+                        )
+                    })
+                    .collect(),
+            ))),
+            Type::FunctionType(t) => Type::FunctionType(Rc::new(FunctionType::new(
+                t.parameter_types
+                    .iter()
+                    .map(|p| p.substitute_type_parameters(type_arguments))
+                    .collect(),
+                t.return_type.substitute_type_parameters(type_arguments),
+            ))),
+            _ => self.clone(),
+        }
+    }
+}
+
+/// Formats `types` as a comma-separated list.
+fn join(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::UnitType => write!(f, "P_unit"),
+            Type::BoolType => write!(f, "P_bool"),
+            Type::I8Type => write!(f, "P_i8"),
+            Type::I16Type => write!(f, "P_i16"),
+            Type::I32Type => write!(f, "P_i32"),
+            Type::I64Type => write!(f, "P_i64"),
+            Type::U8Type => write!(f, "P_u8"),
+            Type::U32Type => write!(f, "P_u32"),
+            Type::U64Type => write!(f, "P_u64"),
+            Type::F64Type => write!(f, "P_f64"),
+            Type::RecordType(t) => {
+                let mut fields = t
+                    .fields
+                    .iter()
+                    .map(|(name, field_type)| format!("{}: {}", name, field_type))
+                    .collect::<Vec<String>>();
+                fields.sort();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            Type::FunctionType(t) => {
+                write!(f, "({}) -> {}", join(&t.parameter_types), t.return_type)
+            }
+            Type::TypeReference(t) if t.type_arguments.is_empty() => {
+                write!(f, "{}", t.identifier.name)
+            }
+            Type::TypeReference(t) => {
+                write!(f, "{}<{}>", t.identifier.name, join(&t.type_arguments))
+            }
+            Type::TypeParameter(p) => write!(f, "{}", p.name),
+            Type::ErrorType => write!(f, "{{unknown}}"),
+            Type::NeverType => write!(f, "!"),
+        }
+    }
 }
@@ -1,10 +1,11 @@
 use super::Type;
-use crate::ast::Identifier;
+use crate::ast::{Identifier, Span};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct TypeReference {
     pub identifier: Identifier,
     pub type_arguments: Vec<Type>,
+    pub span: Span,
 }
 
 impl TypeReference {
@@ -12,6 +13,18 @@ impl TypeReference {
         TypeReference {
             identifier,
             type_arguments,
+            span: Span::default(),
         }
     }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl PartialEq for TypeReference {
+    fn eq(&self, other: &Self) -> bool {
+        self.identifier == other.identifier && self.type_arguments == other.type_arguments
+    }
 }
@@ -1,10 +1,25 @@
-use crate::{Identifier, Type, TypedNodeCommonFields, UntypedNodeCommonFields};
+use crate::{
+    Identifier, NodeCommonFields, Span, Type, TypedNodeCommonFields, UntypedNodeCommonFields,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VariableDeclarator<CommonFields> {
     pub common_fields: CommonFields,
     pub identifier: Identifier,
-    pub variable_type: Type,
+    /// The type annotation of the variable. Variables declared with `let` may omit it, in which
+    /// case their type is inferred from their initial value.
+    pub variable_type: Option<Type>,
+}
+
+impl<CommonFields: NodeCommonFields> VariableDeclarator<CommonFields> {
+    pub fn span(&self) -> Span {
+        self.common_fields.span()
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.common_fields.set_span(span);
+        self
+    }
 }
 
 impl VariableDeclarator<UntypedNodeCommonFields> {
@@ -12,7 +27,15 @@ impl VariableDeclarator<UntypedNodeCommonFields> {
         VariableDeclarator::<UntypedNodeCommonFields> {
             common_fields: UntypedNodeCommonFields::new(),
             identifier,
-            variable_type,
+            variable_type: Some(variable_type),
+        }
+    }
+
+    pub fn new_without_type_annotation(identifier: Identifier) -> Self {
+        VariableDeclarator::<UntypedNodeCommonFields> {
+            common_fields: UntypedNodeCommonFields::new(),
+            identifier,
+            variable_type: None,
         }
     }
 }
@@ -22,7 +45,15 @@ impl VariableDeclarator<TypedNodeCommonFields> {
         VariableDeclarator::<TypedNodeCommonFields> {
             common_fields: TypedNodeCommonFields::new(r#type),
             identifier,
-            variable_type,
+            variable_type: Some(variable_type),
+        }
+    }
+
+    pub fn new_without_type_annotation(r#type: Type, identifier: Identifier) -> Self {
+        VariableDeclarator::<TypedNodeCommonFields> {
+            common_fields: TypedNodeCommonFields::new(r#type),
+            identifier,
+            variable_type: None,
         }
     }
 }
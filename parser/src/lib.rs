@@ -3,220 +3,497 @@ pub mod test_inputs;
 use std::{collections::HashMap, rc::Rc};
 
 use ast::*;
-use dishsoap_lexer::{Lexer, Token};
+use dishsoap_lexer::{FileId, Lexer, Span, Token};
+
+/// Prefix operators bind tighter than every infix operator except `**` and `.`, so `-a.b * c`
+/// negates `a.b` before multiplying, `-a ** 2` negates the square of `a`, and `-a as P_i32`
+/// converts the negation of `a`.
+const PREFIX_OPERATOR_PRECEDENCE: i32 = 12;
 
 pub struct Parser<'ast> {
     lexer: Lexer<'ast>,
-    scope_depth: i32,
+    /// Errors that the parser has recovered from.
+    errors: Vec<ParseError>,
 }
 
 impl<'ast> Parser<'ast> {
     pub fn new(source: &'ast str) -> Self {
-        let parser = Parser {
-            lexer: Lexer::new(source),
-            scope_depth: 0,
-        };
+        Parser::new_with_file_id(source, FileId::default())
+    }
 
-        parser
+    /// Creates a parser whose AST nodes have spans that refer to the file `file_id`.
+    pub fn new_with_file_id(source: &'ast str, file_id: FileId) -> Self {
+        Parser {
+            lexer: Lexer::new_with_file_id(source, file_id),
+            errors: vec![],
+        }
     }
 
-    /// Used to keep track of the number of opening braces encountered.
-    fn increase_scope_depth(&mut self) {
-        self.scope_depth += 1;
+    /// Returns the span of the next token, without consuming it. Used to mark where a node starts.
+    fn peek_span(&mut self) -> Span {
+        self.lexer.peek();
+        self.lexer.span()
     }
 
-    /// Used to keep track of the number of closing braces encountered.
-    fn decrease_scope_depth(&mut self) {
-        self.scope_depth -= 1;
+    /// Returns a span from the start of `start` to the end of the most recently consumed token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.lexer.popped_span())
     }
 
-    /// Get the current scope depth.
-    fn get_scope_depth(&self) -> i32 {
-        self.scope_depth
+    /// Consumes the next token, returning an error if it is not `token`. A token that does not
+    /// match is left in place, so that error recovery can resynchronise on it.
+    fn expect(&mut self, token: Token) -> ParserResult<Token> {
+        if self.lexer.peek() != Some(token) {
+            let error = self.unexpected(ParseErrorKind::UnexpectedToken, vec![token]);
+            return Err(match Parser::insertable_text(token) {
+                Some(text) => {
+                    // Suggest inserting the missing token right after the previous one.
+                    let previous_token_end = self.lexer.popped_span().end;
+                    let file_id = error.span.file_id;
+                    error.with_suggested_fix(SuggestedFix {
+                        message: format!("insert {}", token),
+                        span: Span::new(file_id, previous_token_end, previous_token_end),
+                        replacement: text.to_owned(),
+                    })
+                }
+                None => error,
+            });
+        }
+        Ok(self.lexer.consume(token)?)
     }
 
-    fn parse_identifier(&mut self) -> Identifier {
-        match self.lexer.consume(Token::Identifier) {
-            Err(e) => panic!("{}", e.message),
-            Ok(Token::Identifier) => Identifier::new(self.lexer.slice().to_owned()),
-            _ => unreachable!(),
+    /// Returns the text of `token` if it is punctuation that is commonly forgotten, and that can
+    /// be suggested as a fix when it is missing.
+    fn insertable_text(token: Token) -> Option<&'static str> {
+        match token {
+            Token::Semicolon => Some(";"),
+            Token::ParenClose => Some(")"),
+            _ => None,
         }
     }
 
-    fn parse_type_arguments(&mut self) -> Vec<Type> {
-        let _ = self.lexer.consume(Token::LessThan);
+    /// Consumes the next token if it is `token`. Returns whether a token was consumed.
+    fn eat(&mut self, token: Token) -> bool {
+        if self.lexer.peek() == Some(token) {
+            self.lexer.pop();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Builds an error describing the next token, without consuming it. Characters that the lexer
+    /// could not make sense of are always reported as such, regardless of `kind`.
+    fn unexpected(&mut self, kind: ParseErrorKind, expected: Vec<Token>) -> ParseError {
+        let found = self.lexer.peek();
+        let kind = match found {
+            Some(Token::UnexpectedToken) => ParseErrorKind::InvalidToken,
+            _ => kind,
+        };
+        ParseError::new(kind, expected, found, self.lexer.span())
+    }
+
+    /// Called after an element of a comma-separated list. Consumes the separating comma, and
+    /// returns true if `closing_token` ends the list.
+    fn parse_list_separator(&mut self, closing_token: Token) -> ParserResult<bool> {
+        if self.eat(closing_token) {
+            return Ok(true);
+        }
+        if self.eat(Token::Comma) {
+            return Ok(false);
+        }
+        Err(self.unexpected(
+            ParseErrorKind::UnexpectedToken,
+            vec![Token::Comma, closing_token],
+        ))
+    }
+
+    /// Records `error`, then skips tokens until the start of the next statement in the current
+    /// block: just past a `;`, or at the `}` that closes the block. Skipping also stops at a `func`
+    /// or `class` keyword, or at the end of the file. Returns a span covering everything from
+    /// `start` up to the resynchronisation point.
+    fn recover_in_block(&mut self, error: ParseError, start: Span) -> Span {
+        self.errors.push(error);
+
+        let mut depth = 0;
+        loop {
+            match self.lexer.peek() {
+                Some(Token::BraceOpen) => depth += 1,
+                Some(Token::BraceClose) if depth == 0 => break,
+                Some(Token::BraceClose) => depth -= 1,
+                Some(Token::Semicolon) if depth == 0 => {
+                    self.lexer.pop();
+                    break;
+                }
+                Some(Token::FuncKeyword) | Some(Token::ClassKeyword) | None => break,
+                _ => {}
+            }
+            self.lexer.pop();
+        }
+
+        self.recovered_span(start)
+    }
+
+    /// Records `error`, then skips tokens until the next `func` or `class` keyword, or the end of
+    /// the file. Returns a span covering everything from `start` up to the resynchronisation point.
+    fn recover_at_top_level(&mut self, error: ParseError, start: Span) -> Span {
+        self.errors.push(error);
+
+        while !matches!(
+            self.lexer.peek(),
+            Some(Token::FuncKeyword) | Some(Token::ClassKeyword) | None
+        ) {
+            self.lexer.pop();
+        }
+
+        self.recovered_span(start)
+    }
+
+    /// Returns the span of the tokens skipped since `start`, which is empty if none were skipped.
+    fn recovered_span(&self, start: Span) -> Span {
+        let end = self.lexer.popped_span().end.max(start.start);
+        Span::new(start.file_id, start.start, end)
+    }
+
+    fn parse_identifier(&mut self) -> ParserResult<Identifier> {
+        self.expect(Token::Identifier)?;
+        Ok(Identifier::new(self.lexer.slice().to_owned()).with_span(self.lexer.popped_span()))
+    }
+
+    fn parse_type_arguments(&mut self) -> ParserResult<Vec<Type>> {
+        self.expect(Token::LessThan)?;
         let mut type_arguments = vec![];
+        if self.eat(Token::GreaterThan) {
+            return Ok(type_arguments);
+        }
         loop {
-            if self.lexer.peek() == Some(Token::GreaterThan) {
-                let _ = self.lexer.consume(Token::GreaterThan);
+            let type_argument = self.parse_type()?;
+            type_arguments.push(type_argument);
+
+            if self.parse_list_separator(Token::GreaterThan)? {
                 break;
             }
+        }
 
-            let type_argument = self.parse_type();
-            type_arguments.push(type_argument);
+        Ok(type_arguments)
+    }
 
-            if self.lexer.peek() == Some(Token::Comma) {
-                let _ = self.lexer.consume(Token::Comma);
+    /// Parses the type arguments of an object literal or a function call, like the `<P_i64>` in
+    /// `f<P_i64>(1)`. A `<` after an identifier could also be a comparison, like the one in
+    /// `a < b`, so nothing is consumed unless the type arguments are followed by a `{` or a `(`.
+    fn try_parse_type_arguments(&mut self) -> Option<Vec<Type>> {
+        let checkpoint = self.lexer.clone();
+        match self.parse_type_arguments() {
+            Ok(type_arguments)
+                if matches!(
+                    self.lexer.peek(),
+                    Some(Token::BraceOpen) | Some(Token::ParenOpen)
+                ) =>
+            {
+                Some(type_arguments)
+            }
+            _ => {
+                self.lexer = checkpoint;
+                None
             }
         }
+    }
+
+    /// Parses the type parameters of a class or function declaration, like the `<T, U>` in
+    /// `class X<T, U>`, if there are any.
+    fn parse_type_parameters(&mut self) -> ParserResult<Vec<Identifier>> {
+        let mut type_parameters = vec![];
+        if self.eat(Token::LessThan) && !self.eat(Token::GreaterThan) {
+            loop {
+                let type_parameter = self.parse_identifier()?;
+                type_parameters.push(type_parameter);
 
-        type_arguments
+                if self.parse_list_separator(Token::GreaterThan)? {
+                    break;
+                }
+            }
+        }
+        Ok(type_parameters)
     }
 
-    fn parse_type_reference(&mut self, identifier: Identifier) -> TypeReference {
+    fn parse_type_reference(&mut self, identifier: Identifier) -> ParserResult<TypeReference> {
         let type_arguments = if self.lexer.peek() == Some(Token::LessThan) {
-            self.parse_type_arguments()
+            self.parse_type_arguments()?
         } else {
             vec![]
         };
 
-        TypeReference::new(identifier, type_arguments)
+        let span = self.span_from(identifier.span);
+        Ok(TypeReference::new(identifier, type_arguments).with_span(span))
     }
 
-    fn parse_type(&mut self) -> Type {
+    fn parse_type(&mut self) -> ParserResult<Type> {
         match self.lexer.peek() {
-            Some(Token::UnitPrimitiveKeyword)
-            | Some(Token::BoolPrimitiveKeyword)
-            | Some(Token::I64PrimitiveKeyword) => match self.lexer.pop() {
-                Some(Token::UnitPrimitiveKeyword) => Type::UnitType,
-                Some(Token::BoolPrimitiveKeyword) => Type::BoolType,
-                Some(Token::I64PrimitiveKeyword) => Type::I64Type,
-                _ => panic!("Compilation error"),
-            },
+            Some(Token::UnitPrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::UnitType)
+            }
+            Some(Token::BoolPrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::BoolType)
+            }
+            Some(Token::I8PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::I8Type)
+            }
+            Some(Token::I16PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::I16Type)
+            }
+            Some(Token::I32PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::I32Type)
+            }
+            Some(Token::I64PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::I64Type)
+            }
+            Some(Token::U8PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::U8Type)
+            }
+            Some(Token::U32PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::U32Type)
+            }
+            Some(Token::U64PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::U64Type)
+            }
+            Some(Token::F64PrimitiveKeyword) => {
+                self.lexer.pop();
+                Ok(Type::F64Type)
+            }
             Some(Token::Identifier) => {
-                let identfier = self.parse_identifier();
-                Type::TypeReference(Rc::new(self.parse_type_reference(identfier)))
+                let identfier = self.parse_identifier()?;
+                Ok(Type::TypeReference(Rc::new(
+                    self.parse_type_reference(identfier)?,
+                )))
             }
-            Some(Token::ParenOpen) => todo!("Support function type annotations"),
-            _ => panic!("Compilation error"),
+            Some(Token::ParenOpen) => Err(self.unexpected(
+                ParseErrorKind::UnsupportedSyntax("function type annotations"),
+                vec![],
+            )),
+            _ => Err(self.unexpected(
+                ParseErrorKind::UnexpectedToken,
+                vec![
+                    Token::UnitPrimitiveKeyword,
+                    Token::BoolPrimitiveKeyword,
+                    Token::I8PrimitiveKeyword,
+                    Token::I16PrimitiveKeyword,
+                    Token::I32PrimitiveKeyword,
+                    Token::I64PrimitiveKeyword,
+                    Token::U8PrimitiveKeyword,
+                    Token::U32PrimitiveKeyword,
+                    Token::U64PrimitiveKeyword,
+                    Token::F64PrimitiveKeyword,
+                    Token::Identifier,
+                ],
+            )),
         }
     }
 
-    fn parse_boolean_literal(&mut self) -> BooleanLiteral<UntypedNodeCommonFields> {
-        match self.lexer.pop() {
+    fn parse_boolean_literal(&mut self) -> ParserResult<BooleanLiteral<UntypedNodeCommonFields>> {
+        let boolean_literal = match self.lexer.peek() {
             Some(Token::TrueKeyword) => BooleanLiteral::<UntypedNodeCommonFields>::new(true),
             Some(Token::FalseKeyword) => BooleanLiteral::<UntypedNodeCommonFields>::new(false),
-            _ => panic!("Compilation error: unexpected token"),
+            _ => {
+                return Err(self.unexpected(
+                    ParseErrorKind::UnexpectedToken,
+                    vec![Token::TrueKeyword, Token::FalseKeyword],
+                ))
+            }
+        };
+        self.lexer.pop();
+        Ok(boolean_literal.with_span(self.lexer.popped_span()))
+    }
+
+    /// Parses a decimal, hexadecimal, binary or octal integer literal.
+    fn parse_integer_literal(&mut self) -> ParserResult<IntegerLiteral<UntypedNodeCommonFields>> {
+        let (token, radix) = match self.lexer.peek() {
+            Some(Token::HexLiteral) => (Token::HexLiteral, 16),
+            Some(Token::BinaryLiteral) => (Token::BinaryLiteral, 2),
+            Some(Token::OctalLiteral) => (Token::OctalLiteral, 8),
+            _ => (Token::IntegerLiteral, 10),
+        };
+        self.expect(token)?;
+        let span = self.lexer.popped_span();
+        let slice = self.lexer.slice();
+        // Suffixes are the only part of a literal that can contain `i` or `u`.
+        let (digits, suffix) = slice.split_at(slice.find(['i', 'u']).unwrap_or(slice.len()));
+        let digits = if radix == 10 { digits } else { &digits[2..] }.replace('_', "");
+        let suffix = match suffix {
+            "" => None,
+            "i8" => Some(Type::I8Type),
+            "i16" => Some(Type::I16Type),
+            "i32" => Some(Type::I32Type),
+            "i64" => Some(Type::I64Type),
+            "u8" => Some(Type::U8Type),
+            "u32" => Some(Type::U32Type),
+            "u64" => Some(Type::U64Type),
+            // The lexer only accepts the suffixes above.
+            _ => unreachable!(),
+        };
+        // Whether the literal fits its type is only known once the type checker has worked out
+        // the type, so only literals that don't fit any integer type are rejected here.
+        match u64::from_str_radix(&digits, radix) {
+            Ok(value) => Ok(IntegerLiteral::<UntypedNodeCommonFields>::new(value)
+                .with_suffix(suffix)
+                .with_span(span)),
+            Err(_) => Err(ParseError::new(
+                ParseErrorKind::IntegerLiteralOutOfRange,
+                vec![],
+                Some(token),
+                span,
+            )),
         }
     }
 
-    fn parse_integer_literal(&mut self) -> IntegerLiteral<UntypedNodeCommonFields> {
-        match self.lexer.consume(Token::IntegerLiteral) {
-            Err(e) => panic!("{}", e.message),
-            _ => {
-                let value: String = self.lexer.slice().to_owned();
-                IntegerLiteral::<UntypedNodeCommonFields>::new(value.parse::<i64>().unwrap())
+    fn parse_float_literal(&mut self) -> ParserResult<FloatLiteral<UntypedNodeCommonFields>> {
+        self.expect(Token::RationalLiteral)?;
+        let span = self.lexer.popped_span();
+        // Rust parses literals that are too large as infinity, rather than failing.
+        match self.lexer.slice().parse::<f64>() {
+            Ok(value) if value.is_finite() => {
+                Ok(FloatLiteral::<UntypedNodeCommonFields>::new(value).with_span(span))
             }
+            _ => Err(ParseError::new(
+                ParseErrorKind::FloatLiteralOutOfRange,
+                vec![],
+                Some(Token::RationalLiteral),
+                span,
+            )),
         }
     }
 
     fn parse_object_literal(
         &mut self,
         class: TypeReference,
-    ) -> ObjectLiteral<UntypedNodeCommonFields> {
-        match self.lexer.consume(Token::BraceOpen) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
+    ) -> ParserResult<ObjectLiteral<UntypedNodeCommonFields>> {
+        self.expect(Token::BraceOpen)?;
 
         let mut fields = HashMap::new();
-        loop {
-            if self.lexer.peek() == Some(Token::BraceClose) {
-                let _ = self.lexer.consume(Token::BraceClose);
-                break;
-            }
-
-            let field_name = self.parse_identifier();
-            match self.lexer.consume(Token::Colon) {
-                Err(e) => panic!("{}", e.message),
-                _ => (),
-            }
-            let field_value = match self.parse_expression(0) {
-                Some(e) => e,
-                _ => panic!("Compilation error"),
-            };
+        let mut field_spans = HashMap::new();
+        if !self.eat(Token::BraceClose) {
+            loop {
+                let field_name = self.parse_identifier()?;
+                self.expect(Token::Colon)?;
+                let field_value = self.parse_required_expression(0)?;
 
-            fields.insert(field_name.name, field_value);
+                field_spans.insert(field_name.name.clone(), field_name.span);
+                fields.insert(field_name.name, field_value);
 
-            if self.lexer.peek() == Some(Token::Comma) {
-                let _ = self.lexer.consume(Token::Comma);
+                // Allow a trailing comma before the closing brace.
+                if self.parse_list_separator(Token::BraceClose)? || self.eat(Token::BraceClose) {
+                    break;
+                }
             }
         }
 
-        ObjectLiteral::<UntypedNodeCommonFields>::new(class, fields)
+        let span = self.span_from(class.span);
+        Ok(ObjectLiteral::<UntypedNodeCommonFields>::new(class, fields)
+            .with_field_spans(field_spans)
+            .with_span(span))
     }
 
     /**
-     * Tries to parse an ObjectLiteral, FunctionCall, or VariableReference. Returns None if
-     * unsuccessful.
+     * Parses an ObjectLiteral, FunctionCall, or VariableReference.
      */
     fn parse_object_literal_or_function_call_or_variable_reference(
         &mut self,
-    ) -> Expression<UntypedNodeCommonFields> {
-        let identifier = self.parse_identifier();
+    ) -> ParserResult<Expression<UntypedNodeCommonFields>> {
+        let identifier = self.parse_identifier()?;
+        let type_arguments = if self.lexer.peek() == Some(Token::LessThan) {
+            self.try_parse_type_arguments()
+        } else {
+            None
+        };
+
         match self.lexer.peek() {
-            Some(Token::LessThan) | Some(Token::BraceOpen) => {
-                let type_reference = self.parse_type_reference(identifier);
-                Expression::ObjectLiteral(Rc::new(self.parse_object_literal(type_reference)))
+            Some(Token::BraceOpen) => {
+                let span = self.span_from(identifier.span);
+                let type_reference =
+                    TypeReference::new(identifier, type_arguments.unwrap_or_default())
+                        .with_span(span);
+                Ok(Expression::ObjectLiteral(Rc::new(
+                    self.parse_object_literal(type_reference)?,
+                )))
             }
             Some(Token::ParenOpen) => {
-                let arguments = self.parse_arguments();
-                Expression::FunctionCall(Rc::new(FunctionCall::<UntypedNodeCommonFields>::new(
-                    identifier, arguments,
+                let arguments = self.parse_arguments()?;
+                let span = self.span_from(identifier.span);
+                Ok(Expression::FunctionCall(Rc::new(
+                    FunctionCall::<UntypedNodeCommonFields>::new(identifier, arguments)
+                        .with_type_arguments(type_arguments.unwrap_or_default())
+                        .with_span(span),
                 )))
             }
-            _ => Expression::<UntypedNodeCommonFields>::VariableReference(Rc::new(
-                VariableReference::<UntypedNodeCommonFields>::new(identifier),
-            )),
+            _ => {
+                let span = identifier.span;
+                Ok(Expression::<UntypedNodeCommonFields>::VariableReference(
+                    Rc::new(
+                        VariableReference::<UntypedNodeCommonFields>::new(identifier)
+                            .with_span(span),
+                    ),
+                ))
+            }
         }
     }
 
-    fn parse_if_expression(&mut self) -> IfExpression<UntypedNodeCommonFields> {
-        match self.lexer.consume(Token::IfKeyword) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
-        match self.lexer.consume(Token::ParenOpen) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
-        let condition = self.parse_expression(0);
-        if condition.is_none() {
-            panic!("Compilation error");
-        }
-        match self.lexer.consume(Token::ParenClose) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
+    fn parse_if_expression(&mut self) -> ParserResult<IfExpression<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        self.expect(Token::IfKeyword)?;
+        self.expect(Token::ParenOpen)?;
+        let condition = self.parse_required_expression(0)?;
+        self.expect(Token::ParenClose)?;
 
-        let then_block = self.parse_block();
-        let else_block = match self.lexer.peek() {
-            Some(Token::ElseKeyword) => {
-                let _ = self.lexer.consume(Token::ElseKeyword);
-                self.parse_block()
-            }
-            _ => Block::new_no_final_expression(vec![]),
+        let then_block = self.parse_block()?;
+        let else_block = if self.eat(Token::ElseKeyword) {
+            self.parse_block()?
+        } else {
+            // A missing else block is empty, and sits right after the then block.
+            let then_block_end = self.lexer.popped_span().end;
+            Block::new_no_final_expression(vec![]).with_span(Span::new(
+                then_block.span.file_id,
+                then_block_end,
+                then_block_end,
+            ))
         };
 
-        IfExpression::<UntypedNodeCommonFields>::new(
-            condition.unwrap(),
+        Ok(IfExpression::<UntypedNodeCommonFields>::new(
+            condition,
             Rc::new(then_block),
             Rc::new(else_block),
         )
+        .with_span(self.span_from(start)))
     }
 
-    fn parse_prefix_operator(&mut self) -> PrefixOperator {
-        match self.lexer.pop() {
+    fn parse_prefix_operator(&mut self) -> ParserResult<PrefixOperator> {
+        let operator = match self.lexer.peek() {
             // Some(Token::Plus) => PrefixOperator::Plus,
             Some(Token::Minus) => PrefixOperator::Minus,
             Some(Token::Bang) => PrefixOperator::Bang,
-            _ => panic!("Compilation error: unexpected token"),
-        }
+            Some(Token::OperatorBitNot) => PrefixOperator::BitNot,
+            _ => {
+                return Err(self.unexpected(
+                    ParseErrorKind::UnexpectedToken,
+                    vec![Token::Minus, Token::Bang, Token::OperatorBitNot],
+                ))
+            }
+        };
+        self.lexer.pop();
+        Ok(operator)
     }
 
-    fn parse_infix_operator(&mut self) -> InfixOperator {
-        match self.lexer.pop() {
+    fn parse_infix_operator(&mut self) -> ParserResult<InfixOperator> {
+        let operator = match self.lexer.peek() {
             Some(Token::DoubleEquals) => InfixOperator::DoubleEquals,
+            Some(Token::NotEquals) => InfixOperator::NotEquals,
             Some(Token::LessThan) => InfixOperator::LessThan,
             Some(Token::LessThanEquals) => InfixOperator::LessThanEquals,
             Some(Token::GreaterThan) => InfixOperator::GreaterThan,
@@ -226,72 +503,123 @@ impl<'ast> Parser<'ast> {
             Some(Token::Times) => InfixOperator::Times,
             Some(Token::Divide) => InfixOperator::Divide,
             Some(Token::Percent) => InfixOperator::Modulo,
+            Some(Token::DoubleAsterisk) => InfixOperator::Exponent,
+            Some(Token::BitShiftLeft) => InfixOperator::BitShiftLeft,
+            Some(Token::BitShiftRight) => InfixOperator::BitShiftRight,
+            Some(Token::OperatorBitAnd) => InfixOperator::BitAnd,
+            Some(Token::OperatorBitXor) => InfixOperator::BitXor,
+            Some(Token::OperatorBitOr) => InfixOperator::BitOr,
+            Some(Token::OperatorLogicalAnd) => InfixOperator::LogicalAnd,
+            Some(Token::OperatorLogicalOr) => InfixOperator::LogicalOr,
             Some(Token::Dot) => InfixOperator::Dot,
-            _ => panic!("Compilation error: unexpected token"),
-        }
+            Some(Token::Equals) => InfixOperator::Equals,
+            _ => return Err(self.unexpected(ParseErrorKind::UnexpectedToken, vec![])),
+        };
+        self.lexer.pop();
+        Ok(operator)
     }
 
-    /// Helper function to get operator precedence for Pratt parsing.
+    /// Helper function to get operator precedence for Pratt parsing. The order follows C.
     fn get_precedence(&mut self) -> i32 {
         match self.lexer.peek() {
             Some(Token::Equals) => 1,
-            Some(Token::DoubleEquals) => 2,
+            Some(Token::OperatorLogicalOr) => 2,
+            Some(Token::OperatorLogicalAnd) => 3,
+            Some(Token::OperatorBitOr) => 4,
+            Some(Token::OperatorBitXor) => 5,
+            Some(Token::OperatorBitAnd) => 6,
+            Some(Token::DoubleEquals) | Some(Token::NotEquals) => 7,
             Some(Token::LessThan)
             | Some(Token::LessThanEquals)
             | Some(Token::GreaterThan)
-            | Some(Token::GreaterThanEquals) => 3,
-            Some(Token::Plus) | Some(Token::Minus) => 4,
-            Some(Token::Times) | Some(Token::Divide) | Some(Token::Percent) => 5,
-            Some(Token::Dot) => 6,
+            | Some(Token::GreaterThanEquals) => 8,
+            Some(Token::BitShiftLeft) | Some(Token::BitShiftRight) => 9,
+            Some(Token::Plus) | Some(Token::Minus) => 10,
+            Some(Token::Times) | Some(Token::Divide) | Some(Token::Percent) => 11,
+            Some(Token::AsKeyword) => 12,
+            Some(Token::DoubleAsterisk) => 13,
+            Some(Token::Dot) => 14,
             _ => 0,
         }
     }
 
-    fn parse_prefix_expression(&mut self) -> PrefixExpression<UntypedNodeCommonFields> {
-        let operator = self.parse_prefix_operator();
-        let operand = self.parse_expression(0);
-        if operand.is_none() {
-            panic!("Compilation error");
-        }
+    fn parse_prefix_expression(
+        &mut self,
+    ) -> ParserResult<PrefixExpression<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        let operator = self.parse_prefix_operator()?;
+        let operand = self.parse_required_expression(PREFIX_OPERATOR_PRECEDENCE)?;
+
+        Ok(
+            PrefixExpression::<UntypedNodeCommonFields>::new(operator, operand)
+                .with_span(self.span_from(start)),
+        )
+    }
 
-        PrefixExpression::<UntypedNodeCommonFields>::new(operator, operand.unwrap())
+    /// Parses an expression, returning an error if there is none.
+    fn parse_required_expression(
+        &mut self,
+        precedence: i32,
+    ) -> ParserResult<Expression<UntypedNodeCommonFields>> {
+        match self.parse_expression(precedence)? {
+            Some(e) => Ok(e),
+            None => Err(self.unexpected(ParseErrorKind::ExpectedExpression, vec![])),
+        }
     }
 
-    /// Parses an expression.
+    /// Parses an expression. Returns None if the next token cannot start an expression.
     // Uses Pratt parsing to handle operator precedence.
     // http://journal.stuffwithstuff.com/2011/03/19/pratt-parsers-expression-parsing-made-easy/
-    fn parse_expression(&mut self, precedence: i32) -> Option<Expression<UntypedNodeCommonFields>> {
+    fn parse_expression(
+        &mut self,
+        precedence: i32,
+    ) -> ParserResult<Option<Expression<UntypedNodeCommonFields>>> {
         let mut left = match self.lexer.peek() {
-            Some(Token::TrueKeyword) | Some(Token::FalseKeyword) => Some(
-                Expression::BooleanLiteral(Rc::new(self.parse_boolean_literal())),
-            ),
-            Some(Token::IntegerLiteral) => Some(Expression::IntegerLiteral(Rc::new(
-                self.parse_integer_literal(),
-            ))),
+            Some(Token::TrueKeyword) | Some(Token::FalseKeyword) => {
+                Expression::BooleanLiteral(Rc::new(self.parse_boolean_literal()?))
+            }
+            Some(Token::IntegerLiteral)
+            | Some(Token::HexLiteral)
+            | Some(Token::BinaryLiteral)
+            | Some(Token::OctalLiteral) => {
+                Expression::IntegerLiteral(Rc::new(self.parse_integer_literal()?))
+            }
+            Some(Token::RationalLiteral) => {
+                Expression::FloatLiteral(Rc::new(self.parse_float_literal()?))
+            }
             Some(Token::Identifier) => {
-                Some(self.parse_object_literal_or_function_call_or_variable_reference())
-            }
-            Some(Token::IfKeyword) => Some(Expression::IfExpression(Rc::new(
-                self.parse_if_expression(),
-            ))),
-            Some(Token::Plus) | Some(Token::Minus) | Some(Token::Bang) => Some(
-                Expression::PrefixExpression(Rc::new(self.parse_prefix_expression())),
-            ),
+                self.parse_object_literal_or_function_call_or_variable_reference()?
+            }
+            Some(Token::IfKeyword) => {
+                Expression::IfExpression(Rc::new(self.parse_if_expression()?))
+            }
+            Some(Token::Plus)
+            | Some(Token::Minus)
+            | Some(Token::Bang)
+            | Some(Token::OperatorBitNot) => {
+                Expression::PrefixExpression(Rc::new(self.parse_prefix_expression()?))
+            }
             Some(Token::ParenOpen) => {
-                let _ = self.lexer.consume(Token::ParenOpen);
-                let expression: Option<Expression<UntypedNodeCommonFields>> =
-                    self.parse_expression(precedence);
-                match self.lexer.consume(Token::ParenClose) {
-                    Err(e) => panic!("{}", e.message),
-                    _ => (),
+                let start = self.peek_span();
+                self.expect(Token::ParenOpen)?;
+                // Parentheses reset the precedence, so `a * (b + c)` multiplies by the sum.
+                let expression = self.parse_expression(0)?;
+                self.expect(Token::ParenClose)?;
+
+                expression.unwrap_or_else(|| {
+                    Expression::UnitLiteral(Rc::new(
+                        UnitLiteral::<UntypedNodeCommonFields>::new()
+                            .with_span(self.span_from(start)),
+                    ))
+                })
+            }
+            _ => {
+                if self.get_precedence() > precedence {
+                    // An infix operator with nothing on its left.
+                    return Err(self.unexpected(ParseErrorKind::ExpectedExpression, vec![]));
                 }
-
-                expression.or(Some(Expression::UnitLiteral(Rc::new(UnitLiteral::<
-                    UntypedNodeCommonFields,
-                >::new(
-                )))))
+                return Ok(None);
             }
-            _ => None,
         };
 
         loop {
@@ -301,318 +629,450 @@ impl<'ast> Parser<'ast> {
                 break;
             }
 
-            let infix_operator = self.parse_infix_operator();
+            let start = left.span();
+            // The right side of `as` is a type rather than an expression.
+            if self.eat(Token::AsKeyword) {
+                let target_type = self.parse_type()?;
+                left = Expression::CastExpression(Rc::new(
+                    CastExpression::<UntypedNodeCommonFields>::new(left, target_type)
+                        .with_span(self.span_from(start)),
+                ));
+                continue;
+            }
+
+            let infix_operator = self.parse_infix_operator()?;
 
             left = match infix_operator {
-                InfixOperator::Dot => Some(Expression::FieldAccess(Rc::new(FieldAccess::<
-                    UntypedNodeCommonFields,
-                >::new(
-                    left.unwrap(),
-                    self.parse_identifier().name,
-                )))),
-                _ => Some(Expression::BinaryExpression(Rc::new(BinaryExpression::<
-                    UntypedNodeCommonFields,
-                >::new(
-                    left.unwrap(),
-                    infix_operator,
-                    self.parse_expression(next_precedence).unwrap(),
-                )))),
+                InfixOperator::Dot => {
+                    let field_name = self.parse_identifier()?.name;
+                    Expression::FieldAccess(Rc::new(
+                        FieldAccess::<UntypedNodeCommonFields>::new(left, field_name)
+                            .with_span(self.span_from(start)),
+                    ))
+                }
+                _ => {
+                    // Assignment is right-associative, so `a = b = c` assigns `b = c` to `a`, and
+                    // so is exponentiation, so `a ** b ** c` raises `a` to the power `b ** c`.
+                    let right_precedence = if infix_operator == InfixOperator::Equals
+                        || infix_operator == InfixOperator::Exponent
+                    {
+                        next_precedence - 1
+                    } else {
+                        next_precedence
+                    };
+                    let right_expression = self.parse_required_expression(right_precedence)?;
+                    Expression::BinaryExpression(Rc::new(
+                        BinaryExpression::<UntypedNodeCommonFields>::new(
+                            left,
+                            infix_operator,
+                            right_expression,
+                        )
+                        .with_span(self.span_from(start)),
+                    ))
+                }
             }
         }
-        left
+        Ok(Some(left))
     }
 
-    fn parse_arguments(&mut self) -> Vec<Expression<UntypedNodeCommonFields>> {
+    fn parse_arguments(&mut self) -> ParserResult<Vec<Expression<UntypedNodeCommonFields>>> {
         let mut arguments: Vec<Expression<UntypedNodeCommonFields>> = Vec::new();
 
-        match self.lexer.consume(Token::ParenOpen) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
+        self.expect(Token::ParenOpen)?;
+        if self.eat(Token::ParenClose) {
+            return Ok(arguments);
         }
 
         loop {
-            if self.lexer.peek() == Some(Token::ParenClose) {
-                let _ = self.lexer.consume(Token::ParenClose);
-                break;
-            }
+            let argument = self.parse_required_expression(0)?;
+            arguments.push(argument);
 
-            let argument = self.parse_expression(0);
-            if argument.is_none() {
-                panic!("Compilation error");
-            }
-            arguments.push(argument.unwrap());
-
-            if self.lexer.peek() == Some(Token::Comma) {
-                let _ = self.lexer.consume(Token::Comma);
+            if self.parse_list_separator(Token::ParenClose)? {
+                break;
             }
         }
-        arguments
+        Ok(arguments)
     }
 
-    fn parse_variable_declarator(&mut self) -> VariableDeclarator<UntypedNodeCommonFields> {
-        match self.lexer.peek() {
-            Some(Token::Identifier) => {
-                let identifier = self.parse_identifier();
+    fn parse_variable_declarator(
+        &mut self,
+    ) -> ParserResult<VariableDeclarator<UntypedNodeCommonFields>> {
+        let identifier = self.parse_identifier()?;
 
-                match self.lexer.consume(Token::Colon) {
-                    Err(e) => panic!("{}", e.message),
-                    _ => (),
-                }
+        if self.lexer.peek() != Some(Token::Colon) {
+            return Err(self.unexpected(ParseErrorKind::MissingTypeAnnotation, vec![Token::Colon]));
+        }
+        self.parse_type_annotation(identifier)
+    }
 
-                let variable_type = self.parse_type();
+    /// Parses the declarator of a `let` or `const`, whose type annotation is optional.
+    fn parse_let_variable_declarator(
+        &mut self,
+    ) -> ParserResult<VariableDeclarator<UntypedNodeCommonFields>> {
+        let identifier = self.parse_identifier()?;
 
-                VariableDeclarator::<UntypedNodeCommonFields> {
-                    common_fields: UntypedNodeCommonFields::new(),
+        if self.lexer.peek() != Some(Token::Colon) {
+            let span = identifier.span;
+            return Ok(
+                VariableDeclarator::<UntypedNodeCommonFields>::new_without_type_annotation(
                     identifier,
-                    variable_type,
-                }
-            }
-            _ => unreachable!(),
+                )
+                .with_span(span),
+            );
         }
+        self.parse_type_annotation(identifier)
     }
 
-    fn parse_parameter(&mut self) -> Parameter<UntypedNodeCommonFields> {
-        match self.lexer.peek() {
-            Some(Token::Identifier) => {
-                let variable_declarator = self.parse_variable_declarator();
+    fn parse_type_annotation(
+        &mut self,
+        identifier: Identifier,
+    ) -> ParserResult<VariableDeclarator<UntypedNodeCommonFields>> {
+        self.expect(Token::Colon)?;
 
-                Parameter::<UntypedNodeCommonFields>::new(Rc::new(variable_declarator))
-            }
-            _ => unreachable!(),
-        }
+        let variable_type = self.parse_type()?;
+        let span = self.span_from(identifier.span);
+
+        Ok(
+            VariableDeclarator::<UntypedNodeCommonFields>::new(identifier, variable_type)
+                .with_span(span),
+        )
+    }
+
+    fn parse_parameter(&mut self) -> ParserResult<Parameter<UntypedNodeCommonFields>> {
+        let variable_declarator = self.parse_variable_declarator()?;
+        let span = variable_declarator.span();
+
+        Ok(Parameter::<UntypedNodeCommonFields>::new(Rc::new(variable_declarator)).with_span(span))
     }
 
-    fn parse_parameters(&mut self) -> Vec<Parameter<UntypedNodeCommonFields>> {
+    fn parse_parameters(&mut self) -> ParserResult<Vec<Parameter<UntypedNodeCommonFields>>> {
         let mut parameters: Vec<Parameter<UntypedNodeCommonFields>> = Vec::new();
 
-        match self.lexer.consume(Token::ParenOpen) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
+        self.expect(Token::ParenOpen)?;
+        if self.eat(Token::ParenClose) {
+            return Ok(parameters);
         }
 
         loop {
-            if self.lexer.peek() == Some(Token::ParenClose) {
-                let _ = self.lexer.consume(Token::ParenClose);
-                break;
-            }
-
-            let parameter = self.parse_parameter();
+            let parameter = self.parse_parameter()?;
             parameters.push(parameter);
 
-            if self.lexer.peek() == Some(Token::Comma) {
-                let _ = self.lexer.consume(Token::Comma);
+            if self.parse_list_separator(Token::ParenClose)? {
+                break;
             }
         }
-        parameters
+        Ok(parameters)
     }
 
-    fn parse_block(&mut self) -> Block<UntypedNodeCommonFields> {
-        let target_depth: i32 = self.get_scope_depth();
-
-        if self.lexer.peek() == Some(Token::BraceOpen) {
-            let _ = self.lexer.consume(Token::BraceOpen);
-            self.increase_scope_depth();
-        }
+    fn parse_block(&mut self) -> ParserResult<Block<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        self.expect(Token::BraceOpen)?;
 
         let mut statements: Vec<Statement<UntypedNodeCommonFields>> = Vec::new();
-        let mut maybe_final_expression;
+        let mut maybe_final_expression = None;
 
         loop {
-            let statement = self.parse_statement();
-            if statement.is_some() {
-                statements.push(statement.unwrap());
-                continue;
+            match self.lexer.peek() {
+                Some(Token::BraceClose) => {
+                    self.lexer.pop();
+                    break;
+                }
+                None | Some(Token::FuncKeyword) | Some(Token::ClassKeyword) => {
+                    return Err(ParseError::new(
+                        ParseErrorKind::UnterminatedBlock,
+                        vec![Token::BraceClose],
+                        self.lexer.peek(),
+                        start,
+                    ))
+                }
+                _ => {}
+            }
+
+            let item_start = self.peek_span();
+            let result = if maybe_final_expression.is_some() {
+                // Only the closing brace may follow the final expression of a block.
+                Err(self.unexpected(ParseErrorKind::UnexpectedToken, vec![Token::BraceClose]))
             } else {
-                maybe_final_expression = self.parse_expression(0);
-                match self.lexer.peek() {
-                    Some(Token::BraceClose) => {
-                        let _ = self.lexer.consume(Token::BraceClose);
-                        self.decrease_scope_depth();
-                        if self.get_scope_depth() == target_depth {
-                            break;
-                        }
+                match self.parse_statement() {
+                    Ok(Some(statement)) => {
+                        statements.push(statement);
+                        Ok(())
                     }
-                    _ => (),
+                    Ok(None) => self.parse_required_expression(0).map(|e| {
+                        // An expression followed by a semicolon is evaluated for its side
+                        // effects, and only the last expression in a block can omit it.
+                        if self.eat(Token::Semicolon) {
+                            let span = self.span_from(e.span());
+                            statements.push(Statement::ExpressionStatement(Rc::new(
+                                ExpressionStatement::new(e).with_span(span),
+                            )));
+                        } else {
+                            maybe_final_expression = Some(e);
+                        }
+                    }),
+                    Err(e) => Err(e),
                 }
+            };
+
+            if let Err(e) = result {
+                maybe_final_expression = None;
+                let span = self.recover_in_block(e, item_start);
+                statements.push(Statement::ErrorNode(Rc::new(
+                    ErrorNode::new().with_span(span),
+                )));
             }
         }
 
-        match maybe_final_expression {
+        let block = match maybe_final_expression {
             Some(e) => Block::new_with_final_expression(statements, e),
             None => Block::new_no_final_expression(statements),
-        }
+        };
+        Ok(block.with_span(self.span_from(start)))
     }
 
-    // TODO(derekxu16): Support return statements.
-    // fn parse_return_statement(&mut self) -> ReturnStatement<UntypedNodeCommonFields> {
-    //     match self.lexer.consume(Token::ReturnKeyword) {
-    //         Err(e) => panic!("{}", e.message),
-    //         _ => (),
-    //     }
+    /// Parses `return expression;`, or `return;`, which returns unit.
+    fn parse_return_statement(&mut self) -> ParserResult<ReturnStatement<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        self.expect(Token::ReturnKeyword)?;
+        let expression = if self.lexer.peek() == Some(Token::Semicolon) {
+            Expression::UnitLiteral(Rc::new(
+                UnitLiteral::<UntypedNodeCommonFields>::new().with_span(start),
+            ))
+        } else {
+            self.parse_required_expression(0)?
+        };
+        self.expect(Token::Semicolon)?;
+        Ok(ReturnStatement::new(expression).with_span(self.span_from(start)))
+    }
 
-    //     let expression = match self.parse_expression(0) {
-    //         Some(e) => ReturnStatement::new(e),
-    //         _ => {
-    //             panic!("Compilation error");
-    //         }
-    //     };
+    /// Parses `while (condition) { ... }`.
+    fn parse_while_statement(&mut self) -> ParserResult<WhileStatement<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        self.expect(Token::WhileKeyword)?;
+        self.expect(Token::ParenOpen)?;
+        let condition = self.parse_required_expression(0)?;
+        self.expect(Token::ParenClose)?;
+        let body = self.parse_block()?;
+        Ok(WhileStatement::new(condition, Rc::new(body)).with_span(self.span_from(start)))
+    }
 
-    //     match self.lexer.consume(Token::Semicolon) {
-    //         Err(e) => panic!("{}", e.message),
-    //         _ => (),
-    //     }
+    /// Parses `for (i in start..end) { ... }`. The range may be inclusive, as in `start..=end`,
+    /// and may be followed by a step, as in `start..end step 2`.
+    fn parse_for_statement(&mut self) -> ParserResult<ForStatement<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        self.expect(Token::ForKeyword)?;
+        self.expect(Token::ParenOpen)?;
+        let identifier = self.parse_identifier()?;
+        let identifier_span = identifier.span;
+        let variable_declarator =
+            VariableDeclarator::<UntypedNodeCommonFields>::new_without_type_annotation(identifier)
+                .with_span(identifier_span);
+        self.expect(Token::InKeyword)?;
+
+        let range_start = self.parse_required_expression(0)?;
+        let kind = if self.eat(Token::DoubleDot) {
+            RangeKind::Exclusive
+        } else if self.eat(Token::DoubleDotEquals) {
+            RangeKind::Inclusive
+        } else {
+            return Err(self.unexpected(
+                ParseErrorKind::UnexpectedToken,
+                vec![Token::DoubleDot, Token::DoubleDotEquals],
+            ));
+        };
+        let range_end = self.parse_required_expression(0)?;
+        // `step` is only a keyword here, so it can still be used as a name elsewhere.
+        let step = if self.lexer.peek() == Some(Token::Identifier) && self.lexer.slice() == "step" {
+            self.lexer.pop();
+            Some(self.parse_required_expression(0)?)
+        } else {
+            None
+        };
+        self.expect(Token::ParenClose)?;
 
-    //     expression
-    // }
+        let body = self.parse_block()?;
+        Ok(ForStatement::new(
+            Rc::new(variable_declarator),
+            range_start,
+            range_end,
+            Rc::new(body),
+        )
+        .with_kind(kind)
+        .with_step(step)
+        .with_span(self.span_from(start)))
+    }
 
-    fn parse_class_declaration(&mut self) -> ClassDeclaration {
-        match self.lexer.consume(Token::ClassKeyword) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
-        let identifier = self.parse_identifier();
+    fn parse_class_declaration(&mut self) -> ParserResult<ClassDeclaration> {
+        let start = self.peek_span();
+        self.expect(Token::ClassKeyword)?;
+        let identifier = self.parse_identifier()?;
+        let type_parameters = self.parse_type_parameters()?;
 
-        let mut type_parameters = vec![];
-        if self.lexer.peek() == Some(Token::LessThan) {
-            let _ = self.lexer.consume(Token::LessThan);
+        let mut fields = HashMap::new();
+        self.expect(Token::BraceOpen)?;
+        if !self.eat(Token::BraceClose) {
             loop {
-                if self.lexer.peek() == Some(Token::GreaterThan) {
-                    let _ = self.lexer.consume(Token::GreaterThan);
-                    break;
-                }
-
-                let type_parameter = self.parse_identifier();
-                type_parameters.push(type_parameter);
+                let field_name = self.parse_identifier()?;
+                self.expect(Token::Colon)?;
+                let field_type = self.parse_type()?;
+                fields.insert(field_name.name, field_type);
 
-                if self.lexer.peek() == Some(Token::Comma) {
-                    let _ = self.lexer.consume(Token::Comma);
+                // Allow a trailing comma before the closing brace.
+                if self.parse_list_separator(Token::BraceClose)? || self.eat(Token::BraceClose) {
+                    break;
                 }
             }
         }
 
-        let mut fields = HashMap::new();
-        match self.lexer.consume(Token::BraceOpen) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
-        loop {
-            if self.lexer.peek() == Some(Token::BraceClose) {
-                let _ = self.lexer.consume(Token::BraceClose);
-                break;
-            }
-
-            let field_name = self.parse_identifier();
-            match self.lexer.consume(Token::Colon) {
-                Err(e) => panic!("{}", e.message),
-                _ => (),
-            }
-            let field_type = self.parse_type();
-            fields.insert(field_name.name, field_type);
-
-            if self.lexer.peek() == Some(Token::Comma) {
-                let _ = self.lexer.consume(Token::Comma);
-            }
-        }
-
-        ClassDeclaration::new(identifier, type_parameters, fields)
+        Ok(ClassDeclaration::new(identifier, type_parameters, fields)
+            .with_span(self.span_from(start)))
     }
 
-    fn parse_function_declaration(&mut self) -> FunctionDeclaration<UntypedNodeCommonFields> {
-        match self.lexer.consume(Token::FuncKeyword) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
+    fn parse_function_declaration(
+        &mut self,
+    ) -> ParserResult<FunctionDeclaration<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        self.expect(Token::FuncKeyword)?;
 
-        let identifier = self.parse_identifier();
-        let parameters = self
-            .parse_parameters()
-            .iter()
-            .map(|p| Rc::new(p.clone()))
-            .collect();
+        let identifier = self.parse_identifier()?;
+        let type_parameters = self.parse_type_parameters()?;
+        let parameters = self.parse_parameters()?.into_iter().map(Rc::new).collect();
 
-        match self.lexer.consume(Token::Arrow) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
+        self.expect(Token::Arrow)?;
 
-        let return_type = self.parse_type();
+        let return_type = self.parse_type()?;
+        let body = self.parse_block()?;
 
-        FunctionDeclaration::<UntypedNodeCommonFields>::new(
+        Ok(FunctionDeclaration::<UntypedNodeCommonFields>::new(
             identifier,
             return_type,
             parameters,
-            Rc::new(self.parse_block()),
+            Rc::new(body),
         )
+        .with_type_parameters(type_parameters)
+        .with_span(self.span_from(start)))
     }
 
-    fn parse_variable_declaration(&mut self) -> VariableDeclaration<UntypedNodeCommonFields> {
-        match self.lexer.consume(Token::LetKeyword) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
+    fn parse_variable_declaration(
+        &mut self,
+    ) -> ParserResult<VariableDeclaration<UntypedNodeCommonFields>> {
+        let start = self.peek_span();
+        let kind = match self.lexer.peek() {
+            Some(Token::ConstKeyword) => VariableDeclarationKind::Const,
+            _ => VariableDeclarationKind::Let,
+        };
+        if kind == VariableDeclarationKind::Const {
+            self.expect(Token::ConstKeyword)?;
+        } else {
+            self.expect(Token::LetKeyword)?;
         }
 
-        let variable_declarator = self.parse_variable_declarator();
+        let variable_declarator = self.parse_let_variable_declarator()?;
 
-        match self.lexer.consume(Token::Equals) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
+        self.expect(Token::Equals)?;
 
-        let initial_value = self.parse_expression(0);
-        if initial_value.is_none() {
-            panic!("Compilation error: unexpected token");
-        }
+        let initial_value = self.parse_required_expression(0)?;
 
-        match self.lexer.consume(Token::Semicolon) {
-            Err(e) => panic!("{}", e.message),
-            _ => (),
-        }
+        self.expect(Token::Semicolon)?;
 
-        VariableDeclaration::<UntypedNodeCommonFields>::new(
+        Ok(VariableDeclaration::<UntypedNodeCommonFields>::new(
             Rc::new(variable_declarator),
-            initial_value.unwrap(),
+            initial_value,
         )
+        .with_kind(kind)
+        .with_span(self.span_from(start)))
     }
 
-    fn parse_statement(&mut self) -> Option<Statement<UntypedNodeCommonFields>> {
+    fn parse_statement(&mut self) -> ParserResult<Option<Statement<UntypedNodeCommonFields>>> {
         match self.lexer.peek() {
-            Some(Token::LetKeyword) => Some(Statement::Declaration(
-                Declaration::VariableDeclaration(Rc::new(self.parse_variable_declaration())),
-            )),
-            Some(Token::ReturnKeyword) => {
-                todo!("Support return statements")
-                // Some(Statement::ReturnStatement(self.parse_return_statement()))
+            Some(Token::LetKeyword) | Some(Token::ConstKeyword) => {
+                Ok(Some(Statement::Declaration(
+                    Declaration::VariableDeclaration(Rc::new(self.parse_variable_declaration()?)),
+                )))
             }
-            _ => None,
+            Some(Token::ReturnKeyword) => Ok(Some(Statement::ReturnStatement(Rc::new(
+                self.parse_return_statement()?,
+            )))),
+            Some(Token::WhileKeyword) => Ok(Some(Statement::WhileStatement(Rc::new(
+                self.parse_while_statement()?,
+            )))),
+            Some(Token::ForKeyword) => Ok(Some(Statement::ForStatement(Rc::new(
+                self.parse_for_statement()?,
+            )))),
+            Some(Token::BreakKeyword) => {
+                let start = self.peek_span();
+                self.lexer.pop();
+                self.expect(Token::Semicolon)?;
+                Ok(Some(Statement::BreakStatement(Rc::new(
+                    BreakStatement::new().with_span(self.span_from(start)),
+                ))))
+            }
+            Some(Token::ContinueKeyword) => {
+                let start = self.peek_span();
+                self.lexer.pop();
+                self.expect(Token::Semicolon)?;
+                Ok(Some(Statement::ContinueStatement(Rc::new(
+                    ContinueStatement::new().with_span(self.span_from(start)),
+                ))))
+            }
+            _ => Ok(None),
         }
     }
 
+    /// Parses every declaration in the file. Declarations that contain syntax errors are replaced
+    /// with [ErrorNode]s, and the errors are recorded so that they can all be reported together.
     fn parse_source_file(&mut self) -> SourceFile<UntypedNodeCommonFields> {
         let mut declarations: Vec<Declaration<UntypedNodeCommonFields>> = Vec::new();
         let mut type_declarations: Vec<ClassDeclaration> = Vec::new();
 
         loop {
-            let t = self.lexer.peek();
-            match t {
-                Some(Token::ClassKeyword) => type_declarations.push(self.parse_class_declaration()),
-                Some(Token::FuncKeyword) => declarations.push(Declaration::FunctionDeclaration(
-                    Rc::new(self.parse_function_declaration()),
-                )),
+            let start = self.peek_span();
+            let result = match self.lexer.peek() {
+                Some(Token::ClassKeyword) => self
+                    .parse_class_declaration()
+                    .map(|cd| type_declarations.push(cd)),
+                Some(Token::FuncKeyword) => self
+                    .parse_function_declaration()
+                    .map(|fd| declarations.push(Declaration::FunctionDeclaration(Rc::new(fd)))),
                 None => {
                     // EOF
                     break;
                 }
-                _ => panic!("Compilation error: unexpected token"),
+                _ => Err(self.unexpected(
+                    ParseErrorKind::UnexpectedToken,
+                    vec![Token::ClassKeyword, Token::FuncKeyword],
+                )),
+            };
+
+            if let Err(e) = result {
+                let span = self.recover_at_top_level(e, start);
+                declarations.push(Declaration::ErrorNode(Rc::new(
+                    ErrorNode::new().with_span(span),
+                )));
             }
         }
 
         SourceFile::new(declarations, type_declarations)
     }
 
+    /// Parses a file and returns a [Node::SourceFile], along with every syntax error found in it.
+    /// Parsing continues past syntax errors, and the parts of the file that could not be parsed are
+    /// represented by [ErrorNode]s.
+    pub fn parse_with_errors(&mut self) -> (Node<UntypedNodeCommonFields>, Vec<ParseError>) {
+        let source_file = self.parse_source_file();
+        (
+            Node::SourceFile(Rc::new(source_file)),
+            std::mem::take(&mut self.errors),
+        )
+    }
+
     /// Parses a file and returns a [Node::SourceFile]. This is the entry point for parsing an
-    /// entire file.
-    pub fn parse(&mut self) -> Node<UntypedNodeCommonFields> {
-        Node::SourceFile(Rc::new(self.parse_source_file()))
+    /// entire file. If the file contains syntax errors, all of them are returned instead.
+    pub fn parse(&mut self) -> Result<Node<UntypedNodeCommonFields>, Vec<ParseError>> {
+        match self.parse_with_errors() {
+            (node, errors) if errors.is_empty() => Ok(node),
+            (_, errors) => Err(errors),
+        }
     }
 }
 
@@ -26,6 +26,76 @@ func test() -> P_i64 {
 }
 ";
 
+pub const IF_EXPRESSION_BOOL: &str = "
+func test() -> P_i64 {
+    let b: P_bool = if (1 > 2) {
+        true
+    } else {
+        false
+    };
+    if (b) {
+        1
+    } else {
+        2
+    }
+}
+";
+
+pub const IF_EXPRESSION_RECORD: &str = "
+class X {c: P_i64}
+
+func test() -> P_i64 {
+    let x: X = if (2 > 1) {
+        X {c: 5}
+    } else {
+        X {c: 6}
+    };
+    x.c
+}
+";
+
+pub const IF_EXPRESSION_WITHOUT_ELSE: &str = "
+func test() -> P_i64 {
+    let u: P_unit = if (2 > 1) {
+        let a: P_i64 = 1;
+    };
+    7
+}
+";
+
+pub const COMPARISON_AND_EQUALITY: &str = "
+func test() -> P_i64 {
+    let a: P_bool = 1 <= 1;
+    let b: P_bool = a == (2 > 3);
+    let c: P_bool = 4 == 4;
+    if (b == false == c) {
+        1
+    } else {
+        2
+    }
+}
+";
+
+pub const NOT_EQUALS: &str = "
+func digit(condition: P_bool) -> P_i64 {
+    if (condition) {
+        1
+    } else {
+        0
+    }
+}
+
+func test() -> P_i64 {
+    let nan = 0.0 / 0.0;
+    digit(1 != 2)
+        + digit(3 != 3) * 10
+        + digit(true != false) * 100
+        + digit(255u8 != 255) * 1000
+        + digit(1.5 != 2.5) * 10000
+        + digit(nan != nan) * 100000
+}
+";
+
 pub const OBJECT_INITIALIZATION_WITH_TYPE_ARGUMENTS_AND_FIELD_ACCESS: &str = "
 class X<T> {c: T}
 
@@ -100,6 +170,521 @@ func fib(n: P_i64) -> P_i64 {
 }
 ";
 
+pub const FUNCTION_CALL_UNIT: &str = "
+func f() -> P_unit {
+    let x: P_i64 = 1;
+}
+
+func test() -> P_i64 {
+    let u: P_unit = f();
+    3
+}
+";
+
+pub const ASSIGNMENT: &str = "
+class Counter {
+    count: P_i64,
+}
+
+func increment(c: Counter) -> P_unit {
+    c.count = c.count + 1;
+}
+
+func test() -> P_i64 {
+    let x = 1;
+    x = x + 10;
+    const c = Counter { count: x };
+    increment(c);
+    increment(c);
+    c.count * 100 + x
+}
+";
+
+pub const WHILE_LOOPS: &str = "
+func test() -> P_i64 {
+    let total = 0;
+    let i = 0;
+    while (true) {
+        i = i + 1;
+        if (i > 5) {
+            break;
+        };
+        if (i == 2) {
+            continue;
+        };
+        let j = 0;
+        while (j < 10) {
+            j = j + 1;
+            if (j > i) {
+                break;
+            };
+            total = total + 1;
+        }
+    }
+    total * 100 + i
+}
+";
+
+pub const FOR_LOOPS: &str = "
+func test() -> P_i64 {
+    let total = 0;
+    for (i in 0..5) {
+        total = total + i;
+    }
+    for (i in 1..=10 step 3) {
+        if (i == 7) {
+            continue;
+        };
+        total = total + i * 100;
+    }
+    for (i in 0..100) {
+        if (i == 3) {
+            break;
+        };
+        total = total + 1000;
+    }
+    total
+}
+";
+
+pub const FOR_LOOP_SCOPES: &str = "
+func test() -> P_i64 {
+    let i = true;
+    let total = 0;
+    for (i in 0..5) {
+        let j = i * 10;
+        total = total + j;
+    }
+    for (i in 0..3) {
+        for (i in 0..2) {
+            total = total + 1;
+        }
+        total = total + i * 1000;
+    }
+    if (i) { total } else { -1 }
+}
+";
+
+pub const BLOCK_SCOPES: &str = "
+func test() -> P_i64 {
+    let x = 1;
+    let total = 0;
+    let i = 0;
+    while (i < 3) {
+        let x = 10;
+        total = total + x;
+        i = i + 1;
+    }
+    if (true) {
+        let x = 100;
+        total = total + x;
+    } else {
+        let x = 1000;
+        total = total + x;
+    };
+    total + x
+}
+";
+
+pub const FOR_LOOPS_AT_TYPE_LIMITS: &str = "
+func test() -> P_i64 {
+    let total = 0;
+    for (i in 0u8..=255) {
+        total = total + 1;
+    }
+    for (i in 0u8..255 step 100) {
+        total = total + 1000;
+    }
+    for (i in 9223372036854775800..=9223372036854775807 step 5) {
+        total = total + 100000;
+    }
+    total
+}
+";
+
+pub const SHORT_CIRCUIT_EVALUATION: &str = "
+class Counter {
+    count: P_i64,
+}
+
+func touch(c: Counter, result: P_bool) -> P_bool {
+    c.count = c.count + 1;
+    result
+}
+
+func test() -> P_i64 {
+    const c = Counter { count: 0 };
+    let score = 0;
+    if (false && touch(c, true)) {
+        score = score + 1;
+    };
+    if (true || touch(c, true)) {
+        score = score + 10;
+    };
+    if (true && touch(c, true)) {
+        score = score + 100;
+    };
+    if (false || touch(c, false)) {
+        score = score + 1000;
+    };
+    if (1 < 2 && 2 < 3 || false && touch(c, true)) {
+        score = score + 10000;
+    };
+    c.count * 100000 + score
+}
+";
+
+pub const BITWISE_OPERATORS: &str = "
+func test() -> P_i64 {
+    let a = 1 << 65;
+    let b = -16 >> 2;
+    let c = ~5;
+    let d = 12 & 10 | 6 ^ 3;
+    let n = 64 + 3;
+    let e = 1 << n;
+    a + b * 10 + c * 100 + d * 1000 + e * 100000
+}
+";
+
+pub const EXPONENTS: &str = "
+func test() -> P_i64 {
+    let a = 2 ** 10;
+    let b = 2 ** 3 ** 2;
+    let c = -3 ** 2;
+    let d = (-3) ** 3;
+    let e = 7 ** 0;
+    let f = 2 * 3 ** 2;
+    a + b + c * 10 + d * 100 + e * 10000 + f * 100000
+}
+";
+
+pub const FLOAT_LITERALS: &str = "
+func test() -> P_i64 {
+    1.5 + .25 * 2e3
+}
+";
+
+pub const FLOAT_ARITHMETIC: &str = "
+func half(x: P_f64) -> P_f64 {
+    x / 2.0
+}
+
+func test() -> P_i64 {
+    let a = 1.5 + 2.25;
+    let b = a * 4.0 - 0.5;
+    let c = half(-b);
+    let d = 7.5 % 2.0;
+    let e = 2.5e2 + 1E-1;
+    f64ToI64((a + b + c + d) * 100.0) + f64ToI64(e * 10.0) * 10000
+}
+";
+
+pub const FLOAT_COMPARISONS: &str = "
+func digit(condition: P_bool) -> P_i64 {
+    if (condition) {
+        1
+    } else {
+        0
+    }
+}
+
+func test() -> P_i64 {
+    let nan = 0.0 / 0.0;
+    digit(0.1 + 0.2 > 0.3)
+        + digit(1.5 <= 1.5) * 10
+        + digit(-0.0 == 0.0) * 100
+        + digit(nan == nan) * 1000
+        + digit(nan < 1.0 || nan >= 1.0) * 10000
+        + digit(2.0 < 10.0) * 100000
+}
+";
+
+pub const FLOAT_CONVERSIONS: &str = "
+func test() -> P_i64 {
+    let half = i64ToF64(7) / 2.0;
+    let truncated = f64ToI64(half) + f64ToI64(-3.9) * 10;
+    let saturated = f64ToI64(1e300) == 9223372036854775807 && f64ToI64(-1e300) < -9223372036854775806;
+    let nan = f64ToI64(0.0 / 0.0);
+    if (saturated) {
+        truncated + nan
+    } else {
+        -1
+    }
+}
+";
+
+pub const SHADOWED_BUILTIN_FUNCTION: &str = "
+func i64ToF64(n: P_i64) -> P_f64 {
+    0.5
+}
+
+func test() -> P_i64 {
+    f64ToI64(i64ToF64(7) * 10.0)
+}
+";
+
+pub const CAST_EXPRESSIONS: &str = "
+func test() -> P_i64 {
+    -a as P_u8 * 2u8
+}
+";
+
+pub const SIZED_INTEGER_ARITHMETIC: &str = "
+class Pixel {
+    r: P_u8,
+    g: P_u8,
+    b: P_u8,
+}
+
+func brightness(p: Pixel) -> P_u32 {
+    (p.r as P_u32 + p.g as P_u32 + p.b as P_u32) / 3
+}
+
+func test() -> P_i64 {
+    let a: P_u8 = 200;
+    let b = a + 100;
+    let c: P_i8 = -128;
+    let d = c - 1;
+    let e: P_i16 = 300 * 200;
+    let f: P_i32 = 1 << 31;
+    let g = brightness(Pixel {r: 255, g: 128, b: 2});
+    b as P_i64 + d as P_i64 * 1000 + g as P_i64 * 1000000 + (e as P_i64 + f as P_i64) * 1000000000
+}
+";
+
+pub const UNSIGNED_INTEGERS: &str = "
+func digit(condition: P_bool) -> P_i64 {
+    if (condition) {
+        1
+    } else {
+        0
+    }
+}
+
+func test() -> P_i64 {
+    let big = 4000000000u32;
+    let zero: P_u64 = 0;
+    let max = zero - 1;
+    let count = 0;
+    for (i in 250u8..=254) {
+        count = count + 1;
+    }
+    digit(big / 3 == 1333333333)
+        + digit(big % 7 == 3) * 10
+        + digit(255u8 >> 4 == 15) * 100
+        + digit(max / 2 == 9223372036854775807) * 1000
+        + digit(3u32 ** 20 == 3486784401) * 10000
+        + count * 100000
+        + digit(max > 1 && big >= 3000000000) * 1000000
+}
+";
+
+pub const INTEGER_CASTS: &str = "
+func test() -> P_i64 {
+    let a = 300;
+    let b = a as P_u8;
+    let c = -1 as P_u8;
+    let d = 200u8 as P_i8;
+    let e = -56i8 as P_u32;
+    let f = 4294967295u32 as P_i64;
+    b as P_i64 + c as P_i64 * 1000 + d as P_i64 * 1000000 + e as P_i64 + f
+}
+";
+
+pub const CONSTANT_INTEGER_WRAPPING: &str = "
+func test() -> P_i64 {
+    let a = 127i8 + 1i8;
+    let b: P_u8 = 255 + 1;
+    let c = 300 as P_u8;
+    a as P_i64 * 1000 + b as P_i64 * 100 + c as P_i64
+}
+";
+
+pub const INTEGER_LITERAL_BASES: &str = "
+func test() -> P_i64 {
+    let a = 0xFF + 0b1010 + 0o17 + 1_000;
+    let b = -9223372036854775808;
+    let c = 18446744073709551615u64;
+    let d = -128i8 as P_i64;
+    let e = 0xFFFF_FFFFu32 as P_i64;
+    let f = -0x8000_0000i32 as P_i64;
+    if (b < 0 && b + 9223372036854775807 == -1 && c > 0 && c as P_i64 == -1) {
+        a + d * 10000 + e + f
+    } else {
+        0
+    }
+}
+";
+
+pub const RETURN_STATEMENTS: &str = "
+func sign(n: P_i64) -> P_i64 {
+    let magnitude = if (n < 0) {
+        return -1;
+    } else {
+        if (n == 0) {
+            return 0;
+        } else {
+            n
+        }
+    };
+    magnitude / n
+}
+
+func nothing() -> P_unit {
+    return;
+}
+
+func test() -> P_i64 {
+    let u = nothing();
+    return sign(-5) * 100 + sign(0) * 10 + sign(7);
+}
+";
+
+pub const DIVERGING_IF_EXPRESSIONS: &str = "
+func sign(n: P_i64) -> P_i64 {
+    if (n < 0) {
+        return -1;
+    } else {
+        if (n == 0) {
+            return 0;
+        } else {
+            return 1;
+        }
+    }
+}
+
+func first_digit(n: P_i64) -> P_i64 {
+    if (n < 10) {
+        return n;
+    } else {
+        return first_digit(n / 10);
+    };
+}
+
+func pick(a: P_bool) -> P_i64 {
+    let x = if (a) {
+        5
+    } else {
+        if (a) {
+            return 6;
+        } else {
+            return 7;
+        }
+    };
+    x * 10
+}
+
+func test() -> P_i64 {
+    sign(-5) + sign(0) * 10 + sign(7) * 100 + first_digit(4321) * 1000 + pick(true) * 10000
+        + pick(false) * 1000000
+}
+";
+
+pub const DIVERGING_OPERANDS: &str = "
+class Pair {
+    first: P_i64,
+    second: P_i64,
+}
+
+func add(a: P_i64, b: P_i64) -> P_i64 {
+    a + b
+}
+
+func argument(a: P_bool) -> P_i64 {
+    add(1, if (a) { return 2; } else { return 3; })
+}
+
+func field(a: P_bool) -> P_i64 {
+    const pair = Pair { first: 1, second: if (a) { return 4; } else { return 5; } };
+    pair.first
+}
+
+func operand(a: P_bool) -> P_i64 {
+    1 + if (a) { return 6; } else { return 7; }
+}
+
+func left_operand(a: P_bool) -> P_i64 {
+    (if (a) { return 1; } else { return 2; }) * 3
+}
+
+func assignment(a: P_bool) -> P_i64 {
+    let x = 0;
+    x = if (a) { return 8; } else { return 9; };
+    x
+}
+
+func logical(a: P_bool) -> P_i64 {
+    if (a && if (a) { return 3; } else { return 4; }) { 0 } else { 0 }
+}
+
+func condition(a: P_bool) -> P_i64 {
+    if (if (a) { return 4; } else { return 5; }) { 0 } else { 0 }
+}
+
+func test() -> P_i64 {
+    argument(true) + argument(false) * 10 + field(true) * 100 + field(false) * 1000
+        + operand(true) * 10000 + operand(false) * 100000 + left_operand(true) * 1000000
+        + assignment(false) * 10000000 + logical(true) * 100000000
+        + condition(false) * 1000000000
+}
+";
+
+pub const LET_TYPE_INFERENCE: &str = "
+class Y<T> {
+    a: T,
+}
+
+func test() -> P_i64 {
+    let x = 2;
+    let y = Y<P_i64> { a: x * 3 };
+    let b = y.a > 5;
+    if (b) { y.a } else { 0 }
+}
+";
+
+pub const GENERIC_FUNCTION_CALL: &str = "
+func identity<T>(x: T) -> T {
+    x
+}
+
+func test() -> P_bool {
+    let a: P_bool = identity<P_i64>(1) < 2;
+    identity(a)
+}
+";
+
+pub const GENERIC_FUNCTIONS: &str = "
+class Pair<T> {
+    first: T,
+    second: T,
+}
+
+func identity<T>(x: T) -> T {
+    x
+}
+
+func swap<T>(p: Pair<T>) -> Pair<T> {
+    Pair<T> {
+        first: p.second,
+        second: p.first,
+    }
+}
+
+func first<T>(p: Pair<T>) -> T {
+    identity<T>(p.first)
+}
+
+func test() -> P_i64 {
+    let b: P_bool = first(swap(Pair<P_bool> { first: false, second: true }));
+    let p: Pair<P_i64> = swap(Pair<P_i64> { first: 1, second: 2 });
+    if (identity(b)) {
+        first(p) + identity<P_i64>(10)
+    } else {
+        0
+    }
+}
+";
+
 pub const RAW_VEC: &str = "
 class RawVec {
     capacity: P_i64,
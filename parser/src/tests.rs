@@ -1,5 +1,6 @@
 use crate::*;
 
+#[allow(clippy::module_inception)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -21,6 +22,7 @@ mod tests {
     fn parse(source: &str) -> Node<UntypedNodeCommonFields> {
         let mut parser = Parser::new(source);
         let output = parser.parse_source_file();
+        assert!(parser.errors.is_empty());
         Node::SourceFile(Rc::new(output))
     }
 
@@ -108,6 +110,330 @@ mod tests {
         );
     }
 
+    #[test]
+    fn logical_expressions() {
+        let mut parser = Parser::new(
+            "func test(a: P_bool, b: P_bool) -> P_bool {
+    a || b && 1 < 2 == (a || b)
+}",
+        );
+        let source_file = parser.parse_source_file();
+        assert!(parser.errors.is_empty());
+        let final_expression = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.final_expression.clone().unwrap(),
+            _ => unreachable!(),
+        };
+
+        let variable_reference = |name: &str| {
+            Expression::VariableReference(Rc::new(
+                VariableReference::<UntypedNodeCommonFields>::new(Identifier::new(name.to_owned())),
+            ))
+        };
+        let integer_literal = |value: u64| {
+            Expression::IntegerLiteral(Rc::new(IntegerLiteral::<UntypedNodeCommonFields>::new(
+                value,
+            )))
+        };
+        let binary_expression = |left, operator, right| {
+            Expression::BinaryExpression(Rc::new(BinaryExpression::<UntypedNodeCommonFields>::new(
+                left, operator, right,
+            )))
+        };
+        // `&&` binds tighter than `||`, and comparisons bind tighter than both.
+        assert_eq!(
+            final_expression,
+            binary_expression(
+                variable_reference("a"),
+                InfixOperator::LogicalOr,
+                binary_expression(
+                    variable_reference("b"),
+                    InfixOperator::LogicalAnd,
+                    binary_expression(
+                        binary_expression(
+                            integer_literal(1),
+                            InfixOperator::LessThan,
+                            integer_literal(2)
+                        ),
+                        InfixOperator::DoubleEquals,
+                        binary_expression(
+                            variable_reference("a"),
+                            InfixOperator::LogicalOr,
+                            variable_reference("b")
+                        ),
+                    ),
+                ),
+            )
+        );
+    }
+
+    #[test]
+    fn not_equals_expressions() {
+        let mut parser = Parser::new(
+            "func test(a: P_bool) -> P_bool {
+    1 + 2 != 3 == a
+}",
+        );
+        let source_file = parser.parse_source_file();
+        assert!(parser.errors.is_empty());
+        let final_expression = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.final_expression.clone().unwrap(),
+            _ => unreachable!(),
+        };
+
+        let integer_literal = |value: u64| {
+            Expression::IntegerLiteral(Rc::new(IntegerLiteral::<UntypedNodeCommonFields>::new(
+                value,
+            )))
+        };
+        let binary_expression = |left, operator, right| {
+            Expression::BinaryExpression(Rc::new(BinaryExpression::<UntypedNodeCommonFields>::new(
+                left, operator, right,
+            )))
+        };
+        // `!=` has the same precedence as `==`, so the two group from the left.
+        assert_eq!(
+            final_expression,
+            binary_expression(
+                binary_expression(
+                    binary_expression(integer_literal(1), InfixOperator::Plus, integer_literal(2)),
+                    InfixOperator::NotEquals,
+                    integer_literal(3)
+                ),
+                InfixOperator::DoubleEquals,
+                Expression::VariableReference(Rc::new(
+                    VariableReference::<UntypedNodeCommonFields>::new(Identifier::new(
+                        "a".to_owned()
+                    ))
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn bitwise_expressions() {
+        let mut parser = Parser::new(
+            "func test(a: P_i64) -> P_i64 {
+    ~a & 1 | 2 ^ 3 << 4 + -5
+}",
+        );
+        let source_file = parser.parse_source_file();
+        assert!(parser.errors.is_empty());
+        let final_expression = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.final_expression.clone().unwrap(),
+            _ => unreachable!(),
+        };
+
+        let integer_literal = |value: u64| {
+            Expression::IntegerLiteral(Rc::new(IntegerLiteral::<UntypedNodeCommonFields>::new(
+                value,
+            )))
+        };
+        let prefix_expression = |operator, operand| {
+            Expression::PrefixExpression(Rc::new(PrefixExpression::<UntypedNodeCommonFields>::new(
+                operator, operand,
+            )))
+        };
+        let binary_expression = |left, operator, right| {
+            Expression::BinaryExpression(Rc::new(BinaryExpression::<UntypedNodeCommonFields>::new(
+                left, operator, right,
+            )))
+        };
+        // Prefix operators bind tightest, and the infix operators follow C's precedence.
+        assert_eq!(
+            final_expression,
+            binary_expression(
+                binary_expression(
+                    prefix_expression(
+                        PrefixOperator::BitNot,
+                        Expression::VariableReference(Rc::new(VariableReference::<
+                            UntypedNodeCommonFields,
+                        >::new(
+                            Identifier::new("a".to_owned())
+                        )))
+                    ),
+                    InfixOperator::BitAnd,
+                    integer_literal(1)
+                ),
+                InfixOperator::BitOr,
+                binary_expression(
+                    integer_literal(2),
+                    InfixOperator::BitXor,
+                    binary_expression(
+                        integer_literal(3),
+                        InfixOperator::BitShiftLeft,
+                        binary_expression(
+                            integer_literal(4),
+                            InfixOperator::Plus,
+                            prefix_expression(PrefixOperator::Minus, integer_literal(5))
+                        ),
+                    ),
+                ),
+            )
+        );
+    }
+
+    #[test]
+    fn exponent_expressions() {
+        let mut parser = Parser::new(
+            "func test(a: P_i64) -> P_i64 {
+    -a ** 2 ** 3 * 4
+}",
+        );
+        let source_file = parser.parse_source_file();
+        assert!(parser.errors.is_empty());
+        let final_expression = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.final_expression.clone().unwrap(),
+            _ => unreachable!(),
+        };
+
+        let integer_literal = |value: u64| {
+            Expression::IntegerLiteral(Rc::new(IntegerLiteral::<UntypedNodeCommonFields>::new(
+                value,
+            )))
+        };
+        let binary_expression = |left, operator, right| {
+            Expression::BinaryExpression(Rc::new(BinaryExpression::<UntypedNodeCommonFields>::new(
+                left, operator, right,
+            )))
+        };
+        // `**` is right-associative, and binds tighter than prefix operators and `*`.
+        assert_eq!(
+            final_expression,
+            binary_expression(
+                Expression::PrefixExpression(Rc::new(
+                    PrefixExpression::<UntypedNodeCommonFields>::new(
+                        PrefixOperator::Minus,
+                        binary_expression(
+                            Expression::VariableReference(Rc::new(VariableReference::<
+                                UntypedNodeCommonFields,
+                            >::new(
+                                Identifier::new("a".to_owned())
+                            ))),
+                            InfixOperator::Exponent,
+                            binary_expression(
+                                integer_literal(2),
+                                InfixOperator::Exponent,
+                                integer_literal(3)
+                            ),
+                        ),
+                    )
+                )),
+                InfixOperator::Times,
+                integer_literal(4),
+            )
+        );
+    }
+
+    #[test]
+    fn integer_literal_bases() {
+        let mut parser = Parser::new(
+            "func test() -> P_i64 {
+    0xFF + 0b1010 + 0O17 + 1_000_000 + 0x_dead_BEEFu32 + 18446744073709551615u64
+}",
+        );
+        let source_file = parser.parse_source_file();
+        assert!(parser.errors.is_empty());
+        let mut expression = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.final_expression.clone().unwrap(),
+            _ => unreachable!(),
+        };
+
+        // `+` is left-associative, so the literals are the right operands, from last to first.
+        let mut literals = vec![];
+        while let Expression::BinaryExpression(b) = expression {
+            literals.push(b.right.clone());
+            expression = b.left.clone();
+        }
+        literals.push(expression);
+        literals.reverse();
+
+        let integer_literal = |value: u64, suffix: Option<Type>| {
+            Expression::IntegerLiteral(Rc::new(
+                IntegerLiteral::<UntypedNodeCommonFields>::new(value).with_suffix(suffix),
+            ))
+        };
+        assert_eq!(
+            literals,
+            vec![
+                integer_literal(255, None),
+                integer_literal(10, None),
+                integer_literal(15, None),
+                integer_literal(1_000_000, None),
+                integer_literal(0xDEAD_BEEF, Some(Type::U32Type)),
+                integer_literal(u64::MAX, Some(Type::U64Type)),
+            ]
+        );
+    }
+
+    #[test]
+    fn float_literals() {
+        let sf_node = parse(test_inputs::FLOAT_LITERALS);
+        assert_eq!(
+            sf_node,
+            define_test_body(Rc::new(Block::new_with_final_expression(
+                vec![],
+                Expression::BinaryExpression(Rc::new(
+                    BinaryExpression::<UntypedNodeCommonFields>::new(
+                        Expression::FloatLiteral(Rc::new(
+                            FloatLiteral::<UntypedNodeCommonFields>::new(1.5)
+                        )),
+                        InfixOperator::Plus,
+                        Expression::BinaryExpression(Rc::new(BinaryExpression::<
+                            UntypedNodeCommonFields,
+                        >::new(
+                            Expression::FloatLiteral(Rc::new(FloatLiteral::<
+                                UntypedNodeCommonFields,
+                            >::new(
+                                0.25
+                            ))),
+                            InfixOperator::Times,
+                            Expression::FloatLiteral(Rc::new(FloatLiteral::<
+                                UntypedNodeCommonFields,
+                            >::new(
+                                2000.0
+                            ))),
+                        ))),
+                    )
+                ))
+            )))
+        );
+    }
+
+    #[test]
+    fn cast_expressions() {
+        let sf_node = parse(test_inputs::CAST_EXPRESSIONS);
+        assert_eq!(
+            sf_node,
+            define_test_body(Rc::new(Block::new_with_final_expression(
+                vec![],
+                Expression::BinaryExpression(Rc::new(
+                    BinaryExpression::<UntypedNodeCommonFields>::new(
+                        Expression::CastExpression(Rc::new(CastExpression::<
+                            UntypedNodeCommonFields,
+                        >::new(
+                            Expression::PrefixExpression(Rc::new(PrefixExpression::<
+                                UntypedNodeCommonFields,
+                            >::new(
+                                PrefixOperator::Minus,
+                                Expression::VariableReference(Rc::new(VariableReference::<
+                                    UntypedNodeCommonFields,
+                                >::new(
+                                    Identifier::new("a".to_owned())
+                                )))
+                            ))),
+                            Type::U8Type
+                        ))),
+                        InfixOperator::Times,
+                        Expression::IntegerLiteral(Rc::new(
+                            IntegerLiteral::<UntypedNodeCommonFields>::new(2)
+                                .with_suffix(Some(Type::U8Type))
+                        )),
+                    )
+                ))
+            )))
+        );
+    }
+
     #[test]
     fn if_expressions() {
         let sf_node = parse(test_inputs::IF_EXPRESSION);
@@ -531,6 +857,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn generic_function_call() {
+        let sf_node = parse(test_inputs::GENERIC_FUNCTION_CALL);
+        let t = Type::TypeReference(Rc::new(TypeReference::new(
+            Identifier::new("T".to_owned()),
+            vec![],
+        )));
+        assert_eq!(
+            sf_node,
+            Node::SourceFile(Rc::new(SourceFile::new(
+                vec![
+                    Declaration::FunctionDeclaration(Rc::new(
+                        FunctionDeclaration::<UntypedNodeCommonFields>::new(
+                            Identifier::new("identity".to_owned()),
+                            t.clone(),
+                            vec![Rc::new(Parameter::<UntypedNodeCommonFields>::new(Rc::new(
+                                VariableDeclarator::<UntypedNodeCommonFields>::new(
+                                    Identifier::new("x".to_owned()),
+                                    t,
+                                )
+                            )))],
+                            Rc::new(Block::new_with_final_expression(
+                                vec![],
+                                Expression::VariableReference(Rc::new(VariableReference::<
+                                    UntypedNodeCommonFields,
+                                >::new(
+                                    Identifier::new("x".to_owned())
+                                ))),
+                            ))
+                        )
+                        .with_type_parameters(vec![Identifier::new("T".to_owned())])
+                    )),
+                    Declaration::FunctionDeclaration(Rc::new(FunctionDeclaration::<
+                        UntypedNodeCommonFields,
+                    >::new(
+                        Identifier::new("test".to_owned()),
+                        Type::BoolType,
+                        vec![],
+                        Rc::new(Block::new_with_final_expression(
+                            vec![Statement::Declaration(Declaration::VariableDeclaration(
+                                Rc::new(VariableDeclaration::<UntypedNodeCommonFields>::new(
+                                    Rc::new(VariableDeclarator::<UntypedNodeCommonFields>::new(
+                                        Identifier::new("a".to_owned()),
+                                        Type::BoolType,
+                                    )),
+                                    // `<` only starts type arguments when they are followed by
+                                    // a call or an object literal.
+                                    Expression::BinaryExpression(Rc::new(BinaryExpression::<
+                                        UntypedNodeCommonFields,
+                                    >::new(
+                                        Expression::FunctionCall(Rc::new(
+                                            FunctionCall::<UntypedNodeCommonFields>::new(
+                                                Identifier::new("identity".to_owned()),
+                                                vec![Expression::IntegerLiteral(Rc::new(
+                                                    IntegerLiteral::<UntypedNodeCommonFields>::new(
+                                                        1
+                                                    )
+                                                ))],
+                                            )
+                                            .with_type_arguments(vec![Type::I64Type])
+                                        )),
+                                        InfixOperator::LessThan,
+                                        Expression::IntegerLiteral(Rc::new(IntegerLiteral::<
+                                            UntypedNodeCommonFields,
+                                        >::new(
+                                            2
+                                        ))),
+                                    ))),
+                                ))
+                            ))],
+                            Expression::FunctionCall(Rc::new(FunctionCall::<
+                                UntypedNodeCommonFields,
+                            >::new(
+                                Identifier::new("identity".to_owned()),
+                                vec![Expression::VariableReference(Rc::new(VariableReference::<
+                                    UntypedNodeCommonFields,
+                                >::new(
+                                    Identifier::new("a".to_owned())
+                                )))],
+                            )))
+                        ))
+                    ))),
+                ],
+                vec![]
+            )))
+        );
+    }
+
     #[test]
     fn function_call_update_state() {
         let sf_node = parse(test_inputs::FUNCTION_CALL_UPDATE_STATE);
@@ -657,4 +1071,447 @@ mod tests {
             )))
         );
     }
+
+    fn source_at(source: &str, span: Span) -> &str {
+        &source[span.start..span.end]
+    }
+
+    #[test]
+    fn expression_spans() {
+        let source = test_inputs::ARITHMETIC_OPERATOR_PRECEDENCE;
+        let mut source_map = SourceMap::new();
+        let file_id = source_map.add_file("test.soap".to_owned(), source.to_owned());
+        let mut parser = Parser::new_with_file_id(source, file_id);
+        let source_file = parser.parse_source_file();
+
+        let function_declaration = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd,
+            _ => unreachable!(),
+        };
+        assert_eq!(function_declaration.span().file_id, file_id);
+        assert_eq!(
+            source_at(source, function_declaration.span()),
+            "func test() -> P_i64 {\n    2 + 2 * 2\n}"
+        );
+        assert_eq!(
+            source_at(source, function_declaration.identifier.span),
+            "test"
+        );
+
+        let final_expression = function_declaration.body.final_expression.as_ref().unwrap();
+        assert_eq!(source_at(source, final_expression.span()), "2 + 2 * 2");
+        assert_eq!(
+            source_map.start_line_column(&final_expression.span()),
+            LineColumn { line: 3, column: 5 }
+        );
+        assert_eq!(
+            source_map.end_line_column(&final_expression.span()),
+            LineColumn {
+                line: 3,
+                column: 14
+            }
+        );
+        match final_expression {
+            Expression::BinaryExpression(e) => {
+                assert_eq!(source_at(source, e.left.span()), "2");
+                assert_eq!(source_at(source, e.right.span()), "2 * 2");
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn declaration_spans() {
+        let source = test_inputs::OBJECT_INITIALIZATION_WITH_TYPE_ARGUMENTS_AND_FIELD_ACCESS;
+        let mut parser = Parser::new(source);
+        let source_file = parser.parse_source_file();
+
+        assert_eq!(
+            source_at(source, source_file.type_declarations[0].span),
+            "class X<T> {c: T}"
+        );
+
+        let function_declaration = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd,
+            _ => unreachable!(),
+        };
+        let variable_declaration = match &function_declaration.body.statements[0] {
+            Statement::Declaration(Declaration::VariableDeclaration(vd)) => vd,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            source_at(source, variable_declaration.span()),
+            "let y: Y<P_i64> = Y<P_i64> {a: true, b: X<P_i64> {c: 123}};"
+        );
+        assert_eq!(
+            source_at(source, variable_declaration.variable_declarator.span()),
+            "y: Y<P_i64>"
+        );
+        assert_eq!(
+            source_at(source, variable_declaration.initial_value.span()),
+            "Y<P_i64> {a: true, b: X<P_i64> {c: 123}}"
+        );
+        assert_eq!(
+            source_at(
+                source,
+                function_declaration
+                    .body
+                    .final_expression
+                    .as_ref()
+                    .unwrap()
+                    .span()
+            ),
+            "y.b.c"
+        );
+    }
+
+    #[test]
+    fn assignments() {
+        let sf_node = parse(
+            "func test() -> P_i64 {
+    const a = 1;
+    b = c.d = a;
+    b
+}",
+        );
+        let variable_reference = |name: &str| {
+            Expression::VariableReference(Rc::new(
+                VariableReference::<UntypedNodeCommonFields>::new(Identifier::new(name.to_owned())),
+            ))
+        };
+        assert_eq!(
+            sf_node,
+            Node::SourceFile(Rc::new(SourceFile::new(
+                vec![Declaration::FunctionDeclaration(Rc::new(
+                    FunctionDeclaration::<UntypedNodeCommonFields>::new(
+                        Identifier::new("test".to_owned()),
+                        Type::I64Type,
+                        vec![],
+                        Rc::new(Block::new_with_final_expression(
+                            vec![
+                                Statement::Declaration(
+                                    Declaration::VariableDeclaration(
+                                        Rc::new(
+                                            VariableDeclaration::<UntypedNodeCommonFields>::new(
+                                                Rc::new(VariableDeclarator::<
+                                                    UntypedNodeCommonFields,
+                                                >::new_without_type_annotation(
+                                                    Identifier::new("a".to_owned()),
+                                                )),
+                                                Expression::IntegerLiteral(Rc::new(
+                                                    IntegerLiteral::<UntypedNodeCommonFields>::new(
+                                                        1
+                                                    )
+                                                )),
+                                            )
+                                            .with_kind(VariableDeclarationKind::Const)
+                                        )
+                                    )
+                                ),
+                                // Assignment is right-associative.
+                                Statement::ExpressionStatement(Rc::new(ExpressionStatement::new(
+                                    Expression::BinaryExpression(Rc::new(BinaryExpression::<
+                                        UntypedNodeCommonFields,
+                                    >::new(
+                                        variable_reference("b"),
+                                        InfixOperator::Equals,
+                                        Expression::BinaryExpression(Rc::new(BinaryExpression::<
+                                            UntypedNodeCommonFields,
+                                        >::new(
+                                            Expression::FieldAccess(Rc::new(FieldAccess::<
+                                                UntypedNodeCommonFields,
+                                            >::new(
+                                                variable_reference("c"),
+                                                "d".to_owned(),
+                                            ))),
+                                            InfixOperator::Equals,
+                                            variable_reference("a"),
+                                        ))),
+                                    ))),
+                                ))),
+                            ],
+                            variable_reference("b"),
+                        )),
+                    )
+                ))],
+                vec![]
+            )))
+        );
+    }
+
+    #[test]
+    fn return_statements() {
+        let source = test_inputs::RETURN_STATEMENTS;
+        let mut parser = Parser::new(source);
+        let source_file = parser.parse_source_file();
+
+        let return_statements = source_file
+            .declarations
+            .iter()
+            .skip(1)
+            .map(|d| match d {
+                Declaration::FunctionDeclaration(fd) => match fd.body.statements.last() {
+                    Some(Statement::ReturnStatement(s)) => s.clone(),
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(source_at(source, return_statements[0].span), "return;");
+        // `return;` returns unit.
+        assert_eq!(
+            return_statements[0].expression,
+            Expression::UnitLiteral(Rc::new(UnitLiteral::<UntypedNodeCommonFields>::new()))
+        );
+        assert_eq!(
+            source_at(source, return_statements[1].span),
+            "return sign(-5) * 100 + sign(0) * 10 + sign(7);"
+        );
+        assert_eq!(
+            source_at(source, return_statements[1].expression.span()),
+            "sign(-5) * 100 + sign(0) * 10 + sign(7)"
+        );
+    }
+
+    #[test]
+    fn while_loops() {
+        let source = test_inputs::WHILE_LOOPS;
+        let mut parser = Parser::new(source);
+        let source_file = parser.parse_source_file();
+
+        let body = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.clone(),
+            _ => unreachable!(),
+        };
+        let outer_loop = match &body.statements[2] {
+            Statement::WhileStatement(s) => s.clone(),
+            _ => unreachable!(),
+        };
+        assert!(source_at(source, outer_loop.span).starts_with("while (true) {"));
+        assert!(source_at(source, outer_loop.span).ends_with("}\n    }"));
+        assert_eq!(
+            outer_loop.condition,
+            Expression::BooleanLiteral(Rc::new(BooleanLiteral::<UntypedNodeCommonFields>::new(
+                true
+            )))
+        );
+
+        let break_statement = match &outer_loop.body.statements[1] {
+            Statement::ExpressionStatement(s) => match &s.expression {
+                Expression::IfExpression(e) => e.then_block.statements[0].clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            break_statement,
+            Statement::BreakStatement(Rc::new(BreakStatement::new()))
+        );
+        assert_eq!(source_at(source, break_statement.span()), "break;");
+
+        // A loop can be the last item in a block, without a trailing semicolon.
+        let inner_loop = match outer_loop.body.statements.last() {
+            Some(Statement::WhileStatement(s)) => s.clone(),
+            _ => unreachable!(),
+        };
+        assert!(outer_loop.body.final_expression.is_none());
+        assert_eq!(source_at(source, inner_loop.condition.span()), "j < 10");
+    }
+
+    #[test]
+    fn for_loops() {
+        let source = test_inputs::FOR_LOOPS;
+        let mut parser = Parser::new(source);
+        let source_file = parser.parse_source_file();
+
+        let body = match &source_file.declarations[0] {
+            Declaration::FunctionDeclaration(fd) => fd.body.clone(),
+            _ => unreachable!(),
+        };
+        let for_statements = body
+            .statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::ForStatement(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let integer_literal = |value: u64| {
+            Expression::IntegerLiteral(Rc::new(IntegerLiteral::<UntypedNodeCommonFields>::new(
+                value,
+            )))
+        };
+
+        assert_eq!(for_statements.len(), 3);
+        assert_eq!(for_statements[0].kind, RangeKind::Exclusive);
+        assert_eq!(for_statements[0].step, None);
+        assert_eq!(
+            source_at(source, for_statements[0].variable_declarator.span()),
+            "i"
+        );
+        assert_eq!(for_statements[1].kind, RangeKind::Inclusive);
+        assert_eq!(for_statements[1].start, integer_literal(1));
+        assert_eq!(for_statements[1].end, integer_literal(10));
+        assert_eq!(for_statements[1].step, Some(integer_literal(3)));
+        assert!(source_at(source, for_statements[1].span).starts_with("for (i in 1..=10 step 3) {"));
+    }
+
+    fn parse_errors(source: &str) -> Vec<ParseError> {
+        let mut parser = Parser::new(source);
+        parser.parse().unwrap_err()
+    }
+
+    #[test]
+    fn missing_semicolon() {
+        let source = "func test() -> P_i64 {\n    let a: P_i64 = 1\n    a\n}";
+        let errors = parse_errors(source);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(errors[0].expected, vec![Token::Semicolon]);
+        assert_eq!(errors[0].found, Some(Token::Identifier));
+        assert_eq!(source_at(source, errors[0].span), "a");
+        assert_eq!(errors[0].message(), "expected `;`, found identifier");
+    }
+
+    #[test]
+    fn missing_type_annotation() {
+        let source = "func f(a) -> P_i64 {\n    a\n}";
+        let errors = parse_errors(source);
+        assert_eq!(errors[0].kind, ParseErrorKind::MissingTypeAnnotation);
+        assert_eq!(source_at(source, errors[0].span), ")");
+    }
+
+    #[test]
+    fn unterminated_block() {
+        let source = "func test() -> P_i64 {\n    1 + 2\n";
+        let errors = parse_errors(source);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnterminatedBlock);
+        assert_eq!(errors[0].found, None);
+        assert_eq!(source_at(source, errors[0].span), "{");
+    }
+
+    #[test]
+    fn unexpected_top_level_token() {
+        let source = "let a: P_i64 = 1;";
+        let errors = parse_errors(source);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(
+            errors[0].expected,
+            vec![Token::ClassKeyword, Token::FuncKeyword]
+        );
+        assert_eq!(source_at(source, errors[0].span), "let");
+    }
+
+    #[test]
+    fn integer_literal_out_of_range() {
+        let source = "func test() -> P_i64 {\n    99999999999999999999\n}";
+        let errors = parse_errors(source);
+        assert_eq!(errors[0].kind, ParseErrorKind::IntegerLiteralOutOfRange);
+        assert_eq!(source_at(source, errors[0].span), "99999999999999999999");
+
+        let source = "func test() -> P_i64 {\n    0x1_0000_0000_0000_0000\n}";
+        let errors = parse_errors(source);
+        assert_eq!(errors[0].kind, ParseErrorKind::IntegerLiteralOutOfRange);
+        assert_eq!(source_at(source, errors[0].span), "0x1_0000_0000_0000_0000");
+    }
+
+    #[test]
+    fn float_literal_out_of_range() {
+        let source = "func test() -> P_f64 {\n    1e999\n}";
+        let errors = parse_errors(source);
+        assert_eq!(errors[0].kind, ParseErrorKind::FloatLiteralOutOfRange);
+        assert_eq!(source_at(source, errors[0].span), "1e999");
+    }
+
+    #[test]
+    fn reports_every_syntax_error() {
+        let source = "
+class X {a P_i64}
+
+func f() -> P_i64 {
+    let a: P_i64 = 1 +;
+    let b: P_i64 = (2;
+    a
+}
+
+func g() -> P_i64 {
+    3
+}
+
+func h( -> P_i64 {
+    4
+}
+";
+        let mut parser = Parser::new(source);
+        let (node, errors) = parser.parse_with_errors();
+
+        assert_eq!(
+            errors.iter().map(|e| e.message()).collect::<Vec<String>>(),
+            vec![
+                "expected `:`, found `P_i64`",
+                "expected expression, found `;`",
+                "expected `)`, found `;`",
+                "expected identifier, found `->`",
+            ]
+        );
+        assert_eq!(source_at(source, errors[1].span), ";");
+
+        let source_file = match node {
+            Node::SourceFile(sf) => sf,
+            _ => unreachable!(),
+        };
+        assert!(source_file.type_declarations.is_empty());
+        assert_eq!(source_file.declarations.len(), 4);
+        match &source_file.declarations[0] {
+            Declaration::ErrorNode(e) => {
+                assert_eq!(source_at(source, e.span), "class X {a P_i64}")
+            }
+            _ => panic!("expected an error node"),
+        }
+
+        // The statements that could not be parsed are replaced by error nodes, and the rest of the
+        // function is kept.
+        let f = match &source_file.declarations[1] {
+            Declaration::FunctionDeclaration(fd) => fd,
+            _ => panic!("expected a function declaration"),
+        };
+        assert_eq!(f.body.statements.len(), 2);
+        match &f.body.statements[0] {
+            Statement::ErrorNode(e) => {
+                assert_eq!(source_at(source, e.span), "let a: P_i64 = 1 +;")
+            }
+            _ => panic!("expected an error node"),
+        }
+        assert!(f.body.final_expression.is_some());
+
+        assert!(matches!(
+            source_file.declarations[2],
+            Declaration::FunctionDeclaration(_)
+        ));
+        assert!(matches!(
+            source_file.declarations[3],
+            Declaration::ErrorNode(_)
+        ));
+    }
+
+    #[test]
+    fn recovers_after_unterminated_block() {
+        let source = "
+func f() -> P_i64 {
+    if (true) {
+        1
+    } else {
+        2
+
+func g() -> P_i64 {
+    3
+}
+";
+        let errors = parse_errors(source);
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|e| e.kind == ParseErrorKind::UnterminatedBlock));
+        assert_eq!(source_at(source, errors[0].span), "{");
+    }
 }